@@ -16,12 +16,3 @@ pub struct MovementTestScopeTag;
 impl Component for MovementTestScopeTag {
     type Storage = NullStorage<Self>;
 }
-
-/// Various sorts of tests that can be executed.
-#[derive(Copy, Clone, Debug)]
-pub enum MovementTest {
-    /// This test will prove that the player can ALWAYS make a jump across a 2-wide gap.
-    Jump2Wide,
-    /// This test will prove that the player can NEVER make a jump across a 4-wide gap.
-    Jump4Wide,
-}
@@ -0,0 +1,45 @@
+//! Helpers for the command line tools that operate on level files.
+
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+
+use dsf_core::levels::LevelFormat;
+
+/// The assets directory of the game.
+#[must_use]
+pub fn assets_dir() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("../assets/")
+}
+
+/// The directory containing the levels of the game.
+#[must_use]
+pub fn levels_dir() -> PathBuf {
+    assets_dir().join("world/levels/")
+}
+
+/// Expands the given paths to a sorted list of level files. Files are kept as they are,
+/// directories are replaced by every level file they contain, in any of the formats.
+/// Without any paths, all levels in `levels_dir()` are returned.
+pub fn collect_level_files(paths: Vec<PathBuf>) -> io::Result<Vec<PathBuf>> {
+    let paths = if paths.is_empty() {
+        vec![levels_dir()]
+    } else {
+        paths
+    };
+    let mut files = vec![];
+    for path in paths {
+        if path.is_dir() {
+            for entry in fs::read_dir(&path)? {
+                let file = entry?.path();
+                if LevelFormat::is_level_file(&file) {
+                    files.push(file);
+                }
+            }
+        } else {
+            files.push(path);
+        }
+    }
+    files.sort();
+    Ok(files)
+}
@@ -12,22 +12,26 @@
     clippy::wildcard_imports
 )]
 
-//! This crate is not really used right now. The plan is to be able to run automated tests
+//! This crate contains automated checks for levels. The plan is to be able to run automated tests
 //! with AIs playing the role of the player to prove that the player will or will not be able to
 //! make certain jumps, etc.
 //!
 //! Being able to guarantee that a player cannot make a certain jump is of course very important in
 //! a puzzle game, because these sorts of bugs could make puzzles trivially easy.
 //!
-//! This crate can also eventually be leveraged to create automated tests that prove that a certain
-//! puzzle is solvable.
-
-// TODO: Change this crate to a bin.
+//! The `solver` module runs without a window. Given a level, it either finds a sequence of inputs
+//! that completes the level or proves that the level cannot be completed.
+//!
+//! The `dsf_checks` binary makes these checks available on the command line, without a window.
 
 #[macro_use]
 extern crate log;
 
 pub mod components;
+pub mod level_files;
+pub mod reachability;
 pub mod resources;
+pub mod scenarios;
+pub mod solver;
 pub mod states;
 pub mod systems;
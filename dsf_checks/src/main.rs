@@ -0,0 +1,407 @@
+//! Inspects and converts levels without opening a window, for use in CI and pre-commit hooks.
+//!
+//! Usage: `cargo run -p dsf_checks -- COMMAND [ARGS]...`
+//!
+//! - `validate [FILE_OR_DIR]...` checks the levels against the rules of the tile definitions.
+//! - `solve [FILE_OR_DIR]...` runs the solver on the levels and prints a shortest solution.
+//! - `render FILE` draws the level in ASCII.
+//! - `thumbnail FILE PNG [TILE_SIZE]` draws the level with the sprites of the game into a PNG
+//!   image, with each tile taking up TILE_SIZE by TILE_SIZE pixels (16 by default).
+//! - `convert SOURCE TARGET` converts a level between the RON, binary, ASCII and Tiled formats.
+//!   The formats are picked by the extensions of the files.
+//! - `convert --to-ron|--to-binary|--to-ascii [FILE_OR_DIR]...` converts every level to the given
+//!   format. The converted level is written next to the original, with the extension of the
+//!   target format, and read back to check that nothing was lost. Files that already are in the
+//!   target format are skipped.
+//! - `upgrade [FILE_OR_DIR]...` rewrites RON and binary levels of older versions of the level
+//!   format in the current version, in place.
+//! - `stats [FILE_OR_DIR]...` prints how often each tile is used in the levels.
+//! - `diff BEFORE AFTER` lists the differences between two levels, regardless of their format.
+//! - `merge BASE OURS THEIRS [PATH]` merges the changes that two sides made to the base version of
+//!   a level, and writes the result to OURS. The format of the files is picked by the extension of
+//!   PATH if it is given, otherwise by that of OURS. This can be used as a git merge driver, see
+//!   `docs/LevelDesign.md`.
+//! - `jumps` prints for every gap width and ledge height whether the player can jump across.
+//!
+//! Commands that take files also accept directories, which are replaced by every level file they
+//! contain. Without any, they look at all levels in `assets/world/levels/`.
+//!
+//! Exits with 0 if everything is fine, 1 if a level failed the check (it is invalid, cannot be
+//! solved, cannot be converted or upgraded, the two levels differ or the merge has conflicts) and
+//! 2 if the command could not be run at all.
+
+use std::collections::BTreeMap;
+use std::fmt::Display;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process;
+
+use amethyst::config::Config;
+use dsf_checks::level_files::{assets_dir, collect_level_files};
+use dsf_checks::reachability::{analyse_jumps, ReachabilityConfig};
+use dsf_checks::solver::{solve_level, SolverConfig, SolverOutcome};
+use dsf_core::levels::{
+    diff_levels, export_tiled_file, import_tiled_file, merge_levels, save_thumbnail,
+    upgrade_level_file, validate_level, write_ascii_level, LevelFormat, LevelFormatError,
+    LevelSave, SpriteSheets, TiledMapping, ASCII_LEVEL_EXTENSION, BINARY_LEVEL_EXTENSION,
+    CURRENT_LEVEL_VERSION,
+};
+use dsf_core::resources::{MovementConfig, TileDefinitions};
+use dsf_core::utility::loading_config::LoadingConfig;
+
+const USAGE: &str = "Usage: dsf_checks COMMAND [ARGS]...
+
+Commands:
+    validate [FILE_OR_DIR]...   Check levels against the rules of the tile definitions.
+    solve [FILE_OR_DIR]...      Prove that levels can be completed.
+    render FILE                 Draw a level in ASCII.
+    thumbnail FILE PNG [TILE_SIZE]
+                                Draw a level into a PNG image.
+    convert SOURCE TARGET       Convert a level to the format of the target's extension.
+    convert --to-ron|--to-binary|--to-ascii [FILE_OR_DIR]...
+                                Convert levels and write them next to the originals.
+    upgrade [FILE_OR_DIR]...    Rewrite levels in the current version of the format.
+    stats [FILE_OR_DIR]...      Count the tiles used in levels.
+    diff BEFORE AFTER           List the differences between two levels.
+    merge BASE OURS THEIRS [PATH]
+                                Merge two versions of a level into OURS.
+    jumps                       Print which gaps and ledges the player can jump across.";
+
+/// The size of a single tile in pixels, for thumbnails.
+const DEFAULT_THUMBNAIL_TILE_SIZE: u32 = 16;
+
+/// The outcome of a command that ran to completion.
+#[derive(Copy, Clone, PartialEq)]
+enum Verdict {
+    Pass,
+    Fail,
+}
+
+fn main() {
+    let mut args = std::env::args().skip(1);
+    let command = args.next().unwrap_or_default();
+    let args = args.collect::<Vec<_>>();
+    let result = match (command.as_str(), args.len()) {
+        ("validate", _) => validate(&args),
+        ("solve", _) => solve(&args),
+        ("render", 1) => render(&args[0]),
+        ("thumbnail", 2) | ("thumbnail", 3) => thumbnail(&args),
+        ("convert", _) if args.first().map_or(false, |arg| arg.starts_with("--to-")) => {
+            convert_all(&args)
+        }
+        ("convert", 2) => convert(&args[0], &args[1]),
+        ("upgrade", _) => upgrade(&args),
+        ("stats", _) => stats(&args),
+        ("diff", 2) => diff(&args[0], &args[1]),
+        ("merge", 3) | ("merge", 4) => merge(&args),
+        ("jumps", 0) => jumps(),
+        _ => {
+            eprintln!("{}", USAGE);
+            process::exit(2);
+        }
+    };
+    match result {
+        Ok(Verdict::Pass) => (),
+        Ok(Verdict::Fail) => process::exit(1),
+        Err(error) => {
+            eprintln!("{}", error);
+            process::exit(2);
+        }
+    }
+}
+
+fn validate(args: &[String]) -> Result<Verdict, String> {
+    let tile_defs = load_tile_defs()?;
+    let mut verdict = Verdict::Pass;
+    for file in level_files(args)? {
+        let issues = validate_level(&load_level(&file)?, &tile_defs);
+        if issues.is_empty() {
+            println!("{}: valid", file.display());
+        } else {
+            verdict = Verdict::Fail;
+            for issue in issues {
+                println!("{}: {}", file.display(), issue);
+            }
+        }
+    }
+    Ok(verdict)
+}
+
+fn solve(args: &[String]) -> Result<Verdict, String> {
+    let tile_defs = load_tile_defs()?;
+    let movement = load_movement()?;
+    let mut verdict = Verdict::Pass;
+    for file in level_files(args)? {
+        let level = load_level(&file)?;
+        match solve_level(&level, tile_defs.clone(), SolverConfig::new(movement)) {
+            Ok(SolverOutcome::Solved(actions)) => println!(
+                "{}: solved in {} actions: {:?}",
+                file.display(),
+                actions.len(),
+                actions
+            ),
+            Ok(SolverOutcome::Unsolvable { states_explored }) => {
+                verdict = Verdict::Fail;
+                println!(
+                    "{}: unsolvable, explored all {} states",
+                    file.display(),
+                    states_explored
+                );
+            }
+            Ok(SolverOutcome::Inconclusive { states_explored }) => {
+                verdict = Verdict::Fail;
+                println!(
+                    "{}: inconclusive after exploring {} states",
+                    file.display(),
+                    states_explored
+                );
+            }
+            Err(error) => {
+                verdict = Verdict::Fail;
+                println!("{}: cannot be solved: {}", file.display(), error);
+            }
+        }
+    }
+    Ok(verdict)
+}
+
+fn render(file: &str) -> Result<Verdict, String> {
+    let level = load_level(Path::new(file))?;
+    let text = write_ascii_level(&level, Some(&load_tile_defs()?))
+        .map_err(|error| describe("Failed to draw the level", error))?;
+    print!("{}", text);
+    Ok(Verdict::Pass)
+}
+
+fn thumbnail(args: &[String]) -> Result<Verdict, String> {
+    let tile_size = match args.get(2) {
+        Some(tile_size) => tile_size
+            .parse()
+            .ok()
+            .filter(|tile_size| *tile_size > 0)
+            .ok_or_else(|| format!("Invalid tile size: {}", tile_size))?,
+        None => DEFAULT_THUMBNAIL_TILE_SIZE,
+    };
+    let loading_config = LoadingConfig::load(assets_dir().join("config/loading.ron"))
+        .map_err(|error| describe("Failed to load the loading config", error))?;
+    let sprite_sheets = SpriteSheets::load(&assets_dir(), &loading_config)
+        .map_err(|error| describe("Failed to load the sprites", error))?;
+    let output = Path::new(&args[1]);
+    save_thumbnail(
+        &load_level(Path::new(&args[0]))?,
+        &load_tile_defs()?,
+        &sprite_sheets,
+        tile_size,
+        output,
+    )
+    .map_err(|error| describe("Failed to draw the level", error))?;
+    println!("Drew {} to {}.", args[0], output.display());
+    Ok(Verdict::Pass)
+}
+
+fn convert(source: &str, target: &str) -> Result<Verdict, String> {
+    let (source, target) = (Path::new(source), Path::new(target));
+    let level = if is_tiled_file(source) {
+        import_tiled_file(source, &load_tiled_mapping()?, &load_tile_defs()?)
+            .map_err(|error| describe("Failed to import the map", error))?
+    } else {
+        load_level(source)?
+    };
+    if is_tiled_file(target) {
+        export_tiled_file(&level, target, &load_tiled_mapping()?, &load_tile_defs()?)
+            .map_err(|error| describe(&format!("Failed to write {}", target.display()), error))?;
+    } else {
+        write_level(&level, target, LevelFormat::of(target), &load_tile_defs()?)?;
+    }
+    println!("Converted {} to {}.", source.display(), target.display());
+    Ok(Verdict::Pass)
+}
+
+fn convert_all(args: &[String]) -> Result<Verdict, String> {
+    let extension = match args[0].as_str() {
+        "--to-ron" => "ron",
+        "--to-binary" => BINARY_LEVEL_EXTENSION,
+        "--to-ascii" => ASCII_LEVEL_EXTENSION,
+        other => return Err(format!("Unknown target format: {}", other)),
+    };
+    let tile_defs = load_tile_defs()?;
+    let mut verdict = Verdict::Pass;
+    for file in level_files(&args[1..])? {
+        if has_extension(&file, extension) {
+            continue;
+        }
+        let target = file.with_extension(extension);
+        let converted = load_level(&file).and_then(|level| {
+            write_level(&level, &target, LevelFormat::of(&target), &tile_defs)?;
+            if load_level(&target)? == level {
+                Ok(())
+            } else {
+                Err("The converted level differs from the original".to_string())
+            }
+        });
+        match converted {
+            Ok(()) => println!("Converted {} to {}.", file.display(), target.display()),
+            Err(error) => {
+                verdict = Verdict::Fail;
+                println!("{}: {}", file.display(), error);
+            }
+        }
+    }
+    Ok(verdict)
+}
+
+fn upgrade(args: &[String]) -> Result<Verdict, String> {
+    let mut verdict = Verdict::Pass;
+    for file in level_files(args)? {
+        match upgrade_level_file(&file) {
+            Ok(true) => println!(
+                "Upgraded {} to version {}.",
+                file.display(),
+                CURRENT_LEVEL_VERSION
+            ),
+            Ok(false) => println!("{} is up to date.", file.display()),
+            Err(error) => {
+                verdict = Verdict::Fail;
+                println!("{}: failed to upgrade: {}", file.display(), error);
+            }
+        }
+    }
+    Ok(verdict)
+}
+
+fn stats(args: &[String]) -> Result<Verdict, String> {
+    let mut totals = BTreeMap::<String, usize>::new();
+    let files = level_files(args)?;
+    for file in &files {
+        let level = load_level(file)?;
+        let mut counts = BTreeMap::<&str, usize>::new();
+        for key in level.tiles.values() {
+            *counts.entry(key).or_default() += 1;
+        }
+        let bounds = &level.world_bounds;
+        println!(
+            "{}: {}x{}, {} tiles, {} with instance properties",
+            file.display(),
+            bounds.width(),
+            bounds.height(),
+            level.tiles.len(),
+            level.properties.len()
+        );
+        for (key, count) in counts {
+            println!("    {:<16}{:>6}", key, count);
+            *totals.entry(key.to_string()).or_default() += count;
+        }
+    }
+    if files.len() > 1 {
+        println!("Total over {} levels:", files.len());
+        for (key, count) in totals {
+            println!("    {:<16}{:>6}", key, count);
+        }
+    }
+    Ok(Verdict::Pass)
+}
+
+fn diff(before: &str, after: &str) -> Result<Verdict, String> {
+    let diff = diff_levels(
+        &load_level(Path::new(before))?,
+        &load_level(Path::new(after))?,
+    );
+    print!("{}", diff);
+    Ok(if diff.is_empty() {
+        Verdict::Pass
+    } else {
+        Verdict::Fail
+    })
+}
+
+fn merge(args: &[String]) -> Result<Verdict, String> {
+    let ours = Path::new(&args[1]);
+    // Git passes temporary files without an extension, and the path of the level separately.
+    let format = LevelFormat::of(args.get(3).map_or(ours, Path::new));
+    let load = |file: &str| {
+        LevelSave::load_as(file, format)
+            .map_err(|error| describe(&format!("Failed to load {}", file), error))
+    };
+    let tile_defs = load_tile_defs()?;
+    let outcome = merge_levels(
+        &load(&args[0])?,
+        &load(&args[1])?,
+        &load(&args[2])?,
+        &tile_defs,
+    );
+    write_level(&outcome.level, ours, format, &tile_defs)?;
+    for conflict in &outcome.conflicts {
+        eprintln!("Conflict: {}", conflict);
+    }
+    Ok(if outcome.conflicts.is_empty() {
+        Verdict::Pass
+    } else {
+        Verdict::Fail
+    })
+}
+
+fn jumps() -> Result<Verdict, String> {
+    let player_dimens = load_tile_defs()?.get("Player").dimens;
+    let table = analyse_jumps(
+        &load_movement()?,
+        player_dimens,
+        &ReachabilityConfig::default(),
+    );
+    print!("{}", table);
+    Ok(Verdict::Pass)
+}
+
+fn level_files(args: &[String]) -> Result<Vec<PathBuf>, String> {
+    collect_level_files(args.iter().map(PathBuf::from).collect())
+        .map_err(|error| describe("Failed to read directory", error))
+}
+
+fn load_level(file: &Path) -> Result<LevelSave, String> {
+    LevelSave::load(file)
+        .map_err(|error| describe(&format!("Failed to load {}", file.display()), error))
+}
+
+/// Writes the level in the given format. ASCII levels are drawn with the positions covered by
+/// large tiles marked.
+fn write_level(
+    level: &LevelSave,
+    file: &Path,
+    format: LevelFormat,
+    tile_defs: &TileDefinitions,
+) -> Result<(), String> {
+    let written = if format == LevelFormat::Ascii {
+        write_ascii_level(level, Some(tile_defs))
+            .map_err(LevelFormatError::from)
+            .and_then(|text| fs::write(file, text).map_err(LevelFormatError::from))
+    } else {
+        level.save_as(file, format)
+    };
+    written.map_err(|error| describe(&format!("Failed to write {}", file.display()), error))
+}
+
+fn load_tile_defs() -> Result<TileDefinitions, String> {
+    TileDefinitions::load(assets_dir().join("world/tile_references.ron"))
+        .map_err(|error| describe("Failed to load the tile definitions", error))
+}
+
+fn load_movement() -> Result<MovementConfig, String> {
+    MovementConfig::load(assets_dir().join("config/movement.ron"))
+        .map_err(|error| describe("Failed to load the movement config", error))
+}
+
+fn load_tiled_mapping() -> Result<TiledMapping, String> {
+    TiledMapping::load(assets_dir().join("world/tiled_mapping.ron"))
+        .map_err(|error| describe("Failed to load the Tiled mapping", error))
+}
+
+fn is_tiled_file(file: &Path) -> bool {
+    has_extension(file, "tmx") || has_extension(file, "json")
+}
+
+fn has_extension(file: &Path, extension: &str) -> bool {
+    file.extension().map_or(false, |other| other == extension)
+}
+
+fn describe(message: &str, error: impl Display) -> String {
+    format!("{}: {}", message, error)
+}
@@ -0,0 +1,308 @@
+use std::collections::HashMap;
+use std::fmt;
+
+use dsf_core::components::{Direction1D, Player, Pos, SteeringIntent};
+use dsf_core::levels::LevelSave;
+use dsf_core::resources::{
+    CollisionDefinition, MovementConfig, TileDefinition, TileDefinitions, TileMap, WorldBounds,
+};
+use dsf_core::systems::{simulate_tick, update_intent, PlayerSim};
+
+use crate::solver::{grace_ticks, ticks_per_tile, JumpTiming, TICK_SECONDS};
+
+/// The key of the only tile used to build the test terrain.
+const BLOCK: &str = "Block";
+
+/// Any jump that takes longer than this is considered to never land.
+const MAX_SECONDS: f32 = 5.;
+
+/// Determines which jumps are analysed.
+#[derive(Copy, Clone, Debug)]
+pub struct ReachabilityConfig {
+    /// The widest gap to analyse, in tiles.
+    pub max_gap: i32,
+    /// The lowest target ledge to analyse, relative to the ledge the player jumps from.
+    pub min_height: i32,
+    /// The highest target ledge to analyse, relative to the ledge the player jumps from.
+    pub max_height: i32,
+    /// The number of tiles the player may walk before they reach the edge and jump.
+    pub max_run_up: i32,
+}
+
+impl Default for ReachabilityConfig {
+    fn default() -> Self {
+        ReachabilityConfig {
+            max_gap: 7,
+            min_height: -4,
+            max_height: 4,
+            max_run_up: 2,
+        }
+    }
+}
+
+/// Whether the player can jump from one ledge to another.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Reachability {
+    /// Walking up to the edge and jumping always works, no matter the timing of the inputs.
+    Always,
+    /// Some timings make it, others don't. Puzzles should never rely on these jumps, because
+    /// players will either fail them or use them to skip part of a puzzle.
+    Sometimes,
+    /// No timing makes it.
+    Never,
+}
+
+impl Reachability {
+    fn symbol(self) -> char {
+        match self {
+            Reachability::Always => 'A',
+            Reachability::Sometimes => 'S',
+            Reachability::Never => '-',
+        }
+    }
+}
+
+/// The verdict for a single combination of gap width and ledge height.
+#[derive(Copy, Clone, Debug)]
+pub struct JumpReach {
+    /// Width of the gap between the two ledges, in tiles.
+    pub gap: i32,
+    /// Height of the target ledge, relative to the ledge the player jumps from.
+    pub height: i32,
+    pub reachability: Reachability,
+    /// The number of input timings that started the jump with the player at the very edge.
+    pub attempts_from_edge: usize,
+    /// The number of those timings that landed on the target ledge.
+    pub successes_from_edge: usize,
+    /// The number of input timings that were tried.
+    pub attempts: usize,
+    /// The number of input timings that landed on the target ledge.
+    pub successes: usize,
+}
+
+/// The result of the jump reachability analysis. Can be printed as a table.
+#[derive(Clone, Debug)]
+pub struct ReachabilityTable {
+    pub config: ReachabilityConfig,
+    pub entries: Vec<JumpReach>,
+}
+
+impl ReachabilityTable {
+    #[must_use]
+    pub fn get(&self, gap: i32, height: i32) -> Option<&JumpReach> {
+        self.entries
+            .iter()
+            .find(|entry| entry.gap == gap && entry.height == height)
+    }
+}
+
+impl fmt::Display for ReachabilityTable {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "Columns: gap width in tiles. Rows: height of the target ledge in tiles."
+        )?;
+        writeln!(f, "A = always, S = sometimes, - = never.")?;
+        write!(f, "height\\gap")?;
+        for gap in 0..=self.config.max_gap {
+            write!(f, "{:>3}", gap)?;
+        }
+        writeln!(f)?;
+        for height in (self.config.min_height..=self.config.max_height).rev() {
+            write!(f, "{:>+10}", height)?;
+            for gap in 0..=self.config.max_gap {
+                let symbol = self
+                    .get(gap, height)
+                    .map_or('?', |entry| entry.reachability.symbol());
+                write!(f, "{:>3}", symbol)?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+/// Finds out for every gap width and ledge height in the config whether the player can jump
+/// across, by trying every relevant input timing.
+///
+/// The player jumps to the right, from a ledge that ends at x = 0, to a ledge that starts at
+/// x = 1 + gap. The player is at the edge when their left foot is on the last tile of the ledge.
+///
+/// Two kinds of input timings are tried:
+///
+/// - Standing jumps: the player stands still and presses jump. The jump direction is pressed
+///   at the same time, or at any tick within the `jump_allowance` grace window.
+/// - Running jumps: the player walks towards the edge and presses jump after any number of ticks.
+///
+/// Inputs are fed through the same `update_intent` and `simulate_tick` functions the game uses.
+#[must_use]
+pub fn analyse_jumps(
+    movement: &MovementConfig,
+    player_dimens: Pos,
+    config: &ReachabilityConfig,
+) -> ReachabilityTable {
+    let analysis = JumpAnalysis {
+        movement,
+        player_dimens,
+        config,
+        grace_ticks: grace_ticks(movement),
+        ticks_per_tile: ticks_per_tile(movement),
+    };
+    let mut entries = vec![];
+    for height in config.min_height..=config.max_height {
+        for gap in 0..=config.max_gap {
+            entries.push(analysis.analyse(gap, height));
+        }
+    }
+    ReachabilityTable {
+        config: *config,
+        entries,
+    }
+}
+
+struct JumpAnalysis<'a> {
+    movement: &'a MovementConfig,
+    player_dimens: Pos,
+    config: &'a ReachabilityConfig,
+    grace_ticks: u32,
+    ticks_per_tile: u32,
+}
+
+impl<'a> JumpAnalysis<'a> {
+    fn analyse(&self, gap: i32, height: i32) -> JumpReach {
+        let tile_map = self.build_terrain(gap, height);
+        let mut entry = JumpReach {
+            gap,
+            height,
+            reachability: Reachability::Never,
+            attempts_from_edge: 0,
+            successes_from_edge: 0,
+            attempts: 0,
+            successes: 0,
+        };
+        for start_x in -self.config.max_run_up..=0 {
+            let standing = (0..self.grace_ticks).map(|delay| JumpTiming::Standing {
+                direction_delay: delay,
+            });
+            let running_ticks = (1 - start_x) as u32 * self.ticks_per_tile;
+            let running =
+                (0..=running_ticks).map(|delay| JumpTiming::Running { jump_delay: delay });
+            for timing in standing.chain(running) {
+                if let Some((jumped_from, landed)) = self.attempt(&tile_map, start_x, timing) {
+                    let success = landed.y == height && landed.x + self.player_dimens.x > 1 + gap;
+                    entry.attempts += 1;
+                    entry.successes += success as usize;
+                    if jumped_from == Pos::new(0, 0) {
+                        entry.attempts_from_edge += 1;
+                        entry.successes_from_edge += success as usize;
+                    }
+                }
+            }
+        }
+        entry.reachability = if entry.successes == 0 {
+            Reachability::Never
+        } else if entry.successes_from_edge == entry.attempts_from_edge
+            && entry.attempts_from_edge > 0
+        {
+            Reachability::Always
+        } else {
+            Reachability::Sometimes
+        };
+        entry
+    }
+
+    /// Plays out a single jump. Returns where the jump started and where the player came to rest.
+    /// Returns None if the player never jumped, for instance because they walked off the edge
+    /// before pressing jump.
+    fn attempt(&self, tile_map: &TileMap, start_x: i32, timing: JumpTiming) -> Option<(Pos, Pos)> {
+        let mut sim = PlayerSim::new(
+            Pos::new(start_x, 0),
+            self.player_dimens,
+            self.movement.player_speed,
+        );
+        let mut player = Player::default();
+        let mut intent = SteeringIntent::default();
+        let mut jumped_from = None;
+        for tick in 0..(MAX_SECONDS / TICK_SECONDS) as u32 {
+            let input = timing.input(Direction1D::Positive, tick, self.grace_ticks);
+            update_intent(
+                &mut player,
+                &mut intent,
+                &sim.steering,
+                input,
+                self.movement,
+                TICK_SECONDS,
+            );
+            let events = simulate_tick(&mut sim, &intent, tile_map, TICK_SECONDS);
+            intent.walk_invalidated |= events.walk_invalidated;
+            let steering = &sim.steering;
+            if jumped_from.is_none() {
+                if steering.is_jumping() {
+                    jumped_from = Some(steering.pos);
+                } else if steering.is_falling() {
+                    // Walked off the edge without jumping.
+                    return None;
+                }
+            } else if steering.is_grounded()
+                && steering.pos == steering.destination
+                && sim.velocity.x.abs() < f32::EPSILON
+            {
+                return jumped_from.map(|jumped_from| (jumped_from, steering.pos));
+            }
+        }
+        None
+    }
+
+    /// Builds a ledge to jump from, a ledge to jump to and a floor far below to catch the player if
+    /// they fall short.
+    fn build_terrain(&self, gap: i32, height: i32) -> TileMap {
+        let left = -self.config.max_run_up - 10;
+        let right = 1 + self.config.max_gap + 10;
+        let floor = self.config.min_height - 6;
+        let mut tiles = HashMap::new();
+        let mut put = |x: i32, y: i32| {
+            tiles.insert(Pos::new(x, y), BLOCK.to_string());
+        };
+        for x in left..=right {
+            put(x, floor);
+        }
+        for x in left..=0 {
+            put(x, -1);
+        }
+        // A higher ledge is a wall that reaches all the way down to the ground.
+        let bottom = if height > 0 { -1 } else { height - 1 };
+        for x in (1 + gap)..=right {
+            for y in bottom..height {
+                put(x, y);
+            }
+        }
+        let level = LevelSave {
+            world_bounds: WorldBounds::new(
+                left - 2,
+                floor - 2,
+                right - left + 4,
+                self.config.max_height - floor + 10,
+            ),
+            tiles,
+            ..LevelSave::default()
+        };
+        TileMap::for_play(&level, terrain_definitions())
+    }
+}
+
+fn terrain_definitions() -> TileDefinitions {
+    let mut tile_defs = TileDefinitions::default();
+    tile_defs.map.insert(
+        BLOCK.to_string(),
+        TileDefinition {
+            dimens: Pos::new(1, 1),
+            collision: Some(CollisionDefinition {
+                collides_top: true,
+                collides_side: true,
+                collides_bottom: true,
+            }),
+            ..TileDefinition::default()
+        },
+    );
+    tile_defs
+}
@@ -0,0 +1,3 @@
+mod jumps;
+
+pub use self::jumps::*;
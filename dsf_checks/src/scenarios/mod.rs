@@ -0,0 +1,5 @@
+mod runner;
+mod scenario;
+
+pub use self::runner::*;
+pub use self::scenario::*;
@@ -0,0 +1,154 @@
+use std::fmt;
+use std::path::Path;
+
+use dsf_core::components::{Direction1D, Pos};
+use dsf_core::levels::{LevelFormatError, LevelSave};
+use dsf_core::resources::{Archetype, MovementConfig, TileDefinitions, TileMap};
+use dsf_core::systems::{simulate_tick, PlayerSim};
+
+use crate::scenarios::{ExpectedMode, Scenario};
+use crate::solver::{PuzzleObject, TICK_SECONDS};
+
+/// The state the player ended up in after playing through a scenario, along with every
+/// expectation that was not met.
+#[derive(Clone, Debug)]
+pub struct ScenarioReport {
+    pub pos: Pos,
+    pub mode: ExpectedMode,
+    pub keys_collected: usize,
+    /// Human-readable descriptions of the failed expectations. Empty if the scenario passed.
+    pub failures: Vec<String>,
+}
+
+impl ScenarioReport {
+    #[must_use]
+    pub fn passed(&self) -> bool {
+        self.failures.is_empty()
+    }
+}
+
+/// The reasons a scenario cannot be run at all.
+#[derive(Debug)]
+pub enum ScenarioError {
+    /// The level file could not be loaded.
+    Level(LevelFormatError),
+    /// The scenario has no start position and the level has no player tile.
+    MissingPlayer,
+    /// The scenario has no start position and the level has more than one player tile.
+    MultiplePlayers(usize),
+}
+
+impl fmt::Display for ScenarioError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ScenarioError::Level(error) => write!(f, "failed to load the level: {}", error),
+            ScenarioError::MissingPlayer => {
+                write!(f, "no start position given and the level has no player")
+            }
+            ScenarioError::MultiplePlayers(amount) => write!(
+                f,
+                "no start position given and the level has {} players",
+                amount
+            ),
+        }
+    }
+}
+
+/// Loads the scenario's level from the given tests directory and runs the scenario in it.
+pub fn run_scenario_in_dir(
+    scenario: &Scenario,
+    tests_dir: &Path,
+    tile_defs: TileDefinitions,
+    movement: &MovementConfig,
+) -> Result<ScenarioReport, ScenarioError> {
+    let level = LevelSave::load(tests_dir.join(&scenario.level)).map_err(ScenarioError::Level)?;
+    run_scenario(scenario, &level, tile_defs, movement)
+}
+
+/// Plays through the scenario headlessly, one tick at a time, using the same movement simulation
+/// as the game itself. Mirrors the `PlayerSystem` in that a walk intent stays invalidated until
+/// the scripted walk direction changes.
+pub fn run_scenario(
+    scenario: &Scenario,
+    level: &LevelSave,
+    tile_defs: TileDefinitions,
+    movement: &MovementConfig,
+) -> Result<ScenarioReport, ScenarioError> {
+    let mut players = vec![];
+    let mut keys = vec![];
+    for (pos, key) in &level.tiles {
+        let tile_def = level.tile_def(*pos, key, &tile_defs);
+        match tile_def.archetype {
+            Some(Archetype::Player) => players.push(PuzzleObject::new(*pos, tile_def.dimens)),
+            Some(Archetype::Key) => keys.push(PuzzleObject::new(*pos, tile_def.dimens)),
+            _ => (),
+        }
+    }
+    let player_dimens = tile_defs
+        .map
+        .values()
+        .find(|tile_def| tile_def.archetype == Some(Archetype::Player))
+        .map_or(Pos::new(2, 2), |tile_def| tile_def.dimens);
+    let start = match (scenario.start, players.len()) {
+        (Some(start), _) => start,
+        (None, 1) => players[0].pos,
+        (None, 0) => return Err(ScenarioError::MissingPlayer),
+        (None, amount) => return Err(ScenarioError::MultiplePlayers(amount)),
+    };
+    let tile_map = TileMap::for_play(level, tile_defs);
+    let mut sim = PlayerSim::new(start, player_dimens, movement.player_speed);
+    let mut walk_invalidated = false;
+    let mut previous_walk = Direction1D::Neutral;
+    let mut keys_collected = 0;
+    for entry in &scenario.timeline {
+        for _ in 0..entry.ticks {
+            let mut intent = entry.intent;
+            if walk_invalidated && previous_walk != intent.walk {
+                walk_invalidated = false;
+            }
+            previous_walk = intent.walk;
+            intent.walk_invalidated = walk_invalidated;
+            let events = simulate_tick(&mut sim, &intent, &tile_map, TICK_SECONDS);
+            walk_invalidated |= events.walk_invalidated;
+            let before = keys.len();
+            keys.retain(|key| !key.intersects(sim.translation, player_dimens));
+            keys_collected += before - keys.len();
+        }
+    }
+    let report = ScenarioReport {
+        pos: sim.steering.pos,
+        mode: ExpectedMode::from(sim.steering.mode),
+        keys_collected,
+        failures: vec![],
+    };
+    Ok(check_expectations(scenario, report))
+}
+
+fn check_expectations(scenario: &Scenario, mut report: ScenarioReport) -> ScenarioReport {
+    let expect = &scenario.expect;
+    if let Some(pos) = expect.pos {
+        if pos != report.pos {
+            report.failures.push(format!(
+                "expected to end at {:?}, but ended at {:?}",
+                pos, report.pos
+            ));
+        }
+    }
+    if let Some(mode) = expect.mode {
+        if mode != report.mode {
+            report.failures.push(format!(
+                "expected to end {:?}, but ended {:?}",
+                mode, report.mode
+            ));
+        }
+    }
+    if let Some(keys_collected) = expect.keys_collected {
+        if keys_collected != report.keys_collected {
+            report.failures.push(format!(
+                "expected to collect {} keys, but collected {}",
+                keys_collected, report.keys_collected
+            ));
+        }
+    }
+    report
+}
@@ -0,0 +1,84 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use amethyst::config::{Config, ConfigError};
+use dsf_core::components::{Pos, SteeringIntent, SteeringMode};
+use serde::{Deserialize, Serialize};
+
+/// A scripted movement test. The player is dropped into a level and fed a fixed sequence of
+/// intents, after which the state the player ended up in is compared against the expectations.
+///
+/// Scenarios are stored as RON files in the `assets/tests/scenarios/` directory.
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+#[serde(default)]
+#[serde(deny_unknown_fields)]
+pub struct Scenario {
+    /// A human-readable explanation of what this scenario proves.
+    pub description: String,
+    /// The level file to play in, relative to the `assets/tests/` directory.
+    pub level: String,
+    /// Where the player starts. If this is None, the player starts at the player tile in the level.
+    pub start: Option<Pos>,
+    /// The intents to feed to the player, in order.
+    pub timeline: Vec<TimelineEntry>,
+    /// What the player's state should look like after the entire timeline was played.
+    pub expect: Expectation,
+}
+
+/// The player holds the given intent for the given number of ticks.
+#[derive(Copy, Clone, Debug, Default, Deserialize, Serialize)]
+#[serde(default)]
+#[serde(deny_unknown_fields)]
+pub struct TimelineEntry {
+    pub ticks: u32,
+    pub intent: SteeringIntent,
+}
+
+/// The conditions that must hold at the end of a scenario. Fields that are None are not checked.
+#[derive(Copy, Clone, Debug, Default, Deserialize, Serialize)]
+#[serde(default)]
+#[serde(deny_unknown_fields)]
+pub struct Expectation {
+    /// The discrete position of the player.
+    pub pos: Option<Pos>,
+    /// The steering mode of the player.
+    pub mode: Option<ExpectedMode>,
+    /// The number of keys the player collected.
+    pub keys_collected: Option<usize>,
+}
+
+/// The variants of `SteeringMode`, without the data that changes every tick.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+pub enum ExpectedMode {
+    Grounded,
+    Climbing,
+    Falling,
+    Jumping,
+}
+
+impl From<SteeringMode> for ExpectedMode {
+    fn from(mode: SteeringMode) -> Self {
+        match mode {
+            SteeringMode::Grounded => ExpectedMode::Grounded,
+            SteeringMode::Climbing => ExpectedMode::Climbing,
+            SteeringMode::Falling { .. } => ExpectedMode::Falling,
+            SteeringMode::Jumping { .. } => ExpectedMode::Jumping,
+        }
+    }
+}
+
+/// Loads every scenario in the given directory, sorted by file name.
+pub fn load_scenarios(dir: &Path) -> Result<Vec<(PathBuf, Scenario)>, ConfigError> {
+    let mut files = fs::read_dir(dir)?
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| {
+            path.extension()
+                .map_or(false, |extension| extension == "ron")
+        })
+        .collect::<Vec<_>>();
+    files.sort();
+    files
+        .into_iter()
+        .map(|path| Scenario::load(&path).map(|scenario| (path, scenario)))
+        .collect()
+}
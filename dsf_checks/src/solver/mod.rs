@@ -0,0 +1,7 @@
+mod moves;
+mod puzzle;
+mod search;
+
+pub use self::moves::*;
+pub use self::puzzle::*;
+pub use self::search::*;
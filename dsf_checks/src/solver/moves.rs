@@ -0,0 +1,466 @@
+use std::borrow::Cow;
+
+use dsf_core::components::{Direction1D, Player, Steering, SteeringIntent, SteeringMode};
+use dsf_core::resources::{MovementConfig, TileMap, ToolType};
+use dsf_core::systems::{
+    at_least_one_is_breakable, dynamite_position, explosion_targets, fits_in_tile_map, is_grounded,
+    ladder_positions, none_are_unbreakable, place_ladder, simulate_tick, tiles_below,
+    tiles_to_side, update_intent, PlayerSim, LADDER_TILE,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::solver::{Puzzle, PuzzleState, Stance};
+
+/// The length of a single tick in seconds. This is amethyst's default fixed time step, which is the
+/// time step the game runs its movement systems at.
+pub const TICK_SECONDS: f32 = 1. / 60.;
+
+/// The number of ticks during which the jump direction can still be given, counting the tick in
+/// which jump was pressed. Counted the same way `update_intent` advances the grace timer.
+#[must_use]
+pub fn grace_ticks(movement: &MovementConfig) -> u32 {
+    let mut ticks = 1;
+    let mut time_passed = TICK_SECONDS;
+    while time_passed < movement.jump_allowance {
+        ticks += 1;
+        time_passed += TICK_SECONDS;
+    }
+    ticks
+}
+
+/// The number of ticks it takes the player to walk a single tile.
+#[must_use]
+pub fn ticks_per_tile(movement: &MovementConfig) -> u32 {
+    (1. / (movement.player_speed * TICK_SECONDS)).ceil() as u32
+}
+
+/// The ways the player can press the buttons to jump towards the side, other than pressing jump
+/// and the direction in the same tick.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, Deserialize, Serialize)]
+pub enum JumpTiming {
+    /// Jump from a standstill. The direction is pressed the given number of ticks after jump.
+    Standing { direction_delay: u32 },
+    /// Hold the direction and press jump after the given number of ticks.
+    Running { jump_delay: u32 },
+}
+
+impl JumpTiming {
+    /// The raw input in the given tick, as `update_intent` takes it. The direction is let go at the
+    /// end of the grace window, because after that it no longer influences the jump.
+    #[must_use]
+    pub fn input(self, direction: Direction1D, tick: u32, grace_ticks: u32) -> (f32, f32, bool) {
+        let direction_from = match self {
+            JumpTiming::Standing { direction_delay } => direction_delay,
+            JumpTiming::Running { .. } => 0,
+        };
+        let input_x = if tick >= direction_from && tick < self.input_ticks(grace_ticks) {
+            direction.signum()
+        } else {
+            0.
+        };
+        (input_x, 0., tick == self.jump_tick())
+    }
+
+    /// The number of ticks after which all keys are let go.
+    #[must_use]
+    pub fn input_ticks(self, grace_ticks: u32) -> u32 {
+        self.jump_tick() + grace_ticks
+    }
+
+    fn jump_tick(self) -> u32 {
+        match self {
+            JumpTiming::Standing { .. } => 0,
+            JumpTiming::Running { jump_delay } => jump_delay,
+        }
+    }
+}
+
+/// Everything the player can do while they are at rest. Each action is one "input" in a solution:
+/// it starts from a resting position and ends as soon as the player has come to rest again.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, Deserialize, Serialize)]
+pub enum Action {
+    /// Walk one tile to the left. Turns around first if the player is facing right.
+    WalkLeft,
+    /// Walk one tile to the right. Turns around first if the player is facing left.
+    WalkRight,
+    /// Turn to face left without taking a step.
+    TurnLeft,
+    /// Turn to face right without taking a step.
+    TurnRight,
+    /// Climb one tile up a ladder.
+    ClimbUp,
+    /// Climb one tile down a ladder.
+    ClimbDown,
+    /// Jump straight up.
+    JumpUp,
+    /// Jump towards the left.
+    JumpLeft,
+    /// Jump towards the right.
+    JumpRight,
+    /// Let go of the ladder and fall down.
+    LetGo,
+    /// Use the equipped tool. After placing dynamite, the player waits until it has exploded.
+    UseTool,
+    /// Jump towards the given side, pressing jump and the direction at different times. Some gaps
+    /// can only be crossed this way, for instance with a running jump.
+    TimedJump {
+        direction: Direction1D,
+        timing: JumpTiming,
+    },
+}
+
+impl Action {
+    /// The actions that are performed by briefly tapping a single key, or jump and a direction
+    /// at once.
+    pub const TAPS: [Action; 11] = [
+        Action::WalkLeft,
+        Action::WalkRight,
+        Action::TurnLeft,
+        Action::TurnRight,
+        Action::ClimbUp,
+        Action::ClimbDown,
+        Action::JumpUp,
+        Action::JumpLeft,
+        Action::JumpRight,
+        Action::LetGo,
+        Action::UseTool,
+    ];
+
+    /// All actions, in the order in which the solver tries them.
+    ///
+    /// Timed jumps are tried with every direction delay within the grace window of a jump, and
+    /// with every jump delay during a run-up of up to two tiles. A longer run-up is the same as
+    /// starting to run one tile further along, because the player walks at a constant speed.
+    #[must_use]
+    pub fn all(movement: &MovementConfig) -> Vec<Action> {
+        let grace_ticks = grace_ticks(movement);
+        let run_up_ticks = 2 * ticks_per_tile(movement);
+        let mut actions = Action::TAPS.to_vec();
+        for direction in [Direction1D::Negative, Direction1D::Positive]
+            .iter()
+            .copied()
+        {
+            let standing =
+                (1..grace_ticks).map(|direction_delay| JumpTiming::Standing { direction_delay });
+            let running = (1..run_up_ticks).map(|jump_delay| JumpTiming::Running { jump_delay });
+            actions.extend(
+                standing
+                    .chain(running)
+                    .map(|timing| Action::TimedJump { direction, timing }),
+            );
+        }
+        actions
+    }
+}
+
+/// The result of successfully performing an action.
+#[derive(Clone, Debug)]
+pub struct Transition {
+    /// The state the player ended up in after coming to rest.
+    pub state: PuzzleState,
+    /// True if the player walked into the open door at any point during the action.
+    /// If this is true, the level is completed.
+    pub reached_door: bool,
+}
+
+/// Performs the given action from the given resting state.
+///
+/// The `tile_map` must be the puzzle's tile map with all of the state's broken blocks removed and
+/// all of its placed ladders added.
+///
+/// Movement is simulated tick by tick with `simulate_tick`, the same physics the game runs on.
+/// Returns None if the action is not possible from this state, or if it ends in a fall that never
+/// lands.
+#[must_use]
+pub fn perform_action(
+    puzzle: &Puzzle,
+    tile_map: &TileMap,
+    state: &PuzzleState,
+    action: Action,
+) -> Option<Transition> {
+    let mut mover = Mover {
+        puzzle,
+        tile_map: Cow::Borrowed(tile_map),
+        state: state.clone(),
+        reached_door: false,
+    };
+    let grounded = state.stance == Stance::Grounded;
+    let climbing = state.stance == Stance::Climbing;
+    let empty_handed = state.equipped.is_none();
+    match action {
+        Action::WalkLeft => mover.walk(Direction1D::Negative),
+        Action::WalkRight => mover.walk(Direction1D::Positive),
+        Action::TurnLeft if grounded => mover.turn(Direction1D::Negative),
+        Action::TurnRight if grounded => mover.turn(Direction1D::Positive),
+        Action::ClimbUp => mover.climb(Direction1D::Positive),
+        Action::ClimbDown => mover.climb(Direction1D::Negative),
+        Action::JumpUp if grounded && empty_handed => mover.jump(Direction1D::Neutral),
+        Action::JumpLeft if grounded && empty_handed => mover.jump(Direction1D::Negative),
+        Action::JumpRight if grounded && empty_handed => mover.jump(Direction1D::Positive),
+        Action::LetGo if climbing && empty_handed => mover.let_go(),
+        Action::UseTool if grounded => mover.use_tool(),
+        Action::TimedJump { direction, timing } if grounded && empty_handed => {
+            mover.timed_jump(direction, timing)
+        }
+        _ => None,
+    }?;
+    Some(Transition {
+        state: mover.state,
+        reached_door: mover.reached_door,
+    })
+}
+
+/// Settles the initial state of a puzzle: the player spawns in mid-air as often as not, in which
+/// case they immediately fall down. Also picks up anything the player spawns on top of.
+///
+/// Returns None if the player falls forever.
+#[must_use]
+pub fn settle_initial_state(puzzle: &Puzzle, state: &PuzzleState) -> Option<Transition> {
+    let mut mover = Mover {
+        puzzle,
+        tile_map: Cow::Borrowed(&puzzle.tile_map),
+        state: state.clone(),
+        reached_door: false,
+    };
+    mover.settle()?;
+    Some(Transition {
+        state: mover.state,
+        reached_door: mover.reached_door,
+    })
+}
+
+/// Traces the movement of the player during a single action.
+struct Mover<'a> {
+    puzzle: &'a Puzzle,
+    tile_map: Cow<'a, TileMap>,
+    state: PuzzleState,
+    reached_door: bool,
+}
+
+impl<'a> Mover<'a> {
+    fn walk(&mut self, direction: Direction1D) -> Option<()> {
+        let intent = SteeringIntent {
+            face: direction,
+            walk: direction,
+            ..SteeringIntent::default()
+        };
+        self.simulate(intent)
+    }
+
+    fn turn(&mut self, direction: Direction1D) -> Option<()> {
+        if self.state.facing == direction {
+            None
+        } else {
+            self.state.facing = direction;
+            Some(())
+        }
+    }
+
+    fn climb(&mut self, direction: Direction1D) -> Option<()> {
+        let intent = SteeringIntent {
+            climb: direction,
+            ..SteeringIntent::default()
+        };
+        self.simulate(intent)
+    }
+
+    fn jump(&mut self, direction: Direction1D) -> Option<()> {
+        let intent = SteeringIntent {
+            face: direction,
+            jump: true,
+            jump_direction: direction,
+            ..SteeringIntent::default()
+        };
+        self.simulate(intent)
+    }
+
+    /// Feeds the inputs through `update_intent`, the same way the `PlayerSystem` does.
+    fn timed_jump(&mut self, direction: Direction1D, timing: JumpTiming) -> Option<()> {
+        let movement = self.puzzle.movement;
+        let grace_ticks = grace_ticks(&movement);
+        let mut player = Player::default();
+        self.simulate_inputs(timing.input_ticks(grace_ticks), |intent, steering, tick| {
+            let input = timing.input(direction, tick, grace_ticks);
+            update_intent(
+                &mut player,
+                intent,
+                steering,
+                input,
+                &movement,
+                TICK_SECONDS,
+            );
+        })
+    }
+
+    fn let_go(&mut self) -> Option<()> {
+        let intent = SteeringIntent {
+            jump: true,
+            ..SteeringIntent::default()
+        };
+        self.simulate(intent)
+    }
+
+    fn use_tool(&mut self) -> Option<()> {
+        let mut steering = Steering::new(self.state.pos, self.puzzle.player.dimens);
+        steering.facing.x = self.state.facing;
+        let targeted_blocks = match self.state.equipped? {
+            ToolType::BreakBlocksHorizontally(depth) => {
+                if at_least_one_is_breakable(&tiles_to_side(1, &steering), &self.tile_map) {
+                    tiles_to_side(depth, &steering)
+                } else {
+                    return None;
+                }
+            }
+            ToolType::BreakBlocksBelow(depth) => tiles_below(depth, &steering),
+            // The player waits on the spot until the fuse has burnt down, so the dynamite
+            // explodes within this move. See `solve` for why that is not the whole story.
+            ToolType::Dynamite { radius, .. } => {
+                let targets =
+                    explosion_targets(dynamite_position(&steering), radius, &self.tile_map);
+                if targets.is_empty() {
+                    return None;
+                }
+                self.state.equipped = None;
+                let tile_map = self.tile_map.to_mut();
+                for pos in &targets {
+                    if let Some(anchor) = tile_map.remove_tile(*pos) {
+                        self.state.broken.insert(anchor);
+                    }
+                }
+                return self.settle();
+            }
+            ToolType::PlaceLadder { length, upwards } => {
+                let dimens = self.tile_map.tile_defs.get(LADDER_TILE).dimens;
+                let positions = ladder_positions(&steering, length, upwards, dimens);
+                if positions.is_empty() || !fits_in_tile_map(&positions, dimens, &self.tile_map) {
+                    return None;
+                }
+                self.state.equipped = None;
+                let tile_map = self.tile_map.to_mut();
+                for pos in positions {
+                    place_ladder(tile_map, pos);
+                    self.state.ladders.insert(pos);
+                }
+                return self.settle();
+            }
+        };
+        if !at_least_one_is_breakable(&targeted_blocks, &self.tile_map)
+            || !none_are_unbreakable(&targeted_blocks, &self.tile_map)
+        {
+            return None;
+        }
+        self.state.equipped = None;
+        let tile_map = self.tile_map.to_mut();
+        for pos in &targeted_blocks {
+            if let Some(anchor) = tile_map.remove_tile(*pos) {
+                self.state.broken.insert(anchor);
+            }
+        }
+        self.settle()
+    }
+
+    /// Lets the player come to rest without any input. If the player is standing on thin air,
+    /// they fall down.
+    fn settle(&mut self) -> Option<()> {
+        self.simulate(SteeringIntent::default())
+    }
+
+    /// Simulates the game tick by tick, starting from the current resting state, until the player
+    /// comes to rest again. The intent is only held for the first tick, which is the same as
+    /// briefly tapping a key.
+    ///
+    /// Returns None if the player never comes to rest.
+    fn simulate(&mut self, intent: SteeringIntent) -> Option<()> {
+        self.simulate_inputs(1, |current, _, tick| {
+            *current = if tick == 0 {
+                intent
+            } else {
+                SteeringIntent::default()
+            };
+        })
+    }
+
+    /// Simulates the game tick by tick, starting from the current resting state, until the player
+    /// comes to rest again after the given number of ticks with input. At the start of every tick,
+    /// `update` gets to change the intent, given the player's steering and the number of the tick.
+    ///
+    /// Returns None if the player never comes to rest.
+    fn simulate_inputs(
+        &mut self,
+        input_ticks: u32,
+        mut update: impl FnMut(&mut SteeringIntent, &Steering, u32),
+    ) -> Option<()> {
+        let bounds = self.tile_map.world_bounds;
+        let mut sim = PlayerSim::new(
+            self.state.pos,
+            self.puzzle.player.dimens,
+            self.puzzle.movement.player_speed,
+        );
+        sim.steering.facing.x = self.state.facing;
+        if self.state.stance == Stance::Climbing {
+            sim.steering.mode = SteeringMode::Climbing;
+        }
+        self.visit(sim.translation);
+        // Any movement that takes longer than this will never end: the player must be falling
+        // through the level forever.
+        let max_ticks =
+            input_ticks + ((bounds.width() + bounds.height()) as f32 / TICK_SECONDS) as u32;
+        let mut intent = SteeringIntent::default();
+        for tick in 0..max_ticks {
+            update(&mut intent, &sim.steering, tick);
+            let events = simulate_tick(&mut sim, &intent, &self.tile_map, TICK_SECONDS);
+            intent.walk_invalidated |= events.walk_invalidated;
+            self.visit(sim.translation);
+            if tick + 1 >= input_ticks && is_at_rest(&sim, &self.tile_map) {
+                self.state.pos = sim.steering.pos;
+                if !sim.steering.facing.x.is_neutral() {
+                    self.state.facing = sim.steering.facing.x;
+                }
+                self.state.stance = if sim.steering.is_climbing() {
+                    Stance::Climbing
+                } else {
+                    Stance::Grounded
+                };
+                return Some(());
+            }
+        }
+        None
+    }
+
+    /// The player passes through the given position. Collects keys and tools and checks whether
+    /// they reached the open door.
+    ///
+    /// The position is the center point of the player, like the translation of its `Transform`.
+    fn visit(&mut self, center: (f32, f32)) {
+        let puzzle = self.puzzle;
+        let dimens = puzzle.player.dimens;
+        self.state
+            .keys_left
+            .retain(|index| !puzzle.keys[*index].intersects(center, dimens));
+        if self.state.equipped.is_none() {
+            let tool = self
+                .state
+                .tools_left
+                .iter()
+                .copied()
+                .find(|index| puzzle.tools[*index].0.intersects(center, dimens));
+            if let Some(index) = tool {
+                self.state.tools_left.remove(&index);
+                self.state.equipped = Some(puzzle.tools[index].1);
+            }
+        }
+        if self.state.keys_left.is_empty() && puzzle.door.intersects(center, dimens) {
+            self.reached_door = true;
+        }
+    }
+}
+
+/// Returns true iff the player has stopped moving and will not start moving again without input.
+fn is_at_rest(sim: &PlayerSim, tile_map: &TileMap) -> bool {
+    let steering = &sim.steering;
+    !steering.is_mid_air()
+        && sim.velocity.x.abs() < f32::EPSILON
+        && sim.velocity.y.abs() < f32::EPSILON
+        && steering.pos == steering.destination
+        && (steering.is_climbing() || is_grounded(steering, tile_map))
+}
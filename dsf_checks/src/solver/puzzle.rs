@@ -0,0 +1,193 @@
+use std::collections::BTreeSet;
+use std::fmt;
+
+use dsf_core::components::{Direction1D, Pos};
+use dsf_core::levels::LevelSave;
+use dsf_core::resources::{Archetype, MovementConfig, TileDefinitions, TileMap, ToolType};
+
+/// Anything in the level that the player can collide with, such as a key, a tool or the door.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct PuzzleObject {
+    /// The anchor position of the object: its bottom-left tile.
+    pub pos: Pos,
+    /// Width and height of the object.
+    pub dimens: Pos,
+}
+
+impl PuzzleObject {
+    #[must_use]
+    pub fn new(pos: Pos, dimens: Pos) -> Self {
+        PuzzleObject { pos, dimens }
+    }
+
+    /// Returns true iff an entity with the given center point and dimensions collides with
+    /// this object. This mirrors the collision checks in the `KeyCollectionSystem`,
+    /// `PickupSystem` and `WinSystem`: the object's hitbox is a third of its size in each
+    /// direction from its center.
+    #[must_use]
+    pub fn intersects(&self, center: (f32, f32), dimens: Pos) -> bool {
+        let object_x = self.pos.x as f32 + self.dimens.x as f32 * 0.5;
+        let object_y = self.pos.y as f32 + self.dimens.y as f32 * 0.5;
+        (center.0 - object_x).abs() < dimens.x as f32 * 0.5 + self.dimens.x as f32 / 3.
+            && (center.1 - object_y).abs() < dimens.y as f32 * 0.5 + self.dimens.y as f32 / 3.
+    }
+}
+
+/// The static description of a level, as seen by the solver.
+/// Everything that can change during play is tracked in the `PuzzleState` instead.
+#[derive(Debug, Clone)]
+pub struct Puzzle {
+    /// The tile map as it is at the start of the level, before any blocks are broken.
+    pub tile_map: TileMap,
+    /// Movement parameters, the solver needs these to trace the arc of a jump.
+    pub movement: MovementConfig,
+    /// Where the player spawns, and how big the player is.
+    pub player: PuzzleObject,
+    /// All keys in the level. All of them must be collected before the door opens.
+    pub keys: Vec<PuzzleObject>,
+    /// All tools in the level, along with the kind of tool they are.
+    pub tools: Vec<(PuzzleObject, ToolType)>,
+    /// The exit door.
+    pub door: PuzzleObject,
+}
+
+impl Puzzle {
+    /// Extracts everything the solver needs to know from the level.
+    ///
+    /// A level can only be solved if it contains exactly one player and exactly one door.
+    /// Levels with pushable blocks are refused, because the solver does not push blocks.
+    pub fn new(
+        level: &LevelSave,
+        tile_defs: TileDefinitions,
+        movement: MovementConfig,
+    ) -> Result<Self, SolverError> {
+        let mut players = vec![];
+        let mut doors = vec![];
+        let mut keys = vec![];
+        let mut tools = vec![];
+        let mut pushables = 0;
+        // Iterate in a fixed order, so that the solver is deterministic.
+        let mut tiles = level.tiles.iter().collect::<Vec<_>>();
+        tiles.sort();
+        for (pos, key) in tiles {
+            let tile_def = level.tile_def(*pos, key, &tile_defs);
+            let object = PuzzleObject::new(*pos, tile_def.dimens);
+            match tile_def.archetype {
+                Some(Archetype::Player) => players.push(object),
+                Some(Archetype::Door) => doors.push(object),
+                Some(Archetype::Key) => keys.push(object),
+                Some(Archetype::Tool(tool_type)) => tools.push((object, tool_type)),
+                Some(Archetype::Pushable) => pushables += 1,
+                None => (),
+            }
+        }
+        if pushables > 0 {
+            return Err(SolverError::PushableBlocks(pushables));
+        }
+        let player = match players.len() {
+            1 => players[0],
+            0 => return Err(SolverError::MissingPlayer),
+            amount => return Err(SolverError::MultiplePlayers(amount)),
+        };
+        let door = match doors.len() {
+            1 => doors[0],
+            0 => return Err(SolverError::MissingDoor),
+            amount => return Err(SolverError::MultipleDoors(amount)),
+        };
+        Ok(Puzzle {
+            tile_map: TileMap::for_play(level, tile_defs),
+            movement,
+            player,
+            keys,
+            tools,
+            door,
+        })
+    }
+
+    /// The state of the puzzle at the very start of the level, before the player has had a chance
+    /// to do anything.
+    #[must_use]
+    pub fn initial_state(&self) -> PuzzleState {
+        PuzzleState {
+            pos: self.player.pos,
+            facing: Direction1D::Positive,
+            stance: Stance::Grounded,
+            equipped: None,
+            keys_left: (0..self.keys.len()).collect(),
+            tools_left: (0..self.tools.len()).collect(),
+            broken: BTreeSet::new(),
+            ladders: BTreeSet::new(),
+        }
+    }
+}
+
+/// How the player is positioned while they are at rest, waiting for the next input.
+/// These are the only two values of `SteeringMode` that the player can stand still in;
+/// jumping and falling are always resolved completely by a single move.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Stance {
+    Grounded,
+    Climbing,
+}
+
+/// A single node in the solver's search space. Two states are equal if and only if the player
+/// has exactly the same options available to them from that point onward.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct PuzzleState {
+    /// The player's discrete position.
+    pub pos: Pos,
+    /// The direction the player is facing along the x-axis. Determines where tools are used.
+    pub facing: Direction1D,
+    pub stance: Stance,
+    /// The tool currently equipped by the player.
+    pub equipped: Option<ToolType>,
+    /// Indices into `Puzzle::keys` of the keys that were not yet collected.
+    pub keys_left: BTreeSet<usize>,
+    /// Indices into `Puzzle::tools` of the tools that are still lying around in the level.
+    pub tools_left: BTreeSet<usize>,
+    /// Anchor positions of all tiles that were broken by tools so far.
+    pub broken: BTreeSet<Pos>,
+    /// Anchor positions of all ladder segments that were placed by tools so far.
+    pub ladders: BTreeSet<Pos>,
+}
+
+/// The reasons the solver may refuse to look at a level.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum SolverError {
+    /// The level has no player spawn point.
+    MissingPlayer,
+    /// The level has more than one player spawn point. Contains the number of players found.
+    MultiplePlayers(usize),
+    /// The level has no exit door.
+    MissingDoor,
+    /// The level has more than one exit door. Contains the number of doors found.
+    MultipleDoors(usize),
+    /// The level contains pushable blocks. The solver cannot push blocks, so it cannot tell
+    /// whether the level can be completed. Contains the number of pushable blocks found.
+    PushableBlocks(usize),
+    /// The movement config has a non-positive player speed, making it impossible to trace jumps.
+    InvalidMovementConfig,
+}
+
+impl fmt::Display for SolverError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SolverError::MissingPlayer => write!(f, "the level does not contain a player"),
+            SolverError::MultiplePlayers(amount) => {
+                write!(f, "the level contains {} players instead of one", amount)
+            }
+            SolverError::MissingDoor => write!(f, "the level does not contain an exit door"),
+            SolverError::MultipleDoors(amount) => {
+                write!(f, "the level contains {} doors instead of one", amount)
+            }
+            SolverError::PushableBlocks(amount) => write!(
+                f,
+                "the level contains {} pushable blocks, which the solver does not support",
+                amount
+            ),
+            SolverError::InvalidMovementConfig => {
+                write!(f, "the movement config must have a positive player speed")
+            }
+        }
+    }
+}
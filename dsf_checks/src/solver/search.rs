@@ -0,0 +1,171 @@
+use std::collections::{BTreeSet, HashMap, VecDeque};
+
+use dsf_core::components::Pos;
+use dsf_core::levels::LevelSave;
+use dsf_core::resources::{MovementConfig, TileDefinitions, TileMap, ToolType};
+use dsf_core::systems::place_ladder;
+
+use crate::solver::{
+    perform_action, settle_initial_state, Action, Puzzle, PuzzleState, SolverError,
+};
+
+/// Tweaks the behaviour of the solver.
+#[derive(Copy, Clone, Debug)]
+pub struct SolverConfig {
+    /// The movement parameters the game uses. Jumps are traced with these.
+    pub movement: MovementConfig,
+    /// The solver gives up after exploring this many distinct states.
+    pub max_states: usize,
+}
+
+impl SolverConfig {
+    #[must_use]
+    pub fn new(movement: MovementConfig) -> Self {
+        SolverConfig {
+            movement,
+            max_states: 250_000,
+        }
+    }
+}
+
+/// The verdict of the solver.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum SolverOutcome {
+    /// The level can be completed. Contains one of the shortest winning sequences of actions.
+    Solved(Vec<Action>),
+    /// Every state the player can reach with the solver's actions was explored and none of them
+    /// lead to the door. The actions cover every tap of a key and the same jump timings as
+    /// the jump reachability analysis, so this is a proof that the level cannot be completed.
+    Unsolvable { states_explored: usize },
+    /// The solver ran into `SolverConfig::max_states` before reaching a verdict, or the level
+    /// contains dynamite and none of the states the solver explored lead to the door.
+    Inconclusive { states_explored: usize },
+}
+
+impl SolverOutcome {
+    #[must_use]
+    pub fn is_solved(&self) -> bool {
+        matches!(self, SolverOutcome::Solved(_))
+    }
+}
+
+/// Convenience function that builds a `Puzzle` from the level and solves it.
+pub fn solve_level(
+    level: &LevelSave,
+    tile_defs: TileDefinitions,
+    config: SolverConfig,
+) -> Result<SolverOutcome, SolverError> {
+    if config.movement.player_speed <= 0. {
+        return Err(SolverError::InvalidMovementConfig);
+    }
+    let puzzle = Puzzle::new(level, tile_defs, config.movement)?;
+    Ok(solve(&puzzle, config))
+}
+
+/// Performs a breadth-first search over all states the player can reach in the given puzzle.
+///
+/// Because the search is breadth-first, a returned solution never uses more actions than needed.
+#[must_use]
+pub fn solve(puzzle: &Puzzle, config: SolverConfig) -> SolverOutcome {
+    let start = match settle_initial_state(puzzle, &puzzle.initial_state()) {
+        Some(transition) if transition.reached_door => return SolverOutcome::Solved(vec![]),
+        Some(transition) => transition.state,
+        // The player falls forever the moment the level starts.
+        None => return SolverOutcome::Unsolvable { states_explored: 1 },
+    };
+    // For each visited state, the state it was reached from and the action that got it there.
+    let mut parents: HashMap<PuzzleState, Option<(PuzzleState, Action)>> = HashMap::new();
+    let mut queue = VecDeque::new();
+    let mut tile_maps = TileMapCache::new(&puzzle.tile_map);
+    let actions = Action::all(&puzzle.movement);
+    parents.insert(start.clone(), None);
+    queue.push_back(start);
+    while let Some(state) = queue.pop_front() {
+        if parents.len() >= config.max_states {
+            return SolverOutcome::Inconclusive {
+                states_explored: parents.len(),
+            };
+        }
+        let tile_map = tile_maps.get(&state);
+        for action in actions.iter().copied() {
+            let transition = match perform_action(puzzle, tile_map, &state, action) {
+                Some(transition) => transition,
+                None => continue,
+            };
+            if transition.reached_door {
+                let mut actions = backtrack(&parents, &state);
+                actions.reverse();
+                actions.push(action);
+                return SolverOutcome::Solved(actions);
+            }
+            if !parents.contains_key(&transition.state) {
+                parents.insert(transition.state.clone(), Some((state.clone(), action)));
+                queue.push_back(transition.state);
+            }
+        }
+    }
+    // The solver only lets the player wait for dynamite to explode, it never walks on while the
+    // fuse burns. Those states were not explored, so this is no proof.
+    let has_dynamite = puzzle
+        .tools
+        .iter()
+        .any(|(_, tool_type)| matches!(tool_type, ToolType::Dynamite { .. }));
+    if has_dynamite {
+        SolverOutcome::Inconclusive {
+            states_explored: parents.len(),
+        }
+    } else {
+        SolverOutcome::Unsolvable {
+            states_explored: parents.len(),
+        }
+    }
+}
+
+/// Returns the actions that lead to the given state, starting with the last action.
+fn backtrack(
+    parents: &HashMap<PuzzleState, Option<(PuzzleState, Action)>>,
+    state: &PuzzleState,
+) -> Vec<Action> {
+    let mut actions = vec![];
+    let mut current = state;
+    while let Some(Some((parent, action))) = parents.get(current) {
+        actions.push(*action);
+        current = parent;
+    }
+    actions
+}
+
+/// Keeps one `TileMap` around for every combination of broken blocks and placed ladders
+/// encountered during the search. There are usually only a handful of those, so this is much
+/// cheaper than rebuilding the tile map for every state.
+struct TileMapCache<'a> {
+    original: &'a TileMap,
+    cache: HashMap<(BTreeSet<Pos>, BTreeSet<Pos>), TileMap>,
+}
+
+impl<'a> TileMapCache<'a> {
+    fn new(original: &'a TileMap) -> Self {
+        TileMapCache {
+            original,
+            cache: HashMap::new(),
+        }
+    }
+
+    fn get(&mut self, state: &PuzzleState) -> &TileMap {
+        if state.broken.is_empty() && state.ladders.is_empty() {
+            return self.original;
+        }
+        let original = self.original;
+        let key = (state.broken.clone(), state.ladders.clone());
+        self.cache.entry(key).or_insert_with(|| {
+            let mut tile_map = original.clone();
+            for pos in &state.broken {
+                tile_map.remove_tile(*pos);
+            }
+            for pos in &state.ladders {
+                place_ladder(&mut tile_map, *pos);
+            }
+            tile_map
+        })
+    }
+}
@@ -14,17 +14,20 @@ use amethyst::{GameData, SimpleState, SimpleTrans};
 
 use dsf_precompile::AnimationId;
 
-use crate::components::MovementTest;
-
+use crate::scenarios::{load_scenarios, Scenario};
 use crate::states::setup_test;
 use crate::systems;
 use amethyst::core::ecs::{Dispatcher, DispatcherBuilder};
 use dsf_core::entities::create_camera;
 use dsf_core::resources::{History, UiHandles, UiType};
 use dsf_core::states::window_event_handler;
+use dsf_core::utility::files::get_assets_dir;
 
+/// Lets you try out the movement scenarios by hand. Press a number key to load the level of the
+/// corresponding scenario, in order of file name.
 pub struct MovementTestState {
     dispatcher: Dispatcher<'static, 'static>,
+    scenarios: Vec<Scenario>,
 }
 
 impl Default for MovementTestState {
@@ -37,6 +40,7 @@ impl Default for MovementTestState {
                     &[],
                 )
                 .build(),
+            scenarios: vec![],
         }
     }
 }
@@ -48,6 +52,15 @@ impl SimpleState for MovementTestState {
         UiHandles::add_ui(UiType::Fps, data.world);
         create_camera(data.world);
         data.world.insert(History::default());
+        let scenarios_dir = get_assets_dir().join("tests/scenarios/");
+        self.scenarios = load_scenarios(&scenarios_dir)
+            .unwrap_or_else(|error| {
+                error!("Failed to load movement scenarios: {:?}", error);
+                vec![]
+            })
+            .into_iter()
+            .map(|(_, scenario)| scenario)
+            .collect();
     }
 
     fn on_stop(&mut self, data: StateData<'_, GameData<'_, '_>>) {
@@ -74,26 +87,14 @@ impl SimpleState for MovementTestState {
             }
             // Ui event. Button presses, mouse hover, etc...
             StateEvent::Ui(_) => Trans::None,
-            StateEvent::Input(input_event) => {
-                match input_event {
-                    InputEvent::KeyPressed {
-                        key_code: VirtualKeyCode::Key1,
-                        ..
-                    } => setup_test(MovementTest::Jump2Wide, data.world),
-                    InputEvent::KeyPressed {
-                        key_code: VirtualKeyCode::Key2,
-                        ..
-                    } => setup_test(MovementTest::Jump4Wide, data.world),
-                    InputEvent::KeyPressed {
-                        key_code: VirtualKeyCode::Key3,
-                        ..
-                    } => {
-                        //TODO:...
-                    }
-                    _ => (),
-                };
+            StateEvent::Input(InputEvent::KeyPressed { key_code, .. }) => {
+                if let Some(scenario) = scenario_index(key_code).and_then(|i| self.scenarios.get(i))
+                {
+                    setup_test(scenario, data.world);
+                }
                 Trans::None
             }
+            StateEvent::Input(_) => Trans::None,
         }
     }
 
@@ -124,3 +125,20 @@ impl SimpleState for MovementTestState {
         Trans::None
     }
 }
+
+/// Maps the number keys 1 through 9 to the first nine scenarios.
+fn scenario_index(key_code: VirtualKeyCode) -> Option<usize> {
+    let index = match key_code {
+        VirtualKeyCode::Key1 => 0,
+        VirtualKeyCode::Key2 => 1,
+        VirtualKeyCode::Key3 => 2,
+        VirtualKeyCode::Key4 => 3,
+        VirtualKeyCode::Key5 => 4,
+        VirtualKeyCode::Key6 => 5,
+        VirtualKeyCode::Key7 => 6,
+        VirtualKeyCode::Key8 => 7,
+        VirtualKeyCode::Key9 => 8,
+        _ => return None,
+    };
+    Some(index)
+}
@@ -1,12 +1,14 @@
 use amethyst::ecs::{prelude::World, Entities, Join, ReadStorage};
 
-use crate::components::{MovementTest, MovementTestScopeTag};
+use crate::components::MovementTestScopeTag;
+use crate::scenarios::Scenario;
 use dsf_core::levels::load_level;
 use dsf_core::utility::files::get_assets_dir;
 
-pub fn setup_test(test: MovementTest, world: &mut World) {
+/// Loads the level that the given scenario plays in, so it can be tried out by hand.
+pub fn setup_test(scenario: &Scenario, world: &mut World) {
     clear_previous_test(world);
-    load_level_from_file(&test, world);
+    load_level_from_file(scenario, world);
 }
 
 fn clear_previous_test(world: &mut World) {
@@ -21,11 +23,8 @@ fn clear_previous_test(world: &mut World) {
     );
 }
 
-fn load_level_from_file(test: &MovementTest, world: &mut World) {
-    let file_name = match test {
-        MovementTest::Jump2Wide => "jump_2_wide.ron",
-        MovementTest::Jump4Wide => "jump_4_wide.ron",
-    };
-    let level_file = get_assets_dir().join("tests/").join(file_name);
+fn load_level_from_file(scenario: &Scenario, world: &mut World) {
+    info!("Loading scenario: {}", scenario.description);
+    let level_file = get_assets_dir().join("tests/").join(&scenario.level);
     load_level(&level_file, world).expect("Failed to load level!");
 }
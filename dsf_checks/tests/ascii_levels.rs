@@ -0,0 +1,87 @@
+//! Checks that levels survive a round trip through the ASCII level format, and that ASCII levels
+//! can be written inside tests.
+
+use amethyst::config::Config;
+use dsf_checks::level_files::{assets_dir, collect_level_files, levels_dir};
+use dsf_core::components::Pos;
+use dsf_core::levels::{parse_ascii_level, write_ascii_level, AsciiLevelError, LevelSave};
+use dsf_core::resources::{TileDefinitions, WorldBounds};
+
+#[test]
+fn all_levels_round_trip() {
+    let tile_defs = TileDefinitions::load(assets_dir().join("world/tile_references.ron"))
+        .expect("Failed to load tile definitions.");
+    let files = collect_level_files(vec![levels_dir(), assets_dir().join("tests")])
+        .expect("Failed to list levels.");
+    for file in files {
+        let level = LevelSave::load(&file).expect("Failed to load level.");
+        let text = write_ascii_level(&level, Some(&tile_defs)).expect("Failed to draw level.");
+        let parsed = parse_ascii_level(&text).expect("Failed to parse level.");
+        assert_eq!(parsed, level, "{} did not round trip.", file.display());
+    }
+}
+
+#[test]
+fn level_is_read_from_a_sketch() {
+    let level = parse_ascii_level(
+        "
+        // The player jumps over the gap to reach the key.
+        title: Sketch
+        description: Two\\nlines
+        origin: -3 -2
+        legend:
+        @ Key
+        grid:
+        .++....@
+        .P+.....
+        ###..%%#
+        properties:
+        2 -2 (climbable: true)
+        ",
+    )
+    .expect("Failed to parse level.");
+    assert_eq!(level.world_bounds, WorldBounds::new(-3, -2, 8, 3));
+    assert_eq!(level.metadata.title, "Sketch");
+    assert_eq!(level.metadata.description, "Two\nlines");
+    let key_at = |x, y| level.tiles.get(&Pos::new(x, y)).map(String::as_str);
+    assert_eq!(key_at(-2, -1), Some("Player"));
+    assert_eq!(key_at(4, 0), Some("Key"));
+    assert_eq!(key_at(2, -2), Some("Block2"));
+    assert_eq!(key_at(4, -2), Some("Block1"));
+    assert_eq!(level.tiles.len(), 8);
+    assert_eq!(
+        level.properties.get(&Pos::new(2, -2)).map(|p| p.climbable),
+        Some(Some(true))
+    );
+}
+
+#[test]
+fn mistakes_are_reported() {
+    assert_eq!(
+        parse_ascii_level("grid:\n..\n.?"),
+        Err(AsciiLevelError::UnknownCharacter {
+            line: 3,
+            character: '?'
+        })
+    );
+    assert_eq!(
+        parse_ascii_level("grid:\n..\n..."),
+        Err(AsciiLevelError::RaggedGrid { line: 3 })
+    );
+    assert_eq!(
+        parse_ascii_level("title: No grid"),
+        Err(AsciiLevelError::MissingGrid)
+    );
+    let mut level = LevelSave {
+        world_bounds: WorldBounds::new(0, 0, 2, 2),
+        ..LevelSave::default()
+    };
+    level.tiles.insert(Pos::new(5, 0), "Block1".to_string());
+    assert_eq!(
+        write_ascii_level(&level, None),
+        Err(AsciiLevelError::OutOfBounds {
+            pos: Pos::new(5, 0),
+            key: "Block1".to_string()
+        })
+    );
+}
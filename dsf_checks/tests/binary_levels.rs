@@ -0,0 +1,137 @@
+//! Checks that the binary level format holds exactly the same information as the RON format.
+
+use std::fs;
+
+use dsf_checks::level_files::{collect_level_files, levels_dir};
+use dsf_core::components::Pos;
+use dsf_core::levels::{
+    binary_level_version, decode_level, encode_level, upgrade_level_file, Difficulty,
+    LevelFormatError, LevelMetadata, LevelSave, CURRENT_LEVEL_VERSION,
+};
+use dsf_core::resources::{TileProperties, WorldBounds};
+
+#[test]
+fn all_levels_round_trip() {
+    for file in collect_level_files(vec![levels_dir()]).expect("Failed to list levels.") {
+        let level = LevelSave::load(&file).expect("Failed to load level.");
+        let bytes = encode_level(&level);
+        let decoded = decode_level(&bytes).expect("Failed to decode level.");
+        assert_eq!(decoded, level, "{} did not round trip.", file.display());
+        let ron_size = fs::metadata(&file)
+            .expect("Failed to read file size.")
+            .len();
+        assert!(
+            (bytes.len() as u64) < ron_size,
+            "{} is not smaller in the binary format.",
+            file.display()
+        );
+    }
+}
+
+#[test]
+fn everything_round_trips() {
+    let mut level = LevelSave {
+        world_bounds: WorldBounds::new(-5, -7, 1000, 20),
+        metadata: LevelMetadata {
+            title: "Tïtle".to_string(),
+            author: "Author".to_string(),
+            description: "Some\nlines".to_string(),
+            difficulty: Some(Difficulty::Expert),
+            par_moves: Some(0),
+            par_seconds: Some(u32::MAX),
+        },
+        ..LevelSave::default()
+    };
+    for x in -5..3 {
+        level.tiles.insert(Pos::new(x, -7), "Block1".to_string());
+    }
+    level.tiles.insert(Pos::new(990, 12), "Door".to_string());
+    level.tiles.insert(Pos::new(0, 0), "Player".to_string());
+    level.properties.insert(
+        Pos::new(0, 0),
+        TileProperties {
+            climbable: Some(true),
+            ..TileProperties::default()
+        },
+    );
+    let decoded = decode_level(&encode_level(&level)).expect("Failed to decode level.");
+    assert_eq!(decoded, level);
+
+    let empty = LevelSave::default();
+    let decoded = decode_level(&encode_level(&empty)).expect("Failed to decode empty level.");
+    assert_eq!(decoded, empty);
+}
+
+#[test]
+fn corrupt_data_is_rejected() {
+    let mut level = LevelSave::default();
+    level.tiles.insert(Pos::new(1, 1), "Block1".to_string());
+    let bytes = encode_level(&level);
+    for length in 0..bytes.len() {
+        match decode_level(&bytes[..length]) {
+            Err(LevelFormatError::InvalidBinary(_)) => (),
+            other => panic!("Truncated level was not rejected: {:?}", other),
+        }
+    }
+    assert!(decode_level(b"RON!").is_err());
+}
+
+/// Encodes a level without metadata or properties in the given older version of the format. The
+/// current version only adds sections to the older ones, so those are cut out.
+fn encode_old_level(level: &LevelSave, version: u8) -> Vec<u8> {
+    assert!(level.properties.is_empty() && level.metadata == LevelMetadata::default());
+    let current = encode_level(level);
+    // The magic bytes and the version are followed by six empty metadata fields. The properties
+    // section is a single zero at the end.
+    let mut bytes = b"DSFL".to_vec();
+    bytes.push(version);
+    if version >= 2 {
+        bytes.extend_from_slice(&[0; 6]);
+    }
+    bytes.extend_from_slice(&current[11..current.len() - 1]);
+    bytes
+}
+
+#[test]
+fn older_versions_are_migrated() {
+    let mut level = LevelSave {
+        world_bounds: WorldBounds::new(-2, -3, 10, 12),
+        ..LevelSave::default()
+    };
+    level.tiles.insert(Pos::new(1, 2), "Block1".to_string());
+    for version in 0..CURRENT_LEVEL_VERSION as u8 {
+        let bytes = encode_old_level(&level, version);
+        assert_eq!(binary_level_version(&bytes).ok(), Some(u32::from(version)));
+        let decoded = decode_level(&bytes).expect("Failed to decode older level.");
+        assert_eq!(decoded, level, "Version {} was not migrated.", version);
+    }
+}
+
+#[test]
+fn newer_version_is_rejected() {
+    let mut bytes = encode_level(&LevelSave::default());
+    bytes[4] = CURRENT_LEVEL_VERSION as u8 + 1;
+    match decode_level(&bytes) {
+        Err(LevelFormatError::UnsupportedVersion(version)) => {
+            assert_eq!(version, CURRENT_LEVEL_VERSION + 1)
+        }
+        other => panic!("Expected an unsupported version error, got {:?}", other),
+    }
+}
+
+#[test]
+fn older_binary_files_are_upgraded() {
+    let mut level = LevelSave::default();
+    level.tiles.insert(Pos::new(0, 0), "Door".to_string());
+    let file = std::env::temp_dir().join(format!("dsf_upgrade_{}.dsfl", std::process::id()));
+    fs::write(&file, encode_old_level(&level, 2)).expect("Failed to write level.");
+    assert!(upgrade_level_file(&file).expect("Failed to upgrade level."));
+    let bytes = fs::read(&file).expect("Failed to read upgraded level.");
+    assert_eq!(
+        binary_level_version(&bytes).ok(),
+        Some(CURRENT_LEVEL_VERSION)
+    );
+    assert_eq!(decode_level(&bytes).ok(), Some(level));
+    assert!(!upgrade_level_file(&file).expect("Failed to check upgraded level."));
+    fs::remove_file(&file).expect("Failed to clean up level.");
+}
@@ -0,0 +1,11 @@
+//! Helpers shared by the integration tests.
+
+use amethyst::config::Config;
+use dsf_checks::level_files::assets_dir;
+use dsf_core::resources::TileDefinitions;
+
+/// Loads the tile definitions that the game uses.
+pub fn tile_defs() -> TileDefinitions {
+    TileDefinitions::load(assets_dir().join("world/tile_references.ron"))
+        .expect("Failed to load tile definitions.")
+}
@@ -0,0 +1,113 @@
+//! Checks where dynamite is placed, when it explodes and exactly which tiles its explosion breaks.
+
+mod common;
+
+use amethyst::ecs::{Builder, RunNow, World, WorldExt};
+use amethyst::shrev::EventChannel;
+use dsf_core::components::{Block, Direction1D, Dynamite, Pos, Steering};
+use dsf_core::levels::LevelSave;
+use dsf_core::resources::{TileMap, TileProperties};
+use dsf_core::systems::{dynamite_position, explosion_targets, DynamiteSystem, SoundEvent};
+
+use crate::common::tile_defs;
+
+/// Builds a tile map from breakable blocks and blocks that are made invulnerable.
+fn tile_map(breakable: &[(i32, i32)], invulnerable: &[(i32, i32)]) -> TileMap {
+    let mut level = LevelSave::default();
+    let properties = TileProperties::parse("(sturdiness: Invulnerable)").expect("Failed to parse.");
+    for (x, y) in breakable.iter().chain(invulnerable) {
+        level.tiles.insert(Pos::new(*x, *y), "Block1".to_string());
+    }
+    for (x, y) in invulnerable {
+        level
+            .properties
+            .insert(Pos::new(*x, *y), properties.clone());
+    }
+    TileMap::for_play(&level, tile_defs())
+}
+
+#[test]
+fn explosion_breaks_a_cross_that_stops_at_invulnerable_tiles() {
+    let tile_map = tile_map(
+        &[
+            (6, 5),
+            (7, 5),
+            (8, 5),
+            (3, 5),
+            (5, 7),
+            (5, 8),
+            (5, 4),
+            (6, 6),
+        ],
+        &[(4, 5), (5, 3)],
+    );
+    assert_eq!(
+        explosion_targets(Pos::new(5, 5), 2, &tile_map),
+        vec![
+            Pos::new(6, 5),
+            Pos::new(7, 5),
+            Pos::new(5, 7),
+            Pos::new(5, 4)
+        ]
+    );
+}
+
+#[test]
+fn explosion_breaks_its_centre_and_nothing_without_radius() {
+    let tile_map = tile_map(&[(5, 5), (6, 5)], &[]);
+    assert_eq!(
+        explosion_targets(Pos::new(5, 5), 0, &tile_map),
+        vec![Pos::new(5, 5)]
+    );
+    assert!(explosion_targets(Pos::new(0, 0), 3, &tile_map).is_empty());
+}
+
+#[test]
+fn dynamite_is_placed_at_the_feet_on_the_facing_side() {
+    let mut steering = Steering::new(Pos::new(3, 1), Pos::new(2, 2));
+    assert_eq!(dynamite_position(&steering), Pos::new(4, 1));
+    steering.facing.x = Direction1D::Negative;
+    assert_eq!(dynamite_position(&steering), Pos::new(3, 1));
+}
+
+#[test]
+fn dynamite_explodes_when_its_fuse_has_burnt_down() {
+    let mut world = World::new();
+    world.register::<Block>();
+    world.register::<Dynamite>();
+    world.insert(EventChannel::<SoundEvent>::new());
+    world.insert(tile_map(&[(6, 5)], &[]));
+    let block = world
+        .create_entity()
+        .with(Block {
+            pos: Pos::new(6, 5),
+        })
+        .build();
+    let dynamite = world
+        .create_entity()
+        .with(Dynamite::new(Pos::new(5, 5), 1, 3))
+        .build();
+    for fuse_left in (1..3).rev() {
+        DynamiteSystem.run_now(&world);
+        world.maintain();
+        assert_eq!(
+            world
+                .read_storage::<Dynamite>()
+                .get(dynamite)
+                .map(|placed| placed.fuse),
+            Some(fuse_left)
+        );
+        assert!(world
+            .read_resource::<TileMap>()
+            .get_tile(Pos::new(6, 5))
+            .is_some());
+    }
+    DynamiteSystem.run_now(&world);
+    world.maintain();
+    assert!(!world.is_alive(dynamite));
+    assert!(!world.is_alive(block));
+    assert!(world
+        .read_resource::<TileMap>()
+        .get_tile(Pos::new(6, 5))
+        .is_none());
+}
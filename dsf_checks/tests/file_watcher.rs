@@ -0,0 +1,57 @@
+//! Checks that the file watcher used for hot reloading notices changed files.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+use dsf_core::resources::FileWatcher;
+
+/// Writes the file and moves its modification time ahead, so the change is noticed even if the
+/// file system only stores modification times with a coarse precision.
+fn edit(file: &Path, contents: &str) {
+    fs::write(file, contents).expect("Failed to write file.");
+    fs::OpenOptions::new()
+        .write(true)
+        .open(file)
+        .and_then(|opened| opened.set_modified(SystemTime::now() + Duration::from_secs(10)))
+        .expect("Failed to set the modification time.");
+}
+
+fn temp_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("dsf_{}_{}", name, std::process::id()));
+    fs::create_dir_all(&dir).expect("Failed to create temporary directory.");
+    dir
+}
+
+#[test]
+fn changed_files_are_reported_once() {
+    let dir = temp_dir("file_watcher");
+    let (edited, untouched, created) = (dir.join("a.ron"), dir.join("b.ron"), dir.join("c.ron"));
+    fs::write(&edited, "()").expect("Failed to write file.");
+    fs::write(&untouched, "()").expect("Failed to write file.");
+
+    let mut watcher = FileWatcher::with_poll_interval(Duration::from_secs(0));
+    watcher.watch(vec![edited.clone(), untouched.clone(), created.clone()]);
+    assert_eq!(watcher.changed_files(), Vec::<PathBuf>::new());
+
+    edit(&edited, "(changed: true)");
+    fs::write(&created, "()").expect("Failed to write file.");
+    assert_eq!(watcher.changed_files(), vec![edited, created]);
+    assert_eq!(watcher.changed_files(), Vec::<PathBuf>::new());
+
+    fs::remove_dir_all(&dir).expect("Failed to clean up temporary directory.");
+}
+
+#[test]
+fn files_are_not_checked_before_the_poll_interval_passed() {
+    let dir = temp_dir("file_watcher_interval");
+    let edited = dir.join("a.ron");
+    fs::write(&edited, "()").expect("Failed to write file.");
+
+    let mut watcher = FileWatcher::with_poll_interval(Duration::from_secs(3600));
+    watcher.watch(vec![edited.clone()]);
+    edit(&edited, "(changed: true)");
+    assert_eq!(watcher.changed_files(), Vec::<PathBuf>::new());
+
+    fs::remove_dir_all(&dir).expect("Failed to clean up temporary directory.");
+}
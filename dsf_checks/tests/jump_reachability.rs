@@ -0,0 +1,41 @@
+//! Checks the boundaries of the jump reachability table. Print the whole table with
+//! `cargo run -p dsf_checks -- jumps`.
+
+use std::path::PathBuf;
+
+use amethyst::config::Config;
+use dsf_checks::reachability::{analyse_jumps, Reachability, ReachabilityConfig};
+use dsf_core::components::Pos;
+use dsf_core::resources::MovementConfig;
+
+#[test]
+fn jump_reachability_table() {
+    let movement = MovementConfig::load(
+        PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("../assets/config/movement.ron"),
+    )
+    .expect("Failed to load movement config.");
+    let table = analyse_jumps(&movement, Pos::new(2, 2), &ReachabilityConfig::default());
+    let reach = |gap, height| {
+        table
+            .get(gap, height)
+            .map(|entry| entry.reachability)
+            .expect("Missing table entry.")
+    };
+    // Jumping to a ledge at the same height: up to 3 wide is safe, 4 and 5 wide depend on the
+    // timing, and nothing wider can be crossed.
+    for gap in 0..=3 {
+        assert_eq!(reach(gap, 0), Reachability::Always, "gap {}", gap);
+    }
+    for gap in 4..=5 {
+        assert_eq!(reach(gap, 0), Reachability::Sometimes, "gap {}", gap);
+    }
+    for gap in 6..=7 {
+        assert_eq!(reach(gap, 0), Reachability::Never, "gap {}", gap);
+    }
+    // A ledge 2 tiles up can always be reached across a 2-wide gap, but no ledge is reachable
+    // 3 tiles up.
+    assert_eq!(reach(2, 2), Reachability::Always);
+    for gap in 0..=7 {
+        assert_eq!(reach(gap, 3), Reachability::Never, "gap {}", gap);
+    }
+}
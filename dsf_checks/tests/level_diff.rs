@@ -0,0 +1,70 @@
+//! Checks the semantic diff between two versions of a level.
+
+use dsf_core::components::Pos;
+use dsf_core::levels::{diff_levels, parse_ascii_level, LevelChange};
+use dsf_core::resources::TileProperties;
+
+#[test]
+fn diff_lists_every_change_and_can_be_applied() {
+    let before = parse_ascii_level(
+        "
+        title: Before
+        grid:
+        ......
+        .++..k
+        .P+...
+        ######
+        ",
+    )
+    .expect("Failed to parse level.");
+    let mut after = parse_ascii_level(
+        "
+        title: After
+        grid:
+        ......
+        .++...
+        .P+..D
+        ###%##
+        ",
+    )
+    .expect("Failed to parse level.");
+    after.properties.insert(
+        Pos::new(0, 0),
+        TileProperties {
+            climbable: Some(true),
+            ..TileProperties::default()
+        },
+    );
+
+    let diff = diff_levels(&before, &after);
+    assert_eq!(diff.changes.len(), 5, "{}", diff);
+    assert!(matches!(diff.changes[0], LevelChange::Metadata { .. }));
+    assert_eq!(
+        diff.changes[1],
+        LevelChange::ReplaceTile {
+            pos: Pos::new(3, 0),
+            before: "Block1".to_string(),
+            after: "Block2".to_string(),
+        }
+    );
+    assert_eq!(
+        diff.changes[2],
+        LevelChange::AddTile {
+            pos: Pos::new(5, 1),
+            key: "Door".to_string(),
+        }
+    );
+    assert_eq!(
+        diff.changes[3],
+        LevelChange::RemoveTile {
+            pos: Pos::new(5, 2),
+            key: "Key".to_string(),
+        }
+    );
+    assert!(matches!(diff.changes[4], LevelChange::Properties { .. }));
+
+    let mut patched = before.clone();
+    diff.apply_to(&mut patched);
+    assert_eq!(patched, after);
+    assert!(diff_levels(&after, &after).is_empty());
+}
@@ -0,0 +1,94 @@
+//! Checks that level files of older versions of the format are migrated when they are loaded.
+
+use dsf_core::components::Pos;
+use dsf_core::levels::{parse_level, Difficulty, LevelFormatError, CURRENT_LEVEL_VERSION};
+
+#[test]
+fn unversioned_level_is_migrated() {
+    let level = parse_level(
+        "(
+            world_bounds: (pos: (x: -2, y: -3), dimens: (x: 10, y: 12)),
+            tiles: {(x: 1, y: 2): \"Block1\"},
+        )",
+    )
+    .expect("Failed to parse version 0 level.");
+    assert_eq!(level.version, CURRENT_LEVEL_VERSION);
+    assert_eq!(level.world_bounds.pos, Pos::new(-2, -3));
+    assert_eq!(level.world_bounds.dimens, Pos::new(10, 12));
+    assert_eq!(
+        level.tiles.get(&Pos::new(1, 2)).map(String::as_str),
+        Some("Block1")
+    );
+}
+
+#[test]
+fn level_without_metadata_is_migrated() {
+    let level = parse_level("(version: 1, tiles: {(x: 0, y: 0): \"Door\"})")
+        .expect("Failed to parse version 1 level.");
+    assert_eq!(level.version, CURRENT_LEVEL_VERSION);
+    assert_eq!(level.tiles.len(), 1);
+    assert_eq!(level.metadata.display_title("door.ron"), "door.ron");
+    assert!(level.properties.is_empty());
+}
+
+#[test]
+fn version_1_level_cannot_have_metadata() {
+    let result = parse_level("(version: 1, metadata: (title: \"Tunnels\"))");
+    assert!(matches!(result, Err(LevelFormatError::Parse(_))));
+}
+
+#[test]
+fn level_without_properties_is_migrated() {
+    let level = parse_level(
+        "(
+            version: 2,
+            metadata: (title: \"Tunnels\"),
+            tiles: {(x: 0, y: 0): \"Door\"},
+        )",
+    )
+    .expect("Failed to parse version 2 level.");
+    assert_eq!(level.version, CURRENT_LEVEL_VERSION);
+    assert_eq!(level.metadata.title, "Tunnels");
+    assert_eq!(level.tiles.len(), 1);
+    assert!(level.properties.is_empty());
+}
+
+#[test]
+fn version_2_level_cannot_have_properties() {
+    let result = parse_level("(version: 2, properties: {(x: 0, y: 0): (climbable: true)})");
+    assert!(matches!(result, Err(LevelFormatError::Parse(_))));
+}
+
+#[test]
+fn current_level_is_read_as_is() {
+    let level = parse_level(&format!(
+        "(version: {}, tiles: {{(x: 0, y: 0): \"Door\"}})",
+        CURRENT_LEVEL_VERSION
+    ))
+    .expect("Failed to parse current level.");
+    assert_eq!(level.version, CURRENT_LEVEL_VERSION);
+    assert_eq!(level.tiles.len(), 1);
+}
+
+#[test]
+fn newer_level_is_rejected() {
+    let result = parse_level(&format!("(version: {})", CURRENT_LEVEL_VERSION + 1));
+    match result {
+        Err(LevelFormatError::UnsupportedVersion(version)) => {
+            assert_eq!(version, CURRENT_LEVEL_VERSION + 1)
+        }
+        other => panic!("Expected an unsupported version error, got {:?}", other),
+    }
+}
+
+#[test]
+fn metadata_is_optional() {
+    let level = parse_level(&format!(
+        "(version: {}, metadata: (title: \"Tunnels\", difficulty: Some(Hard), par_moves: Some(12)))",
+        CURRENT_LEVEL_VERSION
+    ))
+    .expect("Failed to parse level with metadata.");
+    assert_eq!(level.metadata.display_title("tunnels.ron"), "Tunnels");
+    assert_eq!(level.metadata.difficulty, Some(Difficulty::Hard));
+    assert_eq!(level.metadata.details(), "Difficulty: Hard\nPar: 12 moves");
+}
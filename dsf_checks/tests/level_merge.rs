@@ -0,0 +1,69 @@
+//! Checks the three-way merge of levels.
+
+mod common;
+
+use dsf_core::components::Pos;
+use dsf_core::levels::{merge_levels, parse_ascii_level, LevelSave, MergeConflict};
+
+use crate::common::tile_defs;
+
+fn level(text: &str) -> LevelSave {
+    parse_ascii_level(text).expect("Failed to parse level.")
+}
+
+#[test]
+fn separate_edits_are_merged() {
+    let base = level("title: Base\ngrid:\n......\n.++...\n.P+...\n######");
+    let mut ours = level("title: Ours\ngrid:\n......\n.++...\n.P+.k.\n######");
+    let theirs = level("title: Base\ngrid:\n#.....\n.++...\n.P+...\n##%###");
+    ours.metadata.par_moves = Some(3);
+    let outcome = merge_levels(&base, &ours, &theirs, &tile_defs());
+    assert_eq!(outcome.conflicts, vec![]);
+    let mut expected = level("title: Ours\ngrid:\n#.....\n.++...\n.P+.k.\n##%###");
+    expected.metadata.par_moves = Some(3);
+    assert_eq!(outcome.level, expected);
+}
+
+#[test]
+fn conflicting_edits_are_reported() {
+    let base = level("title: Base\ngrid:\n......\n......\n......\n######");
+    let ours = level("title: Ours\ngrid:\n......\n.++...\n.P+...\n#%####");
+    let theirs = level("title: Theirs\ngrid:\n......\n..k...\n......\n#.####");
+    let outcome = merge_levels(&base, &ours, &theirs, &tile_defs());
+    assert_eq!(
+        outcome.conflicts,
+        vec![
+            MergeConflict::Metadata { field: "title" },
+            MergeConflict::Tile {
+                pos: Pos::new(1, 0),
+                ours: Some("Block2".to_string()),
+                theirs: None,
+            },
+            MergeConflict::Overlap {
+                ours: Pos::new(1, 1),
+                theirs: Pos::new(2, 2),
+            },
+        ]
+    );
+    // Where the sides conflict, the merged level is our version.
+    assert_eq!(outcome.level, ours);
+}
+
+#[test]
+fn overlapping_tiles_lose_their_properties() {
+    let base = level("grid:\n......\n......\n......\n######");
+    let ours = level("grid:\n......\n.++...\n.P+...\n######");
+    let theirs = level(concat!(
+        "grid:\n......\n..k...\n......\n######\n",
+        "properties:\n2 2 (climbable: true)",
+    ));
+    let outcome = merge_levels(&base, &ours, &theirs, &tile_defs());
+    assert_eq!(
+        outcome.conflicts,
+        vec![MergeConflict::Overlap {
+            ours: Pos::new(1, 1),
+            theirs: Pos::new(2, 2),
+        }]
+    );
+    assert_eq!(outcome.level, ours);
+}
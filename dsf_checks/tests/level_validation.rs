@@ -0,0 +1,79 @@
+//! Validates every level in `assets/world/levels/`, and checks that the validation catches the
+//! issues it is meant to catch.
+
+mod common;
+
+use std::fs;
+
+use dsf_checks::level_files::levels_dir;
+use dsf_core::components::Pos;
+use dsf_core::levels::{validate_level, LevelIssue, LevelSave};
+use dsf_core::resources::{TileProperties, WorldBounds};
+
+use crate::common::tile_defs;
+
+#[test]
+fn all_levels_are_valid() {
+    let tile_defs = tile_defs();
+    let mut invalid = vec![];
+    for entry in fs::read_dir(levels_dir()).expect("Failed to read levels directory.") {
+        let path = entry.expect("Failed to read directory entry.").path();
+        if path.file_stem().map_or(false, |stem| stem == "auto_save") {
+            continue;
+        }
+        let level = LevelSave::load(&path).expect("Failed to load level.");
+        let issues = validate_level(&level, &tile_defs);
+        for issue in &issues {
+            println!("{}: {}", path.display(), issue);
+        }
+        if !issues.is_empty() {
+            invalid.push(path);
+        }
+    }
+    assert!(invalid.is_empty(), "Invalid levels: {:?}", invalid);
+}
+
+#[test]
+fn broken_level_reports_every_issue() {
+    let mut level = LevelSave {
+        world_bounds: WorldBounds::new(0, 0, 10, 10),
+        ..LevelSave::default()
+    };
+    let mut put = |x, y, key: &str| level.tiles.insert(Pos::new(x, y), key.to_string());
+    put(0, 0, "Player");
+    put(1, 1, "Player");
+    put(10, 0, "Block1");
+    put(5, 5, "DoesNotExist");
+    level
+        .properties
+        .insert(Pos::new(7, 7), TileProperties::default());
+    let issues = validate_level(&level, &tile_defs());
+    assert_eq!(
+        issues,
+        vec![
+            LevelIssue::MissingMandatory {
+                key: "Door".to_string()
+            },
+            LevelIssue::DuplicateUnique {
+                key: "Player".to_string(),
+                positions: vec![Pos::new(0, 0), Pos::new(1, 1)],
+            },
+            LevelIssue::Overlap {
+                first: Pos::new(0, 0),
+                second: Pos::new(1, 1),
+            },
+            LevelIssue::OutOfBounds {
+                pos: Pos::new(10, 0),
+                key: "Block1".to_string(),
+            },
+            LevelIssue::UnknownKey {
+                pos: Pos::new(5, 5),
+                key: "DoesNotExist".to_string(),
+            },
+            LevelIssue::PropertiesWithoutTile {
+                pos: Pos::new(7, 7)
+            },
+            LevelIssue::NoKeys,
+        ]
+    );
+}
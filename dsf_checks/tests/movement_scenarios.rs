@@ -0,0 +1,118 @@
+//! Runs every movement scenario in `assets/tests/scenarios/` headlessly.
+
+mod common;
+
+use amethyst::config::Config;
+use dsf_checks::level_files::assets_dir;
+use dsf_checks::scenarios::{
+    load_scenarios, run_scenario_in_dir, ExpectedMode, Scenario, TimelineEntry,
+};
+use dsf_core::components::{Direction1D, Pos, SteeringIntent};
+use dsf_core::resources::MovementConfig;
+
+use crate::common::tile_defs;
+
+#[test]
+fn all_movement_scenarios_pass() {
+    let assets_dir = assets_dir();
+    let tests_dir = assets_dir.join("tests/");
+    let tile_defs = tile_defs();
+    let movement = MovementConfig::load(assets_dir.join("config/movement.ron"))
+        .expect("Failed to load movement config.");
+    let scenarios =
+        load_scenarios(&tests_dir.join("scenarios/")).expect("Failed to load scenarios.");
+    assert!(!scenarios.is_empty(), "No scenarios found.");
+    let mut failed = vec![];
+    for (path, scenario) in &scenarios {
+        let name = path.file_name().unwrap_or_default().to_string_lossy();
+        match run_scenario_in_dir(scenario, &tests_dir, tile_defs.clone(), &movement) {
+            Ok(report) if report.passed() => println!("PASS {}", name),
+            Ok(report) => {
+                println!("FAIL {}: {}", name, scenario.description);
+                for failure in &report.failures {
+                    println!("    {}", failure);
+                }
+                failed.push(name);
+            }
+            Err(error) => {
+                println!("ERROR {}: {}", name, error);
+                failed.push(name);
+            }
+        }
+    }
+    assert!(failed.is_empty(), "Failed scenarios: {:?}", failed);
+}
+
+/// A 6-wide gap can never be jumped across.
+#[test]
+fn no_running_jump_clears_a_6_wide_gap() {
+    assert_no_running_jump_clears("jump_6_wide.txt");
+}
+
+/// `docs/LevelDesign.md` promises that a 5-wide gap cannot be jumped across.
+#[test]
+#[ignore = "a well-timed running jump still clears a 5-wide gap"]
+fn no_running_jump_clears_a_5_wide_gap() {
+    assert_no_running_jump_clears("jump_5_wide.txt");
+}
+
+/// `docs/LevelDesign.md` plans for a 4-wide gap to suffice once jumping has been redone.
+#[test]
+#[ignore = "jumping has not been redone yet"]
+fn no_running_jump_clears_a_4_wide_gap() {
+    assert_no_running_jump_clears("jump_4_wide.txt");
+}
+
+/// A single scenario only tries one run-up, so this tries every run-up from the platform the
+/// player starts on.
+fn assert_no_running_jump_clears(level: &str) {
+    let assets_dir = assets_dir();
+    let tests_dir = assets_dir.join("tests/");
+    let tile_defs = tile_defs();
+    let movement = MovementConfig::load(assets_dir.join("config/movement.ron"))
+        .expect("Failed to load movement config.");
+    let walk = SteeringIntent {
+        face: Direction1D::Positive,
+        walk: Direction1D::Positive,
+        ..SteeringIntent::default()
+    };
+    let jump = SteeringIntent {
+        jump: true,
+        jump_direction: Direction1D::Positive,
+        ..walk
+    };
+    for start_x in 0..=2 {
+        for run_up in 0..40 {
+            let scenario = Scenario {
+                level: level.to_string(),
+                start: Some(Pos::new(start_x, 0)),
+                timeline: vec![
+                    TimelineEntry {
+                        ticks: run_up,
+                        intent: walk,
+                    },
+                    TimelineEntry {
+                        ticks: 1,
+                        intent: jump,
+                    },
+                    TimelineEntry {
+                        ticks: 60,
+                        intent: SteeringIntent::default(),
+                    },
+                ],
+                ..Scenario::default()
+            };
+            let report = run_scenario_in_dir(&scenario, &tests_dir, tile_defs.clone(), &movement)
+                .expect("Failed to run scenario.");
+            assert_eq!(
+                report.mode,
+                ExpectedMode::Falling,
+                "In {}, starting at x {} with a run-up of {} ticks, the player ended up at {:?}.",
+                level,
+                start_x,
+                run_up,
+                report.pos
+            );
+        }
+    }
+}
@@ -0,0 +1,105 @@
+//! Checks where the ladder tool places its ladder, and that the player can climb it.
+
+mod common;
+
+use amethyst::config::Config;
+use dsf_checks::level_files::assets_dir;
+use dsf_checks::solver::{solve_level, Action, SolverConfig, SolverOutcome};
+use dsf_core::components::{Direction1D, Pos, Steering};
+use dsf_core::levels::{parse_ascii_level, LevelSave};
+use dsf_core::resources::{MovementConfig, TileMap};
+use dsf_core::systems::{
+    can_climb_down, can_climb_up, fits_in_tile_map, ladder_positions, place_ladder,
+};
+
+use crate::common::tile_defs;
+
+/// A floor from x = 0 to x = 5 inclusive, with a drop to the right of it.
+fn ledge() -> TileMap {
+    let mut level = LevelSave::default();
+    for x in 0..6 {
+        level.tiles.insert(Pos::new(x, 0), "Block1".to_string());
+    }
+    TileMap::for_play(&level, tile_defs())
+}
+
+#[test]
+fn ladder_is_placed_in_front_of_the_player() {
+    let mut steering = Steering::new(Pos::new(2, 1), Pos::new(2, 2));
+    let dimens = Pos::new(2, 1);
+    assert_eq!(
+        ladder_positions(&steering, 3, true, dimens),
+        vec![Pos::new(4, 1), Pos::new(4, 2), Pos::new(4, 3)]
+    );
+    assert_eq!(
+        ladder_positions(&steering, 2, false, dimens),
+        vec![Pos::new(4, -1), Pos::new(4, 0)]
+    );
+    steering.facing.x = Direction1D::Negative;
+    assert_eq!(
+        ladder_positions(&steering, 1, true, dimens),
+        vec![Pos::new(0, 1)]
+    );
+}
+
+#[test]
+fn ladder_is_not_placed_over_other_tiles() {
+    let tile_map = ledge();
+    let dimens = Pos::new(2, 1);
+    assert!(fits_in_tile_map(
+        &[Pos::new(4, 1), Pos::new(4, 2)],
+        dimens,
+        &tile_map
+    ));
+    assert!(!fits_in_tile_map(&[Pos::new(4, 0)], dimens, &tile_map));
+    assert!(!fits_in_tile_map(&[Pos::new(5, 0)], dimens, &tile_map));
+    assert!(fits_in_tile_map(&[Pos::new(6, 0)], dimens, &tile_map));
+}
+
+#[test]
+fn placed_ladders_can_be_climbed() {
+    let mut tile_map = ledge();
+    let mut steering = Steering::new(Pos::new(2, 1), Pos::new(2, 2));
+    for pos in ladder_positions(&steering, 3, true, Pos::new(2, 1)) {
+        place_ladder(&mut tile_map, pos);
+    }
+    steering.pos = Pos::new(4, 1);
+    assert!(can_climb_up(&steering, &tile_map));
+
+    let mut tile_map = ledge();
+    let mut steering = Steering::new(Pos::new(4, 1), Pos::new(2, 2));
+    for pos in ladder_positions(&steering, 3, false, Pos::new(2, 1)) {
+        place_ladder(&mut tile_map, pos);
+    }
+    steering.pos = Pos::new(6, 1);
+    assert!(can_climb_down(&steering, &tile_map));
+}
+
+/// The door is on top of a ledge that is too high to jump onto, so the only way up is to place
+/// the ladder against the ledge and climb it.
+#[test]
+fn solver_climbs_a_placed_ladder() {
+    let level = parse_ascii_level(concat!(
+        "legend:\n",
+        "L ToolLadder\n",
+        "grid:\n",
+        "#...............\n",
+        "#..........++++.\n",
+        "#..........++++.\n",
+        "#..........++++.\n",
+        "#..........D+++.\n",
+        "#.......########\n",
+        "#++.++..########\n",
+        "#P+.L+..########\n",
+        "################",
+    ))
+    .expect("Failed to parse level.");
+    let movement = MovementConfig::load(assets_dir().join("config/movement.ron"))
+        .expect("Failed to load movement config.");
+    match solve_level(&level, tile_defs(), SolverConfig::new(movement)) {
+        Ok(SolverOutcome::Solved(actions)) => {
+            assert!(actions.contains(&Action::UseTool), "{:?}", actions)
+        }
+        outcome => panic!("Expected a solution, got {:?}", outcome),
+    }
+}
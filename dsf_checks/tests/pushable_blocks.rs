@@ -0,0 +1,157 @@
+//! Checks when the player pushes blocks, and that their tiles move along in the tile map.
+
+mod common;
+
+use amethyst::core::Transform;
+use amethyst::ecs::{Builder, RunNow, World, WorldExt};
+use dsf_core::components::{Block, Direction1D, Player, Pos, Pushable, Steering, SteeringIntent};
+use dsf_core::levels::LevelSave;
+use dsf_core::resources::{Archetype, TileMap, WorldBounds};
+use dsf_core::systems::{tiles_to_push, PushSystem};
+
+use crate::common::tile_defs;
+
+/// A floor with a pushable block on it at (4, 1), and any extra tiles.
+fn tile_map(extra_tiles: &[(i32, i32, &str)]) -> TileMap {
+    let mut level = LevelSave::default();
+    for x in 0..10 {
+        level.tiles.insert(Pos::new(x, 0), "Block1".to_string());
+    }
+    level
+        .tiles
+        .insert(Pos::new(4, 1), "PushableBlock".to_string());
+    for (x, y, key) in extra_tiles {
+        level.tiles.insert(Pos::new(*x, *y), (*key).to_string());
+    }
+    TileMap::for_play(&level, tile_defs())
+}
+
+/// Returns what a 2 by 2 player standing still at the given position pushes.
+fn pushed_from(x: i32, walk: Direction1D, tile_map: &TileMap) -> Vec<Pos> {
+    let steering = Steering::new(Pos::new(x, 1), Pos::new(2, 2));
+    let translation = steering.to_centered_coords(steering.pos);
+    tiles_to_push(&steering, translation, walk, tile_map)
+}
+
+#[test]
+fn player_pushes_block_in_front_of_them() {
+    let tile_map = tile_map(&[]);
+    assert_eq!(
+        pushed_from(2, Direction1D::Positive, &tile_map),
+        vec![Pos::new(4, 1)]
+    );
+    assert_eq!(
+        pushed_from(5, Direction1D::Negative, &tile_map),
+        vec![Pos::new(4, 1)]
+    );
+    assert!(pushed_from(2, Direction1D::Negative, &tile_map).is_empty());
+    assert!(pushed_from(1, Direction1D::Positive, &tile_map).is_empty());
+    assert!(pushed_from(2, Direction1D::Neutral, &tile_map).is_empty());
+}
+
+#[test]
+fn blocks_are_not_pushed_into_other_tiles() {
+    let behind = tile_map(&[(5, 1, "Block1")]);
+    assert!(pushed_from(2, Direction1D::Positive, &behind).is_empty());
+    let wall_above = tile_map(&[(4, 2, "Block1")]);
+    assert!(pushed_from(2, Direction1D::Positive, &wall_above).is_empty());
+}
+
+#[test]
+fn blocks_are_not_pushed_out_of_the_level() {
+    let tile_map = |width| {
+        let mut level = LevelSave {
+            world_bounds: WorldBounds::new(0, 0, width, 4),
+            ..LevelSave::default()
+        };
+        level
+            .tiles
+            .insert(Pos::new(4, 1), "PushableBlock".to_string());
+        TileMap::for_play(&level, tile_defs())
+    };
+    assert!(pushed_from(2, Direction1D::Positive, &tile_map(5)).is_empty());
+    assert_eq!(
+        pushed_from(2, Direction1D::Positive, &tile_map(6)),
+        vec![Pos::new(4, 1)]
+    );
+}
+
+#[test]
+fn stacked_blocks_are_pushed_together() {
+    let tile_map = tile_map(&[(4, 2, "PushableBlock")]);
+    assert_eq!(
+        pushed_from(2, Direction1D::Positive, &tile_map),
+        vec![Pos::new(4, 1), Pos::new(4, 2)]
+    );
+}
+
+#[test]
+fn pushable_tiles_move_in_the_tile_map() {
+    let mut tile_map = tile_map(&[(6, 1, "Block1")]);
+    assert!(tile_map.move_tile(Pos::new(4, 1), Pos::new(5, 1)));
+    assert!(tile_map.get_tile(Pos::new(4, 1)).is_none());
+    let moved = tile_map
+        .get_tile(Pos::new(5, 1))
+        .expect("Tile did not move.");
+    assert_eq!(moved.archetype, Some(Archetype::Pushable));
+    assert!(moved.provides_platform());
+    assert!(!tile_map.move_tile(Pos::new(5, 1), Pos::new(6, 1)));
+    assert!(!tile_map.move_tile(Pos::new(4, 1), Pos::new(3, 1)));
+}
+
+#[test]
+fn push_system_moves_the_block_and_its_tile() {
+    let mut world = World::new();
+    world.register::<Player>();
+    world.register::<SteeringIntent>();
+    world.register::<Transform>();
+    world.register::<Pushable>();
+    world.register::<Steering>();
+    world.register::<Block>();
+    world.insert(tile_map(&[]));
+    let steering = Steering::new(Pos::new(2, 1), Pos::new(2, 2));
+    let (x, y) = steering.to_centered_coords(steering.pos);
+    let mut transform = Transform::default();
+    transform.set_translation_xyz(x, y, 0.);
+    world
+        .create_entity()
+        .with(Player::default())
+        .with(SteeringIntent {
+            walk: Direction1D::Positive,
+            ..SteeringIntent::default()
+        })
+        .with(transform)
+        .with(steering)
+        .build();
+    let block = world
+        .create_entity()
+        .with(Pushable)
+        .with(Block {
+            pos: Pos::new(4, 1),
+        })
+        .with(Steering::new(Pos::new(4, 1), Pos::new(1, 1)))
+        .build();
+    PushSystem.run_now(&world);
+    let tile_map = world.read_resource::<TileMap>();
+    assert!(tile_map.get_tile(Pos::new(4, 1)).is_none());
+    assert_eq!(
+        tile_map
+            .get_tile(Pos::new(5, 1))
+            .and_then(|tile_def| tile_def.archetype),
+        Some(Archetype::Pushable)
+    );
+    assert_eq!(
+        world
+            .read_storage::<Block>()
+            .get(block)
+            .map(|block| block.pos),
+        Some(Pos::new(5, 1))
+    );
+    assert_eq!(
+        world
+            .read_storage::<Steering>()
+            .get(block)
+            .map(|steering| steering.destination),
+        Some(Pos::new(5, 1))
+    );
+}
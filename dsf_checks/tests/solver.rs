@@ -0,0 +1,132 @@
+//! Checks the verdicts of the solver on small levels.
+
+mod common;
+
+use amethyst::config::Config;
+use dsf_checks::level_files::assets_dir;
+use dsf_checks::solver::{solve_level, Action, SolverConfig, SolverError, SolverOutcome};
+use dsf_core::levels::parse_ascii_level;
+use dsf_core::resources::MovementConfig;
+
+use crate::common::tile_defs;
+
+fn solve(text: &str) -> Result<SolverOutcome, SolverError> {
+    let level = parse_ascii_level(text).expect("Failed to parse level.");
+    let movement = MovementConfig::load(assets_dir().join("config/movement.ron"))
+        .expect("Failed to load movement config.");
+    solve_level(&level, tile_defs(), SolverConfig::new(movement))
+}
+
+#[test]
+fn walking_to_the_door_is_solved() {
+    let outcome = solve(concat!(
+        "grid:\n",
+        "........++++\n",
+        "........++++\n",
+        ".++.....++++\n",
+        ".P+.....D+++\n",
+        "############",
+    ));
+    assert!(outcome.expect("Failed to solve.").is_solved());
+}
+
+#[test]
+fn levels_with_pushable_blocks_are_refused() {
+    let outcome = solve(concat!(
+        "legend:\n",
+        "o PushableBlock\n",
+        "grid:\n",
+        "........++++\n",
+        "........++++\n",
+        ".++.....++++\n",
+        ".P+..o..D+++\n",
+        "############",
+    ));
+    assert_eq!(outcome, Err(SolverError::PushableBlocks(1)));
+}
+
+/// Dynamite cannot open a way through the 6-wide walls. The solver does not explore walking on
+/// while the fuse burns, so it must not claim that the level is unsolvable.
+#[test]
+fn levels_with_dynamite_are_never_unsolvable() {
+    let without_dynamite = solve(concat!(
+        "grid:\n",
+        "......................\n",
+        "......................\n",
+        "######.....######.++++\n",
+        "######.....######.++++\n",
+        "######.++..######.++++\n",
+        "######.P+..######.D+++\n",
+        "######################",
+    ));
+    assert!(matches!(
+        without_dynamite,
+        Ok(SolverOutcome::Unsolvable { .. })
+    ));
+    let with_dynamite = solve(concat!(
+        "legend:\n",
+        "y ToolDynamite\n",
+        "grid:\n",
+        "......................\n",
+        "......................\n",
+        "######.....######.++++\n",
+        "######.....######.++++\n",
+        "######.++++######.++++\n",
+        "######.P+y+######.D+++\n",
+        "######################",
+    ));
+    assert!(matches!(
+        with_dynamite,
+        Ok(SolverOutcome::Inconclusive { .. })
+    ));
+}
+
+/// A 4-wide gap can only be crossed with a running jump or a late jump direction, not by
+/// tapping jump and the direction at once.
+#[test]
+fn gaps_that_need_a_timed_jump_are_solved() {
+    let outcome = solve(concat!(
+        "grid:\n",
+        "#...................\n",
+        "#..............++++.\n",
+        "#..............++++.\n",
+        "#.++...........++++.\n",
+        "#.P+...........D+++.\n",
+        "########....########\n",
+        "########....########\n",
+        "########....########\n",
+        "####################",
+    ));
+    match outcome {
+        Ok(SolverOutcome::Solved(actions)) => assert!(
+            actions
+                .iter()
+                .any(|action| matches!(action, Action::TimedJump { .. })),
+            "{:?}",
+            actions
+        ),
+        outcome => panic!("Expected a solution, got {:?}", outcome),
+    }
+}
+
+/// With every jump timing tried and still no way across, the level is proven unsolvable.
+#[test]
+fn gaps_that_cannot_be_jumped_are_unsolvable() {
+    let outcome = solve(concat!(
+        "grid:\n",
+        "#...................\n",
+        "#..............++++.\n",
+        "#..............++++.\n",
+        "#.++...........++++.\n",
+        "#.P+...........D+++.\n",
+        "#####.........######\n",
+        "#####.........######\n",
+        "#####.........######\n",
+        "####################",
+    ));
+    assert!(
+        matches!(outcome, Ok(SolverOutcome::Unsolvable { .. })),
+        "{:?}",
+        outcome
+    );
+}
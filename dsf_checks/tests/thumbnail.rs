@@ -0,0 +1,30 @@
+//! Checks that levels can be drawn into images without a GPU.
+
+use amethyst::config::Config;
+use dsf_checks::level_files::assets_dir;
+use dsf_core::levels::{parse_ascii_level, render_thumbnail, SpriteSheets};
+use dsf_core::resources::TileDefinitions;
+use dsf_core::utility::loading_config::LoadingConfig;
+
+#[test]
+fn level_is_drawn_with_its_sprites() {
+    let tile_defs = TileDefinitions::load(assets_dir().join("world/tile_references.ron"))
+        .expect("Failed to load tile definitions.");
+    let loading_config = LoadingConfig::load(assets_dir().join("config/loading.ron"))
+        .expect("Failed to load the loading config.");
+    let sprite_sheets =
+        SpriteSheets::load(&assets_dir(), &loading_config).expect("Failed to load the sprites.");
+    let level = parse_ascii_level("grid:\n....\n.++.\n.P+.\n####").expect("Failed to parse level.");
+    let thumbnail = render_thumbnail(&level, &tile_defs, &sprite_sheets, 8);
+    assert_eq!(
+        (thumbnail.width(), thumbnail.height()),
+        (
+            level.world_bounds.width() as u32 * 8,
+            level.world_bounds.height() as u32 * 8
+        )
+    );
+    // The bottom row is filled with blocks, the top row is empty.
+    let bottom = thumbnail.height() - 4;
+    assert!((0..thumbnail.width()).all(|x| thumbnail.get_pixel(x, bottom)[3] > 0));
+    assert!((0..thumbnail.width()).all(|x| thumbnail.get_pixel(x, 4)[3] == 0));
+}
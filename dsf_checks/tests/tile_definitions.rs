@@ -0,0 +1,71 @@
+//! Checks that tile definitions can extend other definitions and templates.
+
+use amethyst::config::Config;
+use dsf_checks::level_files::assets_dir;
+use dsf_core::components::Pos;
+use dsf_core::resources::{AssetType, DepthLayer, SpriteType, Sturdiness, TileDefinitions};
+
+fn parse(text: &str) -> Result<TileDefinitions, String> {
+    let text = format!("#![enable(implicit_some)]\n{}", text);
+    TileDefinitions::load_bytes(text.as_bytes()).map_err(|error| error.to_string())
+}
+
+#[test]
+fn definitions_inherit_from_their_ancestors() {
+    let tile_defs = parse(
+        r#"(
+            templates: {
+                "Solid": (depth: Blocks, dimens: (x: 1, y: 1), sturdiness: Breakable),
+                "Wide": (parent: "Solid", dimens: (x: 2, y: 1)),
+            },
+            map: {
+                "Slab": (parent: "Wide", asset: Still("Blocks", 1)),
+                "Pillar": (parent: "Slab", dimens: (x: 1, y: 3), sturdiness: Invulnerable),
+            },
+        )"#,
+    )
+    .expect("Failed to resolve the tile definitions.");
+    let slab = tile_defs.get("Slab");
+    assert_eq!(slab.dimens, Pos::new(2, 1));
+    assert_eq!(slab.depth, DepthLayer::Blocks);
+    assert_eq!(slab.sturdiness, Sturdiness::Breakable);
+    let pillar = tile_defs.get("Pillar");
+    assert_eq!(pillar.dimens, Pos::new(1, 3));
+    assert_eq!(pillar.sturdiness, Sturdiness::Invulnerable);
+    assert_eq!(pillar.asset, Some(AssetType::Still(SpriteType::BLOCKS, 1)));
+    // Templates cannot be placed in a level.
+    assert_eq!(tile_defs.map.len(), 2);
+}
+
+#[test]
+fn broken_inheritance_is_reported() {
+    let unknown = parse(r#"(map: { "Slab": (parent: "Solid") })"#).unwrap_err();
+    assert!(
+        unknown.contains(r#"tile definition "Slab" extends "Solid""#),
+        "{}",
+        unknown
+    );
+    let cycle = parse(r#"(map: { "A": (parent: "B"), "B": (parent: "A") })"#).unwrap_err();
+    assert!(
+        cycle.contains(r#""A" -> "B" -> "A""#) || cycle.contains(r#""B" -> "A" -> "B""#),
+        "{}",
+        cycle
+    );
+    let ambiguous = parse(r#"(templates: { "A": () }, map: { "A": () })"#).unwrap_err();
+    assert!(
+        ambiguous.contains("both a tile definition and a template"),
+        "{}",
+        ambiguous
+    );
+}
+
+#[test]
+fn game_definitions_are_resolved() {
+    let tile_defs = TileDefinitions::load(assets_dir().join("world/tile_references.ron"))
+        .expect("Failed to load tile definitions.");
+    let block = tile_defs.get("Block2");
+    assert!(block.provides_platform() && block.collides_horizontally() && block.collides_bottom());
+    assert!(block.is_breakable());
+    assert_eq!(block.asset, Some(AssetType::Still(SpriteType::BLOCKS, 1)));
+    assert!(!tile_defs.map.contains_key("Block"));
+}
@@ -0,0 +1,38 @@
+//! Checks that the instance properties of tiles are merged over their tile definitions.
+
+mod common;
+
+use dsf_core::components::Pos;
+use dsf_core::levels::LevelSave;
+use dsf_core::resources::{Sturdiness, TileMap, TileProperties};
+
+use crate::common::tile_defs;
+
+#[test]
+fn properties_override_only_their_own_tile() {
+    let mut level = LevelSave::default();
+    level.tiles.insert(Pos::new(0, 0), "Block1".to_string());
+    level.tiles.insert(Pos::new(1, 0), "Block1".to_string());
+    let properties = TileProperties::parse("(climbable: true, sturdiness: Breakable)")
+        .expect("Failed to parse.");
+    level.properties.insert(Pos::new(0, 0), properties);
+
+    let tile_map = TileMap::for_play(&level, tile_defs());
+    let overridden = tile_map.get_tile(Pos::new(0, 0)).expect("Tile is missing.");
+    assert!(overridden.climbable);
+    assert_eq!(overridden.sturdiness, Sturdiness::Breakable);
+    let plain = tile_map.get_tile(Pos::new(1, 0)).expect("Tile is missing.");
+    assert!(!plain.climbable);
+    assert_eq!(plain.dimens, overridden.dimens);
+}
+
+#[test]
+fn properties_round_trip_through_ron() {
+    let properties = TileProperties::parse("(climbable: false)").expect("Failed to parse.");
+    assert_eq!(properties.climbable, Some(false));
+    assert_eq!(
+        TileProperties::parse(&properties.to_ron()).ok(),
+        Some(properties)
+    );
+    assert!(TileProperties::parse("(colour: Red)").is_err());
+}
@@ -0,0 +1,122 @@
+//! Checks the import of Tiled maps and the export of levels to Tiled maps.
+
+mod common;
+
+use amethyst::config::Config;
+use dsf_checks::level_files::{assets_dir, collect_level_files, levels_dir};
+use dsf_core::components::Pos;
+use dsf_core::levels::{
+    export_tiled_json, import_tiled_json, import_tmx, Difficulty, LevelSave, TiledError,
+    TiledMapping,
+};
+use dsf_core::resources::{TileProperties, WorldBounds};
+
+use crate::common::tile_defs;
+
+fn mapping() -> TiledMapping {
+    TiledMapping::load(assets_dir().join("world/tiled_mapping.ron"))
+        .expect("Failed to load Tiled mapping.")
+}
+
+#[test]
+fn all_levels_round_trip() {
+    let (mapping, tile_defs) = (mapping(), tile_defs());
+    for file in collect_level_files(vec![levels_dir()]).expect("Failed to list levels.") {
+        let level = LevelSave::load(&file).expect("Failed to load level.");
+        let json = export_tiled_json(&level, &mapping, &tile_defs);
+        let imported = import_tiled_json(&json, &mapping, &tile_defs).expect("Failed to import.");
+        assert_eq!(imported, level, "{} did not round trip.", file.display());
+    }
+}
+
+#[test]
+fn unmapped_tiles_become_objects() {
+    let mut level = LevelSave {
+        world_bounds: WorldBounds::new(-3, -2, 6, 4),
+        ..LevelSave::default()
+    };
+    level.tiles.insert(Pos::new(-3, -2), "Ladder".to_string());
+    level.tiles.insert(Pos::new(1, 0), "Key".to_string());
+    level.properties.insert(
+        Pos::new(1, 0),
+        TileProperties {
+            climbable: Some(true),
+            ..TileProperties::default()
+        },
+    );
+    let mut mapping = mapping();
+    mapping.tiles.retain(|_, key| key != "Ladder");
+    let tile_defs = tile_defs();
+    let json = export_tiled_json(&level, &mapping, &tile_defs);
+    assert_eq!(json.matches("\"type\": \"Ladder\"").count(), 1);
+    assert_eq!(json.matches("\"type\": \"Key\"").count(), 1);
+    let imported = import_tiled_json(&json, &mapping, &tile_defs).expect("Failed to import.");
+    assert_eq!(imported, level);
+}
+
+#[test]
+fn tmx_map_is_imported() {
+    // A 6 by 4 map. The 2 by 2 player (tile 5) is painted over its whole footprint, the ladder
+    // (tile 2) only in its bottom-left cell.
+    let tmx = r#"<?xml version="1.0" encoding="UTF-8"?>
+<map version="1.10" orientation="orthogonal" renderorder="right-down" width="6" height="4"
+     tilewidth="32" tileheight="32" infinite="0" nextlayerid="3" nextobjectid="3">
+ <properties>
+  <property name="title" value="Imported"/>
+  <property name="difficulty" value="hard"/>
+  <property name="par_moves" type="int" value="12"/>
+  <property name="world_y" type="int" value="-1"/>
+ </properties>
+ <tileset firstgid="1" source="dsf_tiles.tsx"/>
+ <layer id="1" name="tiles" width="6" height="4">
+  <properties><property name="title" value="Not the map title"/></properties>
+  <data encoding="csv">
+0,0,0,0,0,0,
+6,6,0,0,0,0,
+6,6,0,0,0,0,
+1,1,3,0,2,2
+</data>
+ </layer>
+ <objectgroup id="2" name="objects">
+  <object id="1" type="Exit" x="128" y="32" width="64" height="64"/>
+  <object id="2" gid="4" x="64" y="96" width="64" height="64">
+   <properties><property name="properties" value="(climbable: true)"/></properties>
+  </object>
+ </objectgroup>
+</map>"#;
+    let level = import_tmx(tmx, &mapping(), &tile_defs()).expect("Failed to import TMX map.");
+    assert_eq!(level.world_bounds, WorldBounds::new(0, -1, 6, 4));
+    assert_eq!(level.metadata.title, "Imported");
+    assert_eq!(level.metadata.difficulty, Some(Difficulty::Hard));
+    assert_eq!(level.metadata.par_moves, Some(12));
+    let key_at = |x, y| level.tiles.get(&Pos::new(x, y)).map(String::as_str);
+    assert_eq!(key_at(0, -1), Some("Block1"));
+    assert_eq!(key_at(1, -1), Some("Block1"));
+    assert_eq!(key_at(2, -1), Some("Ladder"));
+    assert_eq!(key_at(4, -1), Some("Block2"));
+    assert_eq!(key_at(5, -1), Some("Block2"));
+    assert_eq!(key_at(0, 0), Some("Player"));
+    assert_eq!(key_at(4, 0), Some("Door"));
+    assert_eq!(key_at(2, 0), Some("Key"));
+    assert_eq!(level.tiles.len(), 8);
+    assert_eq!(
+        level.properties.get(&Pos::new(2, 0)).map(|p| p.climbable),
+        Some(Some(true))
+    );
+}
+
+#[test]
+fn unmapped_tile_is_reported() {
+    let json = r#"{
+        "width": 2, "height": 2, "tilewidth": 32, "tileheight": 32,
+        "tilesets": [{"firstgid": 1, "name": "dsf_tiles"}],
+        "layers": [{"type": "tilelayer", "name": "tiles", "data": [0, 0, 0, 100]}]
+    }"#;
+    match import_tiled_json(json, &mapping(), &tile_defs()) {
+        Err(TiledError::UnmappedTile { tileset, id }) => {
+            assert_eq!(tileset, "dsf_tiles");
+            assert_eq!(id, 99);
+        }
+        other => panic!("Expected an unmapped tile, got {:?}", other),
+    }
+}
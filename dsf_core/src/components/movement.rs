@@ -41,7 +41,9 @@ impl Direction2D {
     }
 }
 
-#[derive(Clone, Copy, Component, Debug, Deserialize, Serialize, PrefabData, PartialEq)]
+#[derive(
+    Clone, Copy, Component, Debug, Deserialize, Serialize, PrefabData, PartialEq, Eq, Hash,
+)]
 #[prefab(Component)]
 #[serde(deny_unknown_fields)]
 pub enum Direction1D {
@@ -206,6 +208,7 @@ impl Component for Steering {
 /// the `SteeringSystem` then actually moves the entity based on this intent.
 #[derive(Copy, Clone, Debug, Default, Deserialize, Serialize, PrefabData)]
 #[prefab(Component)]
+#[serde(default)]
 #[serde(deny_unknown_fields)]
 pub struct SteeringIntent {
     /// If a player is still holding a horizontal movement key (for instance; RIGHT) when they
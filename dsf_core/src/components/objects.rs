@@ -26,7 +26,7 @@ impl Key {
     }
 }
 
-#[derive(Clone, Copy, Debug, Default, Deserialize, Serialize, PrefabData)]
+#[derive(Clone, Debug, Default, Deserialize, Serialize, PrefabData)]
 #[prefab(Component)]
 pub struct Tool {
     pub tool_type: ToolType,
@@ -89,6 +89,40 @@ impl Component for ExitDoor {
     type Storage = NullStorage<Self>;
 }
 
+/// A block that the player can push one tile to the side by walking into it. Its tile in the
+/// `TileMap` moves along with it, so that it keeps acting as a platform and a wall.
+/// The `Block` component of the entity always holds the position of that tile.
+#[derive(Clone, Copy, Debug, Default, Deserialize, Serialize, PrefabData)]
+#[prefab(Component)]
+pub struct Pushable;
+
+impl Component for Pushable {
+    type Storage = NullStorage<Self>;
+}
+
+/// Dynamite that was placed by the player. Explodes when the fuse has burnt down.
+#[derive(Clone, Copy, Debug, Default, Deserialize, Serialize, PrefabData)]
+#[prefab(Component)]
+pub struct Dynamite {
+    /// The tile at the centre of the explosion.
+    pub pos: Pos,
+    /// How many tiles the explosion reaches in each of the four directions.
+    pub radius: u8,
+    /// The number of fixed ticks left until the dynamite explodes.
+    pub fuse: u32,
+}
+
+impl Component for Dynamite {
+    type Storage = HashMapStorage<Self>;
+}
+
+impl Dynamite {
+    #[must_use]
+    pub fn new(pos: Pos, radius: u8, fuse: u32) -> Self {
+        Dynamite { pos, radius, fuse }
+    }
+}
+
 /// The blue background sprite.
 #[derive(Clone, Copy, Debug, Default, Deserialize, Serialize, PrefabData)]
 #[prefab(Component)]
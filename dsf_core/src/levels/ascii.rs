@@ -0,0 +1,376 @@
+//! A plain-text level format, in which the level is drawn as a grid of characters. It is meant for
+//! test levels and quick sketches: unlike RON level files, these are readable in a diff and easy
+//! to write inside a Rust test.
+//!
+//! ```text
+//! title: Jump two wide
+//! origin: -3 -2
+//! legend:
+//! @ Key
+//! grid:
+//! .++....@
+//! .P+.....
+//! ###..%%#
+//! properties:
+//! 2 -2 (climbable: true)
+//! ```
+//!
+//! The file starts with optional header lines: `origin` is the position of the bottom-left
+//! character of the grid, the other headers hold the metadata of the level. The world bounds are
+//! the grid itself. Each character in the grid is one position in the world:
+//!
+//! - `.` is empty.
+//! - A character from the legend is the anchor (the bottom-left position) of a tile of that key.
+//! - `+` is a position covered by a tile larger than one by one, but not its anchor. It is only
+//!   there to make the level easier to read, and is ignored when the level is read.
+//!
+//! The legend is added to the default legend (see `default_ascii_legend`), so it can be left out
+//! if the level only uses the common tiles. The optional `properties` section lists the instance
+//! properties of tiles: the position of the tile, followed by its properties in RON.
+//! Lines starting with `//` are comments, except in the grid.
+
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::fmt;
+use std::fmt::Write;
+
+use crate::components::Pos;
+use crate::levels::{Difficulty, LevelSave};
+use crate::resources::{TileDefinitions, TileProperties, WorldBounds};
+
+/// Level files with this extension are stored in the ASCII level format.
+pub const ASCII_LEVEL_EXTENSION: &str = "txt";
+
+const EMPTY: char = '.';
+const COVERED: char = '+';
+/// Characters that are given to keys that have no character in the legend, in this order.
+const SPARE_CHARACTERS: &str = "ABCEFGIJLMNOQRSTUVWXYZabcdefgijlmnopqrstuvwyz0123456789=*@$&!?";
+
+/// Returns the characters that stand for the common tile definition keys.
+#[must_use]
+pub fn default_ascii_legend() -> BTreeMap<char, String> {
+    [
+        ('#', "Block1"),
+        ('%', "Block2"),
+        ('H', "Ladder"),
+        ('k', "Key"),
+        ('D', "Door"),
+        ('P', "Player"),
+        ('x', "ToolPickaxe"),
+        ('h', "ToolHammer"),
+    ]
+    .iter()
+    .map(|(character, key)| (*character, (*key).to_string()))
+    .collect()
+}
+
+/// The reasons a level could not be read from or written to the ASCII level format.
+/// Line numbers start at 1.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AsciiLevelError {
+    /// The line is not valid in the section it is in.
+    InvalidLine { line: usize, reason: String },
+    /// The grid contains a character that is not in the legend.
+    UnknownCharacter { line: usize, character: char },
+    /// Not every line of the grid has the same length.
+    RaggedGrid { line: usize },
+    /// The level has no grid.
+    MissingGrid,
+    /// The tile anchored at this position lies outside the world bounds, so it cannot be drawn.
+    OutOfBounds { pos: Pos, key: String },
+    /// The level uses more different tile keys than there are characters to draw them with.
+    TooManyKeys,
+}
+
+impl fmt::Display for AsciiLevelError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AsciiLevelError::InvalidLine { line, reason } => {
+                write!(f, "line {}: {}", line, reason)
+            }
+            AsciiLevelError::UnknownCharacter { line, character } => write!(
+                f,
+                "line {}: character {:?} is not in the legend",
+                line, character
+            ),
+            AsciiLevelError::RaggedGrid { line } => write!(
+                f,
+                "line {}: the line is not as long as the first line of the grid",
+                line
+            ),
+            AsciiLevelError::MissingGrid => write!(f, "the level has no grid"),
+            AsciiLevelError::OutOfBounds { pos, key } => write!(
+                f,
+                "the {:?} at {:?} is outside the world bounds",
+                key,
+                (pos.x, pos.y)
+            ),
+            AsciiLevelError::TooManyKeys => {
+                write!(f, "the level uses too many different tiles to draw")
+            }
+        }
+    }
+}
+
+#[derive(PartialEq)]
+enum Section {
+    Header,
+    Legend,
+    Grid,
+    Properties,
+}
+
+/// Reads a level in the ASCII level format. Leading and trailing whitespace is ignored on every
+/// line, so the level may be indented, for example inside a Rust string literal.
+pub fn parse_ascii_level(text: &str) -> Result<LevelSave, AsciiLevelError> {
+    let mut level = LevelSave::default();
+    let mut origin = Pos::default();
+    let mut legend = default_ascii_legend();
+    // The grid lines with their line numbers, from top to bottom.
+    let mut grid: Vec<(usize, &str)> = vec![];
+    let mut properties = vec![];
+    let mut section = Section::Header;
+    for (index, line) in text.lines().enumerate() {
+        let line_nr = index + 1;
+        let line = line.trim();
+        let invalid = |reason: &str| AsciiLevelError::InvalidLine {
+            line: line_nr,
+            reason: reason.to_string(),
+        };
+        if line.is_empty() || (section != Section::Grid && line.starts_with("//")) {
+            continue;
+        }
+        match line {
+            "legend:" => section = Section::Legend,
+            "grid:" => section = Section::Grid,
+            "properties:" => section = Section::Properties,
+            _ => match section {
+                Section::Header => {
+                    let (name, value) = split_once(line, ':')
+                        .ok_or_else(|| invalid("expected a header such as \"title: ...\""))?;
+                    let value = value.trim();
+                    let number = || {
+                        value
+                            .parse::<u32>()
+                            .map_err(|_| invalid("expected a whole number"))
+                    };
+                    let metadata = &mut level.metadata;
+                    match name.trim() {
+                        "origin" => {
+                            origin = parse_pos(value)
+                                .ok_or_else(|| invalid("expected \"origin: X Y\""))?
+                        }
+                        "title" => metadata.title = unescape(value),
+                        "author" => metadata.author = unescape(value),
+                        "description" => metadata.description = unescape(value),
+                        "difficulty" => {
+                            metadata.difficulty = Some(
+                                Difficulty::parse(value)
+                                    .ok_or_else(|| invalid("unknown difficulty"))?,
+                            )
+                        }
+                        "par_moves" => metadata.par_moves = Some(number()?),
+                        "par_seconds" => metadata.par_seconds = Some(number()?),
+                        _ => return Err(invalid("unknown header")),
+                    }
+                }
+                Section::Legend => {
+                    let mut chars = line.chars();
+                    let character = chars.next().expect("The line is not empty.");
+                    let key = chars.as_str().trim();
+                    if key.is_empty() || character == EMPTY || character == COVERED {
+                        return Err(invalid("expected a character followed by a tile key"));
+                    }
+                    legend.insert(character, key.to_string());
+                }
+                Section::Grid => grid.push((line_nr, line)),
+                Section::Properties => {
+                    let mut parts = line.splitn(3, char::is_whitespace);
+                    let pos = match (parts.next(), parts.next()) {
+                        (Some(x), Some(y)) => parse_pos(&format!("{} {}", x, y)),
+                        _ => None,
+                    }
+                    .ok_or_else(|| invalid("expected \"X Y (PROPERTIES)\""))?;
+                    let tile_properties = TileProperties::parse(parts.next().unwrap_or(""))
+                        .map_err(|error| invalid(&format!("invalid tile properties: {}", error)))?;
+                    properties.push((pos, tile_properties));
+                }
+            },
+        }
+    }
+
+    let width = grid
+        .first()
+        .map(|(_, row)| row.chars().count())
+        .ok_or(AsciiLevelError::MissingGrid)?;
+    let height = grid.len();
+    level.world_bounds = WorldBounds::new(origin.x, origin.y, width as i32, height as i32);
+    for (row, (line_nr, line)) in grid.iter().enumerate() {
+        if line.chars().count() != width {
+            return Err(AsciiLevelError::RaggedGrid { line: *line_nr });
+        }
+        let y = origin.y + (height - 1 - row) as i32;
+        for (column, character) in line.chars().enumerate() {
+            if character == EMPTY || character == COVERED {
+                continue;
+            }
+            let key = legend
+                .get(&character)
+                .ok_or(AsciiLevelError::UnknownCharacter {
+                    line: *line_nr,
+                    character,
+                })?;
+            level
+                .tiles
+                .insert(Pos::new(origin.x + column as i32, y), key.clone());
+        }
+    }
+    level.properties.extend(properties);
+    Ok(level)
+}
+
+/// Draws the level in the ASCII level format. If tile definitions are given, the positions covered
+/// by tiles larger than one by one are marked. Fails if a tile is anchored outside the world
+/// bounds, because it cannot be drawn.
+pub fn write_ascii_level(
+    level: &LevelSave,
+    tile_defs: Option<&TileDefinitions>,
+) -> Result<String, AsciiLevelError> {
+    let bounds = &level.world_bounds;
+    let (width, height) = (bounds.width().max(0), bounds.height().max(0));
+
+    // Pick a character for every key, preferring the default legend.
+    let keys = level.tiles.values().collect::<BTreeSet<_>>();
+    let defaults = default_ascii_legend()
+        .into_iter()
+        .map(|(character, key)| (key, character))
+        .collect::<HashMap<_, _>>();
+    let mut spare = SPARE_CHARACTERS
+        .chars()
+        .filter(|character| !defaults.values().any(|other| other == character));
+    let mut characters = HashMap::new();
+    let mut legend = BTreeMap::new();
+    for key in keys {
+        let character = match defaults.get(key) {
+            Some(character) => *character,
+            None => {
+                let character = spare.next().ok_or(AsciiLevelError::TooManyKeys)?;
+                legend.insert(character, key);
+                character
+            }
+        };
+        characters.insert(key, character);
+    }
+
+    let mut grid = vec![vec![EMPTY; width as usize]; height as usize];
+    let cell = |pos: Pos| {
+        let (column, row) = (pos.x - bounds.x(), pos.y - bounds.y());
+        if column >= 0 && column < width && row >= 0 && row < height {
+            Some((row as usize, column as usize))
+        } else {
+            None
+        }
+    };
+    let tiles = level.tiles.iter().collect::<BTreeMap<_, _>>();
+    let mut covered = vec![];
+    for (pos, key) in &tiles {
+        let (row, column) = cell(**pos).ok_or_else(|| AsciiLevelError::OutOfBounds {
+            pos: **pos,
+            key: (*key).clone(),
+        })?;
+        grid[row][column] = characters[key];
+        if let Some(dimens) = tile_defs
+            .and_then(|tile_defs| tile_defs.map.get(*key))
+            .map(|def| def.dimens)
+        {
+            for x in 0..dimens.x {
+                for y in 0..dimens.y {
+                    if x != 0 || y != 0 {
+                        covered.extend(cell(pos.append_xy(x, y)));
+                    }
+                }
+            }
+        }
+    }
+    for (row, column) in covered {
+        if grid[row][column] == EMPTY {
+            grid[row][column] = COVERED;
+        }
+    }
+
+    let mut text = String::new();
+    let metadata = &level.metadata;
+    for (name, value) in &[
+        ("title", &metadata.title),
+        ("author", &metadata.author),
+        ("description", &metadata.description),
+    ] {
+        if !value.is_empty() {
+            writeln!(text, "{}: {}", name, escape(value)).expect("Failed to write to string.");
+        }
+    }
+    if let Some(difficulty) = metadata.difficulty {
+        writeln!(text, "difficulty: {}", difficulty).expect("Failed to write to string.");
+    }
+    if let Some(par_moves) = metadata.par_moves {
+        writeln!(text, "par_moves: {}", par_moves).expect("Failed to write to string.");
+    }
+    if let Some(par_seconds) = metadata.par_seconds {
+        writeln!(text, "par_seconds: {}", par_seconds).expect("Failed to write to string.");
+    }
+    writeln!(text, "origin: {} {}", bounds.x(), bounds.y()).expect("Failed to write to string.");
+    if !legend.is_empty() {
+        text.push_str("legend:\n");
+        for (character, key) in legend {
+            writeln!(text, "{} {}", character, key).expect("Failed to write to string.");
+        }
+    }
+    text.push_str("grid:\n");
+    // The first line of the grid is the top row of the world.
+    for row in grid.iter().rev() {
+        text.extend(row.iter());
+        text.push('\n');
+    }
+    if !level.properties.is_empty() {
+        text.push_str("properties:\n");
+        for (pos, properties) in level.properties.iter().collect::<BTreeMap<_, _>>() {
+            writeln!(text, "{} {} {}", pos.x, pos.y, properties.to_ron())
+                .expect("Failed to write to string.");
+        }
+    }
+    Ok(text)
+}
+
+fn split_once(text: &str, separator: char) -> Option<(&str, &str)> {
+    text.find(separator)
+        .map(|index| (&text[..index], &text[index + separator.len_utf8()..]))
+}
+
+fn parse_pos(text: &str) -> Option<Pos> {
+    let mut numbers = text.split_whitespace().map(str::parse::<i32>);
+    match (numbers.next(), numbers.next(), numbers.next()) {
+        (Some(Ok(x)), Some(Ok(y)), None) => Some(Pos::new(x, y)),
+        _ => None,
+    }
+}
+
+/// Headers are a single line, so line breaks in metadata are escaped.
+fn escape(text: &str) -> String {
+    text.replace('\\', "\\\\").replace('\n', "\\n")
+}
+
+fn unescape(text: &str) -> String {
+    let mut result = String::new();
+    let mut chars = text.chars();
+    while let Some(character) = chars.next() {
+        if character == '\\' {
+            match chars.next() {
+                Some('n') => result.push('\n'),
+                Some(other) => result.push(other),
+                None => result.push('\\'),
+            }
+        } else {
+            result.push(character);
+        }
+    }
+    result
+}
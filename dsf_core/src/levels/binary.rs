@@ -0,0 +1,399 @@
+//! A compact binary encoding of `LevelSave`. It holds exactly the same information as the RON
+//! format, so levels can be converted back and forth without losing anything.
+//!
+//! Binary level files are much smaller and faster to parse than RON level files, because the
+//! tiles are stored as a palette-indexed, run-length encoded grid rather than as one struct per
+//! tile. Use them for big levels and for tools that load many levels at once.
+//!
+//! The layout is as follows. Integers are LEB128 varints, signed integers are zigzag encoded
+//! first. Strings are a length followed by that many bytes of UTF-8.
+//!
+//! - The magic bytes `DSFL` and the version of the level format.
+//! - The metadata: title, author and description, the difficulty (0 for none, otherwise its
+//!   position in `Difficulty::ALL` plus one) and the par moves and seconds (0 for none, otherwise
+//!   the value plus one).
+//! - The world bounds: x, y, width and height.
+//! - The palette: the number of tile definition keys, followed by the keys in alphabetical order.
+//! - The grid: the x, y, width and height of the smallest rectangle containing all tiles,
+//!   followed by runs that cover the rectangle row by row, from the bottom-left. Each run is a
+//!   length and a value: 0 for empty positions, otherwise the palette index of the tile plus one.
+//! - The instance properties: their number, followed by the x and y position and the RON
+//!   representation of each, ordered by position.
+//!
+//! Files of older versions of the level format lack the parts that were added later: the metadata
+//! was added in version 2 and the instance properties in version 3. They are migrated the same
+//! way as RON files of that version.
+
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+use crate::components::Pos;
+use crate::levels::{
+    migrate_v0_to_v1, migrate_v1_to_v2, migrate_v2_to_v3, Difficulty, LevelFormatError,
+    LevelMetadata, LevelSave, LevelSaveV0, LevelSaveV1, LevelSaveV2, CURRENT_LEVEL_VERSION,
+};
+use crate::resources::{TileProperties, WorldBounds};
+
+/// Level files with this extension are stored in the binary format; all others are RON.
+pub const BINARY_LEVEL_EXTENSION: &str = "dsfl";
+
+const MAGIC: &[u8; 4] = b"DSFL";
+
+/// Encodes the level in the current version of the binary level format.
+#[must_use]
+pub fn encode_level(level: &LevelSave) -> Vec<u8> {
+    let mut writer = Writer::default();
+    writer.bytes.extend_from_slice(MAGIC);
+    writer.unsigned(u64::from(CURRENT_LEVEL_VERSION));
+
+    let metadata = &level.metadata;
+    writer.string(&metadata.title);
+    writer.string(&metadata.author);
+    writer.string(&metadata.description);
+    let difficulty = metadata.difficulty.map_or(0, |difficulty| {
+        Difficulty::ALL
+            .iter()
+            .position(|other| *other == difficulty)
+            .expect("Every difficulty is in Difficulty::ALL.")
+            + 1
+    });
+    writer.unsigned(difficulty as u64);
+    writer.optional(metadata.par_moves);
+    writer.optional(metadata.par_seconds);
+
+    writer.bounds(&level.world_bounds);
+
+    let palette = level
+        .tiles
+        .values()
+        .map(String::as_str)
+        .collect::<BTreeSet<_>>()
+        .into_iter()
+        .collect::<Vec<_>>();
+    writer.unsigned(palette.len() as u64);
+    palette.iter().for_each(|key| writer.string(key));
+
+    let grid = grid_bounds(level);
+    writer.bounds(&grid);
+    let cells = level
+        .tiles
+        .iter()
+        .map(|(pos, key)| {
+            let value = palette
+                .binary_search(&key.as_str())
+                .expect("Every key is in the palette.")
+                + 1;
+            (cell_index(&grid, *pos), value as u64)
+        })
+        .collect::<BTreeMap<_, _>>();
+    let mut next_index = 0;
+    let mut run: Option<(u64, u64)> = None;
+    for (index, value) in cells {
+        match run {
+            Some((length, run_value)) if next_index == index && run_value == value => {
+                run = Some((length + 1, value));
+            }
+            _ => {
+                if let Some((length, run_value)) = run {
+                    writer.run(length, run_value);
+                }
+                if index > next_index {
+                    writer.run(index - next_index, 0);
+                }
+                run = Some((1, value));
+            }
+        }
+        next_index = index + 1;
+    }
+    if let Some((length, run_value)) = run {
+        writer.run(length, run_value);
+    }
+    let cell_count = grid.dimens.x as u64 * grid.dimens.y as u64;
+    if cell_count > next_index {
+        writer.run(cell_count - next_index, 0);
+    }
+
+    let properties = level.properties.iter().collect::<BTreeMap<_, _>>();
+    writer.unsigned(properties.len() as u64);
+    for (pos, properties) in properties {
+        writer.signed(pos.x);
+        writer.signed(pos.y);
+        writer.string(&properties.to_ron());
+    }
+    writer.bytes
+}
+
+/// Decodes a level from the binary level format, migrating it to the current version of the
+/// format if needed.
+pub fn decode_level(bytes: &[u8]) -> Result<LevelSave, LevelFormatError> {
+    let mut reader = Reader::new(bytes)?;
+    let version = reader.u32()?;
+    let level = match version {
+        0 => {
+            let world_bounds = reader.bounds()?;
+            let tiles = reader.tiles()?;
+            let level = migrate_v0_to_v1(LevelSaveV0 {
+                world_bounds,
+                tiles,
+            });
+            migrate_v2_to_v3(migrate_v1_to_v2(level))
+        }
+        1 => {
+            let world_bounds = reader.bounds()?;
+            let tiles = reader.tiles()?;
+            migrate_v2_to_v3(migrate_v1_to_v2(LevelSaveV1 {
+                version,
+                world_bounds,
+                tiles,
+            }))
+        }
+        2 => {
+            let metadata = reader.metadata()?;
+            let world_bounds = reader.bounds()?;
+            let tiles = reader.tiles()?;
+            migrate_v2_to_v3(LevelSaveV2 {
+                version,
+                metadata,
+                world_bounds,
+                tiles,
+            })
+        }
+        CURRENT_LEVEL_VERSION => {
+            let metadata = reader.metadata()?;
+            let world_bounds = reader.bounds()?;
+            let tiles = reader.tiles()?;
+            let properties = reader.properties()?;
+            LevelSave {
+                version,
+                metadata,
+                world_bounds,
+                tiles,
+                properties,
+            }
+        }
+        _ => return Err(LevelFormatError::UnsupportedVersion(version)),
+    };
+    if reader.cursor != bytes.len() {
+        return Err(invalid("there is data after the end of the level"));
+    }
+    Ok(level)
+}
+
+/// Only reads the version of the level format from a binary level file.
+pub fn binary_level_version(bytes: &[u8]) -> Result<u32, LevelFormatError> {
+    Reader::new(bytes)?.u32()
+}
+
+fn invalid(reason: &str) -> LevelFormatError {
+    LevelFormatError::InvalidBinary(reason.to_string())
+}
+
+/// The smallest rectangle that contains the anchor positions of all tiles in the level.
+fn grid_bounds(level: &LevelSave) -> WorldBounds {
+    let mut positions = level.tiles.keys();
+    if let Some(first) = positions.next() {
+        let (min, max) = positions.fold((*first, *first), |(min, max), pos| {
+            (
+                Pos::new(min.x.min(pos.x), min.y.min(pos.y)),
+                Pos::new(max.x.max(pos.x), max.y.max(pos.y)),
+            )
+        });
+        WorldBounds::new(min.x, min.y, max.x - min.x + 1, max.y - min.y + 1)
+    } else {
+        WorldBounds::new(0, 0, 0, 0)
+    }
+}
+
+fn cell_index(grid: &WorldBounds, pos: Pos) -> u64 {
+    (pos.y - grid.pos.y) as u64 * grid.dimens.x as u64 + (pos.x - grid.pos.x) as u64
+}
+
+fn cell_pos(grid: &WorldBounds, index: u64) -> Pos {
+    let width = grid.dimens.x as u64;
+    Pos::new(
+        grid.pos.x + (index % width) as i32,
+        grid.pos.y + (index / width) as i32,
+    )
+}
+
+#[derive(Default)]
+struct Writer {
+    bytes: Vec<u8>,
+}
+
+impl Writer {
+    fn unsigned(&mut self, mut value: u64) {
+        loop {
+            let byte = (value & 0x7f) as u8;
+            value >>= 7;
+            if value == 0 {
+                self.bytes.push(byte);
+                return;
+            }
+            self.bytes.push(byte | 0x80);
+        }
+    }
+
+    fn signed(&mut self, value: i32) {
+        self.unsigned(u64::from(((value << 1) ^ (value >> 31)) as u32));
+    }
+
+    fn optional(&mut self, value: Option<u32>) {
+        self.unsigned(value.map_or(0, |value| u64::from(value) + 1));
+    }
+
+    fn string(&mut self, value: &str) {
+        self.unsigned(value.len() as u64);
+        self.bytes.extend_from_slice(value.as_bytes());
+    }
+
+    fn bounds(&mut self, bounds: &WorldBounds) {
+        self.signed(bounds.pos.x);
+        self.signed(bounds.pos.y);
+        self.signed(bounds.dimens.x);
+        self.signed(bounds.dimens.y);
+    }
+
+    fn run(&mut self, length: u64, value: u64) {
+        self.unsigned(length);
+        self.unsigned(value);
+    }
+}
+
+struct Reader<'a> {
+    bytes: &'a [u8],
+    cursor: usize,
+}
+
+impl<'a> Reader<'a> {
+    /// Starts reading right after the magic bytes.
+    fn new(bytes: &'a [u8]) -> Result<Self, LevelFormatError> {
+        let mut reader = Reader { bytes, cursor: 0 };
+        if reader.take(MAGIC.len())? != MAGIC {
+            return Err(invalid("the file does not start with the magic bytes"));
+        }
+        Ok(reader)
+    }
+
+    fn take(&mut self, amount: usize) -> Result<&'a [u8], LevelFormatError> {
+        let end = self
+            .cursor
+            .checked_add(amount)
+            .filter(|end| *end <= self.bytes.len())
+            .ok_or_else(|| invalid("the file ends unexpectedly"))?;
+        let taken = &self.bytes[self.cursor..end];
+        self.cursor = end;
+        Ok(taken)
+    }
+
+    fn unsigned(&mut self) -> Result<u64, LevelFormatError> {
+        let mut value = 0_u64;
+        for shift in (0..64).step_by(7) {
+            let byte = self.take(1)?[0];
+            value |= u64::from(byte & 0x7f) << shift;
+            if byte & 0x80 == 0 {
+                return Ok(value);
+            }
+        }
+        Err(invalid("a number is too large"))
+    }
+
+    fn u32(&mut self) -> Result<u32, LevelFormatError> {
+        let value = self.unsigned()?;
+        if value > u64::from(u32::MAX) {
+            Err(invalid("a number is too large"))
+        } else {
+            Ok(value as u32)
+        }
+    }
+
+    fn signed(&mut self) -> Result<i32, LevelFormatError> {
+        let value = self.u32()?;
+        Ok((value >> 1) as i32 ^ -((value & 1) as i32))
+    }
+
+    fn optional(&mut self) -> Result<Option<u32>, LevelFormatError> {
+        match self.unsigned()? {
+            0 => Ok(None),
+            value if value - 1 > u64::from(u32::MAX) => Err(invalid("a number is too large")),
+            value => Ok(Some((value - 1) as u32)),
+        }
+    }
+
+    fn string(&mut self) -> Result<String, LevelFormatError> {
+        let length = self.unsigned()? as usize;
+        String::from_utf8(self.take(length)?.to_vec())
+            .map_err(|_| invalid("a string is not valid UTF-8"))
+    }
+
+    fn bounds(&mut self) -> Result<WorldBounds, LevelFormatError> {
+        let x = self.signed()?;
+        let y = self.signed()?;
+        let width = self.signed()?;
+        let height = self.signed()?;
+        if width < 0 || height < 0 {
+            return Err(invalid("a rectangle has negative dimensions"));
+        }
+        Ok(WorldBounds::new(x, y, width, height))
+    }
+
+    fn metadata(&mut self) -> Result<LevelMetadata, LevelFormatError> {
+        let title = self.string()?;
+        let author = self.string()?;
+        let description = self.string()?;
+        let difficulty = match self.unsigned()? {
+            0 => None,
+            value => Some(
+                *Difficulty::ALL
+                    .get(value as usize - 1)
+                    .ok_or_else(|| invalid("unknown difficulty"))?,
+            ),
+        };
+        Ok(LevelMetadata {
+            title,
+            author,
+            description,
+            difficulty,
+            par_moves: self.optional()?,
+            par_seconds: self.optional()?,
+        })
+    }
+
+    /// Reads the palette and the grid.
+    fn tiles(&mut self) -> Result<HashMap<Pos, String>, LevelFormatError> {
+        let palette = (0..self.unsigned()?)
+            .map(|_| self.string())
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let grid = self.bounds()?;
+        let cell_count = grid.dimens.x as u64 * grid.dimens.y as u64;
+        let mut tiles = HashMap::new();
+        let mut index = 0;
+        while index < cell_count {
+            let length = self.unsigned()?;
+            let value = self.unsigned()?;
+            if length == 0 || index + length > cell_count {
+                return Err(invalid("a run of tiles does not fit in the grid"));
+            }
+            if value != 0 {
+                let key = palette
+                    .get(value as usize - 1)
+                    .ok_or_else(|| invalid("a tile refers to a key that is not in the palette"))?;
+                for cell in index..index + length {
+                    tiles.insert(cell_pos(&grid, cell), key.clone());
+                }
+            }
+            index += length;
+        }
+        Ok(tiles)
+    }
+
+    fn properties(&mut self) -> Result<HashMap<Pos, TileProperties>, LevelFormatError> {
+        let mut properties = HashMap::new();
+        for _ in 0..self.unsigned()? {
+            let pos = Pos::new(self.signed()?, self.signed()?);
+            let tile_properties = TileProperties::parse(&self.string()?)?;
+            properties.insert(pos, tile_properties);
+        }
+        Ok(properties)
+    }
+}
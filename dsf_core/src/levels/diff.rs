@@ -0,0 +1,236 @@
+use std::collections::BTreeSet;
+use std::fmt;
+
+use crate::components::Pos;
+use crate::levels::{LevelMetadata, LevelSave};
+use crate::resources::{TileProperties, WorldBounds};
+
+/// A single difference between two versions of a level.
+#[derive(Clone, Debug, PartialEq)]
+pub enum LevelChange {
+    /// The world bounds were adjusted.
+    WorldBounds {
+        before: WorldBounds,
+        after: WorldBounds,
+    },
+    /// The title, author, etc. of the level were edited.
+    Metadata {
+        before: LevelMetadata,
+        after: LevelMetadata,
+    },
+    /// A tile was placed where there was none.
+    AddTile { pos: Pos, key: String },
+    /// The tile anchored at this position was removed.
+    RemoveTile { pos: Pos, key: String },
+    /// The tile anchored at this position was replaced by a tile with a different key.
+    ReplaceTile {
+        pos: Pos,
+        before: String,
+        after: String,
+    },
+    /// The instance properties at this position were added, edited or removed.
+    Properties {
+        pos: Pos,
+        before: Option<TileProperties>,
+        after: Option<TileProperties>,
+    },
+}
+
+impl fmt::Display for LevelChange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LevelChange::WorldBounds { before, after } => write!(
+                f,
+                "world bounds: {} -> {}",
+                describe_bounds(before),
+                describe_bounds(after)
+            ),
+            LevelChange::Metadata { before, after } => {
+                write!(
+                    f,
+                    "metadata: {}",
+                    describe_metadata(before, after).join(", ")
+                )
+            }
+            LevelChange::AddTile { pos, key } => write!(f, "+ {:?} at {:?}", key, (pos.x, pos.y)),
+            LevelChange::RemoveTile { pos, key } => {
+                write!(f, "- {:?} at {:?}", key, (pos.x, pos.y))
+            }
+            LevelChange::ReplaceTile { pos, before, after } => {
+                write!(f, "~ {:?} -> {:?} at {:?}", before, after, (pos.x, pos.y))
+            }
+            LevelChange::Properties { pos, before, after } => write!(
+                f,
+                "properties at {:?}: {} -> {}",
+                (pos.x, pos.y),
+                describe_properties(before),
+                describe_properties(after)
+            ),
+        }
+    }
+}
+
+fn describe_bounds(bounds: &WorldBounds) -> String {
+    format!(
+        "{}x{} at {:?}",
+        bounds.width(),
+        bounds.height(),
+        (bounds.x(), bounds.y())
+    )
+}
+
+fn describe_properties(properties: &Option<TileProperties>) -> String {
+    properties
+        .as_ref()
+        .map_or_else(|| "none".to_string(), TileProperties::to_ron)
+}
+
+/// Describes every field that differs, as "field before -> after".
+fn describe_metadata(before: &LevelMetadata, after: &LevelMetadata) -> Vec<String> {
+    let mut fields = vec![];
+    let mut compare = |name: &str, before: String, after: String| {
+        if before != after {
+            fields.push(format!("{} {} -> {}", name, before, after));
+        }
+    };
+    compare(
+        "title",
+        format!("{:?}", before.title),
+        format!("{:?}", after.title),
+    );
+    compare(
+        "author",
+        format!("{:?}", before.author),
+        format!("{:?}", after.author),
+    );
+    compare(
+        "description",
+        format!("{:?}", before.description),
+        format!("{:?}", after.description),
+    );
+    compare(
+        "difficulty",
+        format!("{:?}", before.difficulty),
+        format!("{:?}", after.difficulty),
+    );
+    compare(
+        "par_moves",
+        format!("{:?}", before.par_moves),
+        format!("{:?}", after.par_moves),
+    );
+    compare(
+        "par_seconds",
+        format!("{:?}", before.par_seconds),
+        format!("{:?}", after.par_seconds),
+    );
+    fields
+}
+
+/// Every difference between two versions of a level. Unlike a textual diff of the level files,
+/// this does not depend on the order or the formatting of the files, nor on their format.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct LevelDiff {
+    /// The changes, in a deterministic order: world bounds first, then metadata, then tiles and
+    /// finally instance properties, each sorted by position.
+    pub changes: Vec<LevelChange>,
+}
+
+impl LevelDiff {
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+
+    /// Applies every change to the level. Applying the diff between two levels to the first level
+    /// turns it into the second.
+    pub fn apply_to(&self, level: &mut LevelSave) {
+        for change in &self.changes {
+            match change {
+                LevelChange::WorldBounds { after, .. } => level.world_bounds = *after,
+                LevelChange::Metadata { after, .. } => level.metadata = after.clone(),
+                LevelChange::AddTile { pos, key }
+                | LevelChange::ReplaceTile {
+                    pos, after: key, ..
+                } => {
+                    level.tiles.insert(*pos, key.clone());
+                }
+                LevelChange::RemoveTile { pos, .. } => {
+                    level.tiles.remove(pos);
+                }
+                LevelChange::Properties { pos, after, .. } => {
+                    match after {
+                        Some(properties) => level.properties.insert(*pos, properties.clone()),
+                        None => level.properties.remove(pos),
+                    };
+                }
+            }
+        }
+    }
+}
+
+impl fmt::Display for LevelDiff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for change in &self.changes {
+            writeln!(f, "{}", change)?;
+        }
+        Ok(())
+    }
+}
+
+/// Compares two versions of a level.
+#[must_use]
+pub fn diff_levels(before: &LevelSave, after: &LevelSave) -> LevelDiff {
+    let mut changes = vec![];
+    if before.world_bounds != after.world_bounds {
+        changes.push(LevelChange::WorldBounds {
+            before: before.world_bounds,
+            after: after.world_bounds,
+        });
+    }
+    if before.metadata != after.metadata {
+        changes.push(LevelChange::Metadata {
+            before: before.metadata.clone(),
+            after: after.metadata.clone(),
+        });
+    }
+    let positions = before
+        .tiles
+        .keys()
+        .chain(after.tiles.keys())
+        .collect::<BTreeSet<_>>();
+    for pos in positions {
+        let change = match (before.tiles.get(pos), after.tiles.get(pos)) {
+            (None, Some(key)) => LevelChange::AddTile {
+                pos: *pos,
+                key: key.clone(),
+            },
+            (Some(key), None) => LevelChange::RemoveTile {
+                pos: *pos,
+                key: key.clone(),
+            },
+            (Some(old), Some(new)) if old != new => LevelChange::ReplaceTile {
+                pos: *pos,
+                before: old.clone(),
+                after: new.clone(),
+            },
+            _ => continue,
+        };
+        changes.push(change);
+    }
+    let properties = before
+        .properties
+        .keys()
+        .chain(after.properties.keys())
+        .collect::<BTreeSet<_>>();
+    for pos in properties {
+        let (old, new) = (before.properties.get(pos), after.properties.get(pos));
+        if old != new {
+            changes.push(LevelChange::Properties {
+                pos: *pos,
+                before: old.cloned(),
+                after: new.cloned(),
+            });
+        }
+    }
+    LevelDiff { changes }
+}
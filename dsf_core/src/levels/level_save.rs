@@ -1,29 +1,158 @@
 use crate::components::Pos;
-use crate::resources::WorldBounds;
+use crate::levels::{
+    encode_level, load_level_file, write_ascii_level, LevelFormatError, LevelMetadata,
+    ASCII_LEVEL_EXTENSION, BINARY_LEVEL_EXTENSION, CURRENT_LEVEL_VERSION,
+};
+use crate::resources::{TileDefinition, TileDefinitions, TileProperties, WorldBounds};
+use amethyst::config::Config;
 use serde::{Deserialize, Serialize, Serializer};
 use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::path::Path;
 
 /// Describes a complete level. This is the format that the level is stored in.
 /// Contains a map of positions, mapped to tile definitions.
 /// This struct can be loaded from a level file and used to start a game.
-#[derive(Debug, Deserialize, Serialize, Default)]
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
 #[serde(default)]
 #[serde(deny_unknown_fields)]
 pub struct LevelSave {
+    /// The version of the level format. Older level files are migrated when they are loaded.
+    pub version: u32,
+    /// Information about the level that is shown on the level select screen.
+    pub metadata: LevelMetadata,
     /// The level's exterior borders. In this game, the world wraps at the borders.
     pub world_bounds: WorldBounds,
     /// Mapping of (x,y) position in the world to a TileDefinition key.
     /// These keys can be used to look up the corresponding TileDefinition.
     #[serde(serialize_with = "ordered_map")]
     pub tiles: HashMap<Pos, String>,
+    /// Instance properties of individual tiles, mapped by the position of the tile they belong to.
+    /// They override the properties of the tile's `TileDefinition`.
+    #[serde(serialize_with = "ordered_map")]
+    #[serde(skip_serializing_if = "HashMap::is_empty")]
+    pub properties: HashMap<Pos, TileProperties>,
+}
+
+impl Default for LevelSave {
+    fn default() -> Self {
+        LevelSave {
+            version: CURRENT_LEVEL_VERSION,
+            metadata: LevelMetadata::default(),
+            world_bounds: WorldBounds::default(),
+            tiles: HashMap::default(),
+            properties: HashMap::default(),
+        }
+    }
+}
+
+impl LevelSave {
+    /// Loads a level file of any supported version of the format, migrating it to the current
+    /// version if needed. Use this instead of `Config::load`, which cannot read older versions.
+    /// The format of the file is picked by its extension, see `LevelFormat::of`.
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<LevelSave, LevelFormatError> {
+        let path = path.as_ref();
+        load_level_file(path, LevelFormat::of(path))
+    }
+
+    /// Loads a level file that is in the given format, regardless of its extension.
+    pub fn load_as<P: AsRef<Path>>(
+        path: P,
+        format: LevelFormat,
+    ) -> Result<LevelSave, LevelFormatError> {
+        load_level_file(path.as_ref(), format)
+    }
+
+    /// Writes the level to file, in the format that belongs to its extension.
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<(), LevelFormatError> {
+        let path = path.as_ref();
+        self.save_as(path, LevelFormat::of(path))
+    }
+
+    /// Writes the level to file in the given format, regardless of its extension.
+    ///
+    /// ASCII files written this way do not mark the positions covered by tiles larger than one by
+    /// one, because that requires the tile definitions. Use `write_ascii_level` to include them.
+    pub fn save_as<P: AsRef<Path>>(
+        &self,
+        path: P,
+        format: LevelFormat,
+    ) -> Result<(), LevelFormatError> {
+        let path = path.as_ref();
+        match format {
+            LevelFormat::Ron => self.write(path)?,
+            LevelFormat::Binary => fs::write(path, encode_level(self))?,
+            LevelFormat::Ascii => fs::write(path, write_ascii_level(self, None)?)?,
+        }
+        Ok(())
+    }
+
+    /// Returns the definition of the tile at the given position, with the instance properties of
+    /// that tile applied. The key must be the tile definition key of the tile at that position.
+    #[must_use]
+    pub fn tile_def(&self, pos: Pos, key: &str, tile_defs: &TileDefinitions) -> TileDefinition {
+        let tile_def = tile_defs.get(key).clone();
+        match self.properties.get(&pos) {
+            Some(properties) => properties.apply_to(tile_def),
+            None => tile_def,
+        }
+    }
+}
+
+/// The formats that level files can be stored in.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum LevelFormat {
+    Ron,
+    /// See `encode_level`.
+    Binary,
+    /// See `parse_ascii_level`.
+    Ascii,
+}
+
+impl LevelFormat {
+    /// Every format, starting with the one that new levels are saved in.
+    pub const ALL: [LevelFormat; 3] = [LevelFormat::Ron, LevelFormat::Binary, LevelFormat::Ascii];
+
+    /// Returns the format of the level file, based on its extension. Files with the binary or
+    /// ASCII level extension are in that format, all others are RON.
+    #[must_use]
+    pub fn of(path: &Path) -> LevelFormat {
+        match path.extension().and_then(|extension| extension.to_str()) {
+            Some(BINARY_LEVEL_EXTENSION) => LevelFormat::Binary,
+            Some(ASCII_LEVEL_EXTENSION) => LevelFormat::Ascii,
+            _ => LevelFormat::Ron,
+        }
+    }
+
+    /// The extension of level files in this format.
+    #[must_use]
+    pub fn extension(self) -> &'static str {
+        match self {
+            LevelFormat::Ron => "ron",
+            LevelFormat::Binary => BINARY_LEVEL_EXTENSION,
+            LevelFormat::Ascii => ASCII_LEVEL_EXTENSION,
+        }
+    }
+
+    /// Returns true if the file has the extension of one of the level formats.
+    #[must_use]
+    pub fn is_level_file(path: &Path) -> bool {
+        path.extension().map_or(false, |extension| {
+            LevelFormat::ALL
+                .iter()
+                .any(|format| extension == format.extension())
+        })
+    }
 }
 
 /// A function used by serde to serialise the tile map in a deterministic way.
 /// This will prevent the output being different each time the level is saved, which will
 /// prevent lots of unnecessarily large diffs in the git commits.
-fn ordered_map<S>(value: &HashMap<Pos, String>, serializer: S) -> Result<S::Ok, S::Error>
+/// Also used for other maps keyed by position, such as the tiles of stamps in the editor.
+pub fn ordered_map<S, V>(value: &HashMap<Pos, V>, serializer: S) -> Result<S::Ok, S::Error>
 where
     S: Serializer,
+    V: Serialize,
 {
     let ordered: BTreeMap<_, _> = value.iter().collect();
     ordered.serialize(serializer)
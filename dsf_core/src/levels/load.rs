@@ -15,45 +15,31 @@ use dsf_precompile::MyPrefabData;
 
 use crate::components::{
     BackgroundTag, Block, DebugPosGhostTag, DebugSteeringGhostTag, ExitDoor, Key, KeyDisplay,
-    Player, Pos, Steering, SteeringIntent, Tool, Velocity,
+    Player, Pos, Pushable, Steering, SteeringIntent, Tool, Velocity,
 };
-use crate::levels::LevelSave;
+use crate::levels::{validate_level, LevelFormatError, LevelSave};
 use crate::resources::{
-    get_asset_dimensions, Archetype, AssetType, Assets, DebugSettings, DepthLayer, History,
-    SpriteType, TileDefinition, TileDefinitions, TileMap, WinCondition, WorldBounds,
+    Archetype, AssetType, Assets, DebugSettings, DepthLayer, History, SpriteType, TileDefinition,
+    TileDefinitions, TileMap, WinCondition, WorldBounds,
 };
-use crate::utility::files::get_world_dir;
+use crate::utility::files::get_tile_definitions_file;
 
 pub fn load_tile_definitions() -> Result<TileDefinitions, ConfigError> {
-    let file = get_world_dir().join("tile_references.ron");
-    TileDefinitions::load(file)
+    TileDefinitions::load(get_tile_definitions_file())
 }
 
-pub fn load_level(level_file: &Path, world: &mut World) -> Result<(), ConfigError> {
+pub fn load_level(level_file: &Path, world: &mut World) -> Result<(), LevelFormatError> {
     let mut win_condition = WinCondition::default();
     let display_debug_frames = world.read_resource::<DebugSettings>().display_debug_frames;
     let tile_defs = load_tile_definitions()?;
     let level = LevelSave::load(level_file)?;
+    for issue in validate_level(&level, &tile_defs) {
+        error!("Level {:?} is invalid: {}", level_file, issue);
+    }
     add_background(world, &level.world_bounds);
     level.tiles.iter().for_each(|(pos, tile_def_key)| {
-        let tile_def = tile_defs.get(tile_def_key);
-        let still_asset = load_still_asset(tile_def, &world.read_resource::<Assets>());
-        let anim_asset = load_anim_asset(tile_def, &world.read_resource::<Assets>());
-        let transform = tile_def
-            .asset
-            .as_ref()
-            .map(|asset| load_transform(*pos, tile_def.depth, tile_def.dimens, asset));
-        let mut builder = world.create_entity();
-        if let Some(still_asset) = still_asset {
-            builder = builder.with(still_asset);
-        }
-        if let Some(anim_asset) = anim_asset {
-            builder = builder.with(anim_asset);
-        }
-        if let Some(transform) = transform {
-            builder = builder.with(transform);
-        }
-        builder = builder.with(Block { pos: *pos });
+        let tile_def = &level.tile_def(*pos, tile_def_key, &tile_defs);
+        let builder = create_tile_entity(world, *pos, tile_def);
         match tile_def.archetype {
             Some(Archetype::Player) => {
                 let _ = build_player(builder, *pos, tile_def);
@@ -66,7 +52,7 @@ pub fn load_level(level_file: &Path, world: &mut World) -> Result<(), ConfigErro
                 builder.with(Key::new(*pos)).build();
             }
             Some(Archetype::Tool(tool_type)) => {
-                if let Some(AssetType::Still(sprite, sprite_nr)) = tile_def.asset {
+                if let Some(AssetType::Still(sprite, sprite_nr)) = tile_def.asset.clone() {
                     builder
                         .with(Tool::new(tool_type, sprite, sprite_nr))
                         .build();
@@ -80,6 +66,14 @@ pub fn load_level(level_file: &Path, world: &mut World) -> Result<(), ConfigErro
             Some(Archetype::Door) => {
                 builder.with(ExitDoor).build();
             }
+            Some(Archetype::Pushable) => {
+                builder
+                    .with(Pushable)
+                    .with(Velocity::default())
+                    .with(SteeringIntent::default())
+                    .with(Steering::new(*pos, tile_def.dimens))
+                    .build();
+            }
             _ => {
                 builder.build();
             }
@@ -92,6 +86,37 @@ pub fn load_level(level_file: &Path, world: &mut World) -> Result<(), ConfigErro
     Ok(())
 }
 
+/// Starts building the entity for a tile at the given position, with its sprite or animation, its
+/// transform and a `Block` component. Components that depend on the archetype are not added.
+pub fn create_tile_entity<'a>(
+    world: &'a mut World,
+    pos: Pos,
+    tile_def: &TileDefinition,
+) -> EntityBuilder<'a> {
+    let (still_asset, anim_asset, transform) = {
+        let assets = world.read_resource::<Assets>();
+        (
+            load_still_asset(tile_def, &assets),
+            load_anim_asset(tile_def, &assets),
+            tile_def
+                .asset
+                .as_ref()
+                .map(|asset| load_transform(pos, tile_def.depth, tile_def.dimens, asset, &assets)),
+        )
+    };
+    let mut builder = world.create_entity();
+    if let Some(still_asset) = still_asset {
+        builder = builder.with(still_asset);
+    }
+    if let Some(anim_asset) = anim_asset {
+        builder = builder.with(anim_asset);
+    }
+    if let Some(transform) = transform {
+        builder = builder.with(transform);
+    }
+    builder.with(Block { pos })
+}
+
 fn build_player(builder: EntityBuilder<'_>, pos: Pos, tile_def: &TileDefinition) -> Entity {
     builder
         .with(Transparent)
@@ -107,9 +132,10 @@ pub fn add_background(world: &mut World, world_bounds: &WorldBounds) {
         world_bounds.pos,
         DepthLayer::Background,
         world_bounds.dimens,
-        &AssetType::Still(SpriteType::Selection, 1),
+        &AssetType::Still(SpriteType::SELECTION, 1),
+        &world.read_resource::<Assets>(),
     );
-    let asset = load_asset_from_world(SpriteType::Selection, 1, world);
+    let asset = load_asset_from_world(SpriteType::SELECTION, 1, world);
     world
         .create_entity()
         .with(BackgroundTag)
@@ -158,7 +184,7 @@ fn add_key_displays_to_door(world: &mut World, win_condition: &WinCondition) {
                 transform.set_translation_y((-1.5 + y_offset as f32) * 64.);
                 transform.set_translation_z(1.); //One higher than parent.
                 transform.set_scale(Vector3::new(0.5, 0.5, 1.0));
-                let sprite = load_asset_from_world(SpriteType::Blocks, 3, world);
+                let sprite = load_asset_from_world(SpriteType::BLOCKS, 3, world);
                 world
                     .create_entity()
                     .with(Parent {
@@ -173,30 +199,35 @@ fn add_key_displays_to_door(world: &mut World, win_condition: &WinCondition) {
 }
 
 fn build_frames(world: &mut World, tile_def: &TileDefinition) {
-    let frame = world.read_resource::<Assets>().get_still(SpriteType::Frame);
-    let sprite_render = SpriteRender {
-        sprite_sheet: frame,
-        sprite_number: 0, // First sprite
+    let frame_asset = AssetType::Still(SpriteType::FRAME, 0);
+    let (sprite_render, steering_ghost_transform, pos_ghost_transform) = {
+        let assets = world.read_resource::<Assets>();
+        let sprite_render = SpriteRender {
+            sprite_sheet: assets.get_still(&SpriteType::FRAME),
+            sprite_number: 0, // First sprite
+        };
+        let steering_ghost_transform = load_transform(
+            Pos::default(),
+            DepthLayer::UiElements,
+            tile_def.dimens,
+            &frame_asset,
+            &assets,
+        );
+        let pos_ghost_transform = load_transform(
+            Pos::default(),
+            DepthLayer::UiElements,
+            Pos::new(1, 1),
+            &frame_asset,
+            &assets,
+        );
+        (sprite_render, steering_ghost_transform, pos_ghost_transform)
     };
-
-    let steering_ghost_transform = load_transform(
-        Pos::default(),
-        DepthLayer::UiElements,
-        tile_def.dimens,
-        &AssetType::Still(SpriteType::Frame, 0),
-    );
     world
         .create_entity()
         .with(sprite_render.clone())
         .with(steering_ghost_transform)
         .with(DebugSteeringGhostTag)
         .build();
-    let pos_ghost_transform = load_transform(
-        Pos::default(),
-        DepthLayer::UiElements,
-        Pos::new(1, 1),
-        &AssetType::Still(SpriteType::Frame, 0),
-    );
     world
         .create_entity()
         .with(sprite_render)
@@ -206,8 +237,14 @@ fn build_frames(world: &mut World, tile_def: &TileDefinition) {
 }
 
 #[must_use]
-pub fn load_transform(pos: Pos, depth: DepthLayer, dimens: Pos, asset: &AssetType) -> Transform {
-    let asset_dimensions = get_asset_dimensions(asset);
+pub fn load_transform(
+    pos: Pos,
+    depth: DepthLayer,
+    dimens: Pos,
+    asset: &AssetType,
+    assets: &Assets,
+) -> Transform {
+    let asset_dimensions = assets.get_dimensions(asset);
     let mut transform = Transform::default();
     transform.set_translation_xyz(
         pos.x as f32 + dimens.x as f32 * 0.5,
@@ -224,10 +261,10 @@ pub fn load_transform(pos: Pos, depth: DepthLayer, dimens: Pos, asset: &AssetTyp
 
 #[must_use]
 pub fn load_still_asset(tile: &TileDefinition, assets: &Assets) -> Option<SpriteRender> {
-    match &tile.asset? {
+    match tile.asset.as_ref()? {
         AssetType::Animated(..) => None,
         AssetType::Still(spritesheet, sprite_nr) => {
-            let handle = assets.get_still(*spritesheet);
+            let handle = assets.get_still(spritesheet);
             Some(SpriteRender {
                 sprite_sheet: handle,
                 sprite_number: *sprite_nr,
@@ -243,7 +280,7 @@ pub fn load_asset_from_world(
 ) -> SpriteRender {
     let assets = world.read_resource::<Assets>();
     SpriteRender {
-        sprite_sheet: assets.get_still(sprite),
+        sprite_sheet: assets.get_still(&sprite),
         sprite_number: sprite_nr,
     }
 }
@@ -251,7 +288,7 @@ pub fn load_asset_from_world(
 #[must_use]
 pub fn load_sprite_render(sprite: SpriteType, sprite_nr: usize, assets: &Assets) -> SpriteRender {
     SpriteRender {
-        sprite_sheet: assets.get_still(sprite),
+        sprite_sheet: assets.get_still(&sprite),
         sprite_number: sprite_nr,
     }
 }
@@ -261,9 +298,9 @@ pub fn load_anim_asset(
     tile: &TileDefinition,
     assets: &Assets,
 ) -> Option<Handle<Prefab<MyPrefabData>>> {
-    match &tile.asset? {
+    match tile.asset.as_ref()? {
         AssetType::Animated(anim) => {
-            let handle = assets.get_animated(*anim);
+            let handle = assets.get_animated(anim);
             Some(handle)
         }
         AssetType::Still(..) => None,
@@ -277,11 +314,12 @@ pub fn attach_graphics(
     dimens: Pos,
     tint: Option<Tint>,
 ) -> Entity {
-    let still_asset = if let AssetType::Still(sprite, sprite_nr) = *asset {
-        Some(load_asset_from_world(sprite, sprite_nr, world))
+    let still_asset = if let AssetType::Still(sprite, sprite_nr) = asset {
+        Some(load_asset_from_world(sprite.clone(), *sprite_nr, world))
     } else {
         None
     };
+    let asset_dimensions = world.read_resource::<Assets>().get_dimensions(asset);
 
     let mut builder = world.create_entity();
     if let Some(still_asset) = still_asset {
@@ -296,12 +334,11 @@ pub fn attach_graphics(
     builder
         .with(Transparent) // TODO: only attach this when needed.
         .with(Parent { entity })
-        .with(transform_scale(dimens, asset))
+        .with(transform_scale(dimens, asset_dimensions))
         .build()
 }
 
-fn transform_scale(dimens: Pos, asset: &AssetType) -> Transform {
-    let asset_dimensions = get_asset_dimensions(asset);
+fn transform_scale(dimens: Pos, asset_dimensions: Pos) -> Transform {
     let mut transform = Transform::default();
     transform.set_scale(Vector3::new(
         dimens.x as f32 / asset_dimensions.x as f32,
@@ -0,0 +1,272 @@
+use std::collections::{BTreeSet, HashMap};
+use std::fmt;
+use std::hash::Hash;
+
+use crate::components::Pos;
+use crate::levels::{LevelMetadata, LevelSave};
+use crate::resources::{TileDefinitions, WorldBounds};
+
+/// A part of the level that both sides of a merge changed in different ways.
+/// The merged level contains our version of it.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum MergeConflict {
+    /// Both sides adjusted the world bounds.
+    WorldBounds,
+    /// Both sides edited this metadata field.
+    Metadata { field: &'static str },
+    /// Both sides placed, removed or replaced the tile anchored at this position.
+    /// Contains the key each side ended up with; None if that side removed the tile.
+    Tile {
+        pos: Pos,
+        ours: Option<String>,
+        theirs: Option<String>,
+    },
+    /// A tile that we placed overlaps a tile that they placed.
+    Overlap { ours: Pos, theirs: Pos },
+    /// Both sides edited the instance properties at this position.
+    Properties { pos: Pos },
+}
+
+impl fmt::Display for MergeConflict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MergeConflict::WorldBounds => write!(f, "both sides changed the world bounds"),
+            MergeConflict::Metadata { field } => {
+                write!(f, "both sides changed the {} of the level", field)
+            }
+            MergeConflict::Tile { pos, ours, theirs } => write!(
+                f,
+                "both sides changed the tile at {:?}: ours is {}, theirs is {}",
+                (pos.x, pos.y),
+                ours.as_ref()
+                    .map_or_else(|| "removed".to_string(), |key| format!("{:?}", key)),
+                theirs
+                    .as_ref()
+                    .map_or_else(|| "removed".to_string(), |key| format!("{:?}", key))
+            ),
+            MergeConflict::Overlap { ours, theirs } => write!(
+                f,
+                "our tile at {:?} overlaps their tile at {:?}",
+                (ours.x, ours.y),
+                (theirs.x, theirs.y)
+            ),
+            MergeConflict::Properties { pos } => write!(
+                f,
+                "both sides changed the tile properties at {:?}",
+                (pos.x, pos.y)
+            ),
+        }
+    }
+}
+
+/// The result of a three-way merge.
+#[derive(Clone, Debug)]
+pub struct MergeOutcome {
+    /// The merged level. Where there are conflicts, it contains our version.
+    pub level: LevelSave,
+    /// Every conflict that needs to be resolved by hand, sorted by kind and position.
+    /// The merge succeeded if this is empty.
+    pub conflicts: Vec<MergeConflict>,
+}
+
+/// The three versions of a single value in a three-way merge.
+enum Merged<T> {
+    Value(T),
+    Conflict,
+}
+
+impl<T> Merged<T> {
+    fn or_conflict(self, field: &'static str, ours: T, conflicts: &mut Vec<&'static str>) -> T {
+        match self {
+            Merged::Value(value) => value,
+            Merged::Conflict => {
+                conflicts.push(field);
+                ours
+            }
+        }
+    }
+}
+
+fn merge_value<T: PartialEq + Clone>(base: &T, ours: &T, theirs: &T) -> Merged<T> {
+    if ours == theirs || base == theirs {
+        Merged::Value(ours.clone())
+    } else if base == ours {
+        Merged::Value(theirs.clone())
+    } else {
+        Merged::Conflict
+    }
+}
+
+/// Merges two versions of a map that were both derived from the base version, key by key.
+/// Returns the merged map and the keys that both sides changed in different ways.
+fn merge_maps<K, V>(
+    base: &HashMap<K, V>,
+    ours: &HashMap<K, V>,
+    theirs: &HashMap<K, V>,
+) -> (HashMap<K, V>, Vec<K>)
+where
+    K: Copy + Eq + Hash + Ord,
+    V: Clone + PartialEq,
+{
+    let keys = base
+        .keys()
+        .chain(ours.keys())
+        .chain(theirs.keys())
+        .collect::<BTreeSet<_>>();
+    let mut merged = HashMap::new();
+    let mut conflicts = vec![];
+    for key in keys {
+        let (old, mine, other) = (base.get(key), ours.get(key), theirs.get(key));
+        let value = match merge_value(&old, &mine, &other) {
+            Merged::Value(value) => value,
+            Merged::Conflict => {
+                conflicts.push(*key);
+                mine
+            }
+        };
+        if let Some(value) = value {
+            merged.insert(*key, value.clone());
+        }
+    }
+    (merged, conflicts)
+}
+
+/// Merges the changes that two sides made to the same base version of a level.
+///
+/// Changes are merged per tile, per instance properties, per metadata field and for the world
+/// bounds as a whole. A change that only one side made is taken over. If both sides changed the
+/// same thing in different ways, that is a conflict. Tiles placed by the two sides that overlap
+/// each other are a conflict as well, even if they are anchored at different positions; the tile
+/// definitions are needed to find those.
+#[must_use]
+pub fn merge_levels(
+    base: &LevelSave,
+    ours: &LevelSave,
+    theirs: &LevelSave,
+    tile_defs: &TileDefinitions,
+) -> MergeOutcome {
+    let mut conflicts = vec![];
+    let mut level = ours.clone();
+
+    match merge_value(&base.world_bounds, &ours.world_bounds, &theirs.world_bounds) {
+        Merged::Value(bounds) => level.world_bounds = bounds,
+        Merged::Conflict => conflicts.push(MergeConflict::WorldBounds),
+    }
+    let (metadata, fields) = merge_metadata(&base.metadata, &ours.metadata, &theirs.metadata);
+    level.metadata = metadata;
+    conflicts.extend(
+        fields
+            .into_iter()
+            .map(|field| MergeConflict::Metadata { field }),
+    );
+
+    let (tiles, tile_conflicts) = merge_maps(&base.tiles, &ours.tiles, &theirs.tiles);
+    level.tiles = tiles;
+    conflicts.extend(tile_conflicts.into_iter().map(|pos| MergeConflict::Tile {
+        pos,
+        ours: ours.tiles.get(&pos).cloned(),
+        theirs: theirs.tiles.get(&pos).cloned(),
+    }));
+    let (properties, property_conflicts) =
+        merge_maps(&base.properties, &ours.properties, &theirs.properties);
+    level.properties = properties;
+    let overlaps = find_overlaps(base, ours, theirs, tile_defs);
+    for (ours_pos, theirs_pos) in &overlaps {
+        // Keep our tile. Their tile is dropped, unless it was already there in the base version.
+        // Their instance properties at that position are dropped along with it.
+        match base.tiles.get(theirs_pos) {
+            Some(key) => level.tiles.insert(*theirs_pos, key.clone()),
+            None => level.tiles.remove(theirs_pos),
+        };
+        match ours.properties.get(theirs_pos) {
+            Some(properties) => level.properties.insert(*theirs_pos, properties.clone()),
+            None => level.properties.remove(theirs_pos),
+        };
+        conflicts.push(MergeConflict::Overlap {
+            ours: *ours_pos,
+            theirs: *theirs_pos,
+        });
+    }
+    conflicts.extend(
+        property_conflicts
+            .into_iter()
+            .map(|pos| MergeConflict::Properties { pos }),
+    );
+    MergeOutcome { level, conflicts }
+}
+
+/// Merges the metadata field by field. Returns the merged metadata, which contains our version of
+/// the conflicting fields, and the names of those fields.
+fn merge_metadata(
+    base: &LevelMetadata,
+    ours: &LevelMetadata,
+    theirs: &LevelMetadata,
+) -> (LevelMetadata, Vec<&'static str>) {
+    let mut conflicts = vec![];
+    let merged =
+        LevelMetadata {
+            title: merge_value(&base.title, &ours.title, &theirs.title).or_conflict(
+                "title",
+                ours.title.clone(),
+                &mut conflicts,
+            ),
+            author: merge_value(&base.author, &ours.author, &theirs.author).or_conflict(
+                "author",
+                ours.author.clone(),
+                &mut conflicts,
+            ),
+            description: merge_value(&base.description, &ours.description, &theirs.description)
+                .or_conflict("description", ours.description.clone(), &mut conflicts),
+            difficulty: merge_value(&base.difficulty, &ours.difficulty, &theirs.difficulty)
+                .or_conflict("difficulty", ours.difficulty, &mut conflicts),
+            par_moves: merge_value(&base.par_moves, &ours.par_moves, &theirs.par_moves)
+                .or_conflict("par_moves", ours.par_moves, &mut conflicts),
+            par_seconds: merge_value(&base.par_seconds, &ours.par_seconds, &theirs.par_seconds)
+                .or_conflict("par_seconds", ours.par_seconds, &mut conflicts),
+        };
+    (merged, conflicts)
+}
+
+/// Finds the tiles that we placed (or replaced) that overlap the tiles that they placed.
+/// Returns pairs of anchor positions: our tile first, their tile second.
+fn find_overlaps(
+    base: &LevelSave,
+    ours: &LevelSave,
+    theirs: &LevelSave,
+    tile_defs: &TileDefinitions,
+) -> Vec<(Pos, Pos)> {
+    let placed = |side: &LevelSave| {
+        side.tiles
+            .iter()
+            .filter(|(pos, key)| base.tiles.get(pos) != Some(key))
+            .map(|(pos, key)| (*pos, footprint(*pos, key, side, tile_defs)))
+            .collect::<Vec<_>>()
+    };
+    let (ours_placed, theirs_placed) = (placed(ours), placed(theirs));
+    let mut overlaps = BTreeSet::new();
+    for (ours_pos, ours_bounds) in &ours_placed {
+        for (theirs_pos, theirs_bounds) in &theirs_placed {
+            // Tiles placed at the same position are handled by merging the tiles map.
+            if ours_pos != theirs_pos && intersects(ours_bounds, theirs_bounds) {
+                overlaps.insert((*ours_pos, *theirs_pos));
+            }
+        }
+    }
+    overlaps.into_iter().collect()
+}
+
+fn footprint(pos: Pos, key: &str, level: &LevelSave, tile_defs: &TileDefinitions) -> WorldBounds {
+    let dimens = if tile_defs.map.contains_key(key) {
+        level.tile_def(pos, key, tile_defs).dimens
+    } else {
+        Pos::new(1, 1)
+    };
+    WorldBounds::new(pos.x, pos.y, dimens.x, dimens.y)
+}
+
+fn intersects(first: &WorldBounds, second: &WorldBounds) -> bool {
+    first.x() < second.x() + second.width()
+        && second.x() < first.x() + first.width()
+        && first.y() < second.y() + second.height()
+        && second.y() < first.y() + first.height()
+}
@@ -0,0 +1,98 @@
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+/// Information about a level that is not needed to play it, but is shown to players on the level
+/// select screen. Every field is optional; fields that are left empty are simply not shown.
+#[derive(Clone, Debug, Default, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(default)]
+#[serde(deny_unknown_fields)]
+pub struct LevelMetadata {
+    /// The name of the level as it is shown to players. If empty, the file name is shown instead.
+    pub title: String,
+    pub author: String,
+    /// A hint or a short description of the level.
+    pub description: String,
+    pub difficulty: Option<Difficulty>,
+    /// The number of moves in which the author was able to complete the level.
+    pub par_moves: Option<u32>,
+    /// The number of seconds in which the author was able to complete the level.
+    pub par_seconds: Option<u32>,
+}
+
+impl LevelMetadata {
+    /// Returns the title of the level, or the given fallback if the level has no title.
+    #[must_use]
+    pub fn display_title<'a>(&'a self, fallback: &'a str) -> &'a str {
+        if self.title.is_empty() {
+            fallback
+        } else {
+            &self.title
+        }
+    }
+
+    /// Describes the author, difficulty, par and description of the level, one per line.
+    /// Fields that are empty are left out.
+    #[must_use]
+    pub fn details(&self) -> String {
+        let mut lines = vec![];
+        if !self.author.is_empty() {
+            lines.push(format!("By {}", self.author));
+        }
+        if let Some(difficulty) = self.difficulty {
+            lines.push(format!("Difficulty: {}", difficulty));
+        }
+        let par = match (self.par_moves, self.par_seconds) {
+            (Some(moves), Some(seconds)) => Some(format!("{} moves, {} seconds", moves, seconds)),
+            (Some(moves), None) => Some(format!("{} moves", moves)),
+            (None, Some(seconds)) => Some(format!("{} seconds", seconds)),
+            (None, None) => None,
+        };
+        if let Some(par) = par {
+            lines.push(format!("Par: {}", par));
+        }
+        if !self.description.is_empty() {
+            lines.push(self.description.clone());
+        }
+        lines.join("\n")
+    }
+}
+
+/// How hard a level is, according to its author.
+#[derive(Copy, Clone, Debug, Deserialize, Serialize, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Difficulty {
+    Easy,
+    Medium,
+    Hard,
+    Expert,
+}
+
+impl Difficulty {
+    pub const ALL: [Difficulty; 4] = [
+        Difficulty::Easy,
+        Difficulty::Medium,
+        Difficulty::Hard,
+        Difficulty::Expert,
+    ];
+
+    /// Parses the name of a difficulty, ignoring case. Returns None if there is no such difficulty.
+    #[must_use]
+    pub fn parse(name: &str) -> Option<Difficulty> {
+        Difficulty::ALL
+            .iter()
+            .copied()
+            .find(|difficulty| difficulty.to_string().eq_ignore_ascii_case(name.trim()))
+    }
+}
+
+impl fmt::Display for Difficulty {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Difficulty::Easy => "Easy",
+            Difficulty::Medium => "Medium",
+            Difficulty::Hard => "Hard",
+            Difficulty::Expert => "Expert",
+        };
+        write!(f, "{}", name)
+    }
+}
@@ -0,0 +1,196 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use amethyst::config::ConfigError;
+use serde::Deserialize;
+
+use crate::components::Pos;
+use crate::levels::{
+    binary_level_version, decode_level, parse_ascii_level, AsciiLevelError, LevelFormat,
+    LevelMetadata, LevelSave,
+};
+use crate::resources::WorldBounds;
+
+/// The version of the level format that is written by this build of the game.
+/// Increase this whenever `LevelSave` changes in a way that older level files can no longer be
+/// read, and add a migration from the previous version to `parse_level` and `decode_level`.
+pub const CURRENT_LEVEL_VERSION: u32 = 3;
+
+/// The reasons a level file could not be loaded or upgraded.
+#[derive(Debug)]
+pub enum LevelFormatError {
+    /// The level file could not be read.
+    Io(io::Error),
+    /// The contents of the level file do not match the format of its version.
+    Parse(ron::de::Error),
+    /// The level file was written by a newer version of the game, with this format version.
+    UnsupportedVersion(u32),
+    /// The binary level file is truncated or corrupt, for the given reason.
+    InvalidBinary(String),
+    /// The ASCII level file could not be read, or the level could not be drawn in ASCII.
+    Ascii(AsciiLevelError),
+    /// A config file could not be read or written. For example: the tile definitions could not
+    /// be loaded, or the upgraded level could not be written back to file.
+    Config(ConfigError),
+}
+
+impl fmt::Display for LevelFormatError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LevelFormatError::Io(error) => write!(f, "failed to read the level: {}", error),
+            LevelFormatError::Parse(error) => write!(f, "failed to parse the level: {}", error),
+            LevelFormatError::UnsupportedVersion(version) => write!(
+                f,
+                "the level has format version {}, but this build only supports up to version {}",
+                version, CURRENT_LEVEL_VERSION
+            ),
+            LevelFormatError::InvalidBinary(reason) => {
+                write!(f, "the binary level is invalid: {}", reason)
+            }
+            LevelFormatError::Ascii(error) => write!(f, "{}", error),
+            LevelFormatError::Config(error) => write!(f, "{}", error),
+        }
+    }
+}
+
+impl From<io::Error> for LevelFormatError {
+    fn from(error: io::Error) -> Self {
+        LevelFormatError::Io(error)
+    }
+}
+
+impl From<ConfigError> for LevelFormatError {
+    fn from(error: ConfigError) -> Self {
+        LevelFormatError::Config(error)
+    }
+}
+
+impl From<AsciiLevelError> for LevelFormatError {
+    fn from(error: AsciiLevelError) -> Self {
+        LevelFormatError::Ascii(error)
+    }
+}
+
+impl From<ron::de::Error> for LevelFormatError {
+    fn from(error: ron::de::Error) -> Self {
+        LevelFormatError::Parse(error)
+    }
+}
+
+/// Only reads the version of a level file, ignoring everything else in it.
+/// Level files from before versioning was introduced have no version field; they are version 0.
+#[derive(Deserialize)]
+struct VersionProbe {
+    #[serde(default)]
+    version: u32,
+}
+
+/// Version 0 of the level format: the original format, without a version field.
+#[derive(Default, Deserialize)]
+#[serde(default)]
+#[serde(deny_unknown_fields)]
+pub(crate) struct LevelSaveV0 {
+    pub(crate) world_bounds: WorldBounds,
+    pub(crate) tiles: HashMap<Pos, String>,
+}
+
+/// Version 1 of the level format added the version field.
+#[derive(Default, Deserialize)]
+#[serde(default)]
+#[serde(deny_unknown_fields)]
+pub(crate) struct LevelSaveV1 {
+    pub(crate) version: u32,
+    pub(crate) world_bounds: WorldBounds,
+    pub(crate) tiles: HashMap<Pos, String>,
+}
+
+pub(crate) fn migrate_v0_to_v1(level: LevelSaveV0) -> LevelSaveV1 {
+    LevelSaveV1 {
+        version: 1,
+        world_bounds: level.world_bounds,
+        tiles: level.tiles,
+    }
+}
+
+/// Version 2 of the level format added the metadata: title, author, etc.
+#[derive(Default, Deserialize)]
+#[serde(default)]
+#[serde(deny_unknown_fields)]
+pub(crate) struct LevelSaveV2 {
+    pub(crate) version: u32,
+    pub(crate) metadata: LevelMetadata,
+    pub(crate) world_bounds: WorldBounds,
+    pub(crate) tiles: HashMap<Pos, String>,
+}
+
+pub(crate) fn migrate_v1_to_v2(level: LevelSaveV1) -> LevelSaveV2 {
+    LevelSaveV2 {
+        version: 2,
+        metadata: LevelMetadata::default(),
+        world_bounds: level.world_bounds,
+        tiles: level.tiles,
+    }
+}
+
+/// Version 3 added the instance properties of individual tiles.
+pub(crate) fn migrate_v2_to_v3(level: LevelSaveV2) -> LevelSave {
+    LevelSave {
+        version: 3,
+        metadata: level.metadata,
+        world_bounds: level.world_bounds,
+        tiles: level.tiles,
+        properties: HashMap::new(),
+    }
+}
+
+/// Parses the contents of a level file of any supported version, migrating it to the current
+/// version of the format.
+pub fn parse_level(contents: &str) -> Result<LevelSave, LevelFormatError> {
+    let version = ron::de::from_str::<VersionProbe>(contents)?.version;
+    match version {
+        0 => {
+            let level = migrate_v0_to_v1(ron::de::from_str(contents)?);
+            Ok(migrate_v2_to_v3(migrate_v1_to_v2(level)))
+        }
+        1 => Ok(migrate_v2_to_v3(migrate_v1_to_v2(ron::de::from_str(
+            contents,
+        )?))),
+        2 => Ok(migrate_v2_to_v3(ron::de::from_str(contents)?)),
+        CURRENT_LEVEL_VERSION => Ok(ron::de::from_str(contents)?),
+        _ => Err(LevelFormatError::UnsupportedVersion(version)),
+    }
+}
+
+/// Loads the level file and migrates it to the current version of the format, if needed.
+/// The file itself is left untouched; use `upgrade_level_file` to store the migrated level.
+pub fn load_level_file(path: &Path, format: LevelFormat) -> Result<LevelSave, LevelFormatError> {
+    match format {
+        LevelFormat::Ron => parse_level(&fs::read_to_string(path)?),
+        LevelFormat::Binary => decode_level(&fs::read(path)?),
+        LevelFormat::Ascii => Ok(parse_ascii_level(&fs::read_to_string(path)?)?),
+    }
+}
+
+/// Rewrites the level file in the current version of the format.
+/// Returns true if the file was upgraded, false if it already was in the current version.
+pub fn upgrade_level_file(path: &Path) -> Result<bool, LevelFormatError> {
+    let format = LevelFormat::of(path);
+    let version = match format {
+        LevelFormat::Ron => ron::de::from_str::<VersionProbe>(&fs::read_to_string(path)?)?.version,
+        LevelFormat::Binary => binary_level_version(&fs::read(path)?)?,
+        LevelFormat::Ascii => {
+            // ASCII files are not versioned, they are always read as the current version.
+            // Check that this one can be read.
+            load_level_file(path, format)?;
+            return Ok(false);
+        }
+    };
+    if version == CURRENT_LEVEL_VERSION {
+        return Ok(false);
+    }
+    load_level_file(path, format)?.save(path)?;
+    Ok(true)
+}
@@ -1,5 +1,23 @@
+mod ascii;
+mod binary;
+mod diff;
 mod level_save;
 mod load;
+mod merge;
+mod metadata;
+mod migration;
+mod thumbnail;
+mod tiled;
+mod validation;
 
+pub use self::ascii::*;
+pub use self::binary::*;
+pub use self::diff::*;
 pub use self::level_save::*;
 pub use self::load::*;
+pub use self::merge::*;
+pub use self::metadata::*;
+pub use self::migration::*;
+pub use self::thumbnail::*;
+pub use self::tiled::*;
+pub use self::validation::*;
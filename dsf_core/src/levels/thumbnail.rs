@@ -0,0 +1,273 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use amethyst::renderer::sprite::{Sprite, Sprites};
+use image::{ImageError, Rgba, RgbaImage};
+
+use crate::components::Pos;
+use crate::levels::LevelSave;
+use crate::resources::{AssetType, SpriteType, TileDefinitions};
+use crate::utility::loading_config::LoadingConfig;
+
+/// The number of samples taken per pixel in each direction is capped at this, which limits the
+/// cost of shrinking the large sprites to small thumbnails.
+const MAX_SAMPLES: u32 = 8;
+
+#[derive(Debug)]
+pub enum ThumbnailError {
+    /// A texture could not be read or decoded.
+    Image { path: PathBuf, error: ImageError },
+    /// A sprite sheet definition could not be read, or the thumbnail could not be written.
+    Io { path: PathBuf, error: io::Error },
+    /// A sprite sheet definition could not be parsed.
+    SpriteSheet {
+        path: PathBuf,
+        error: ron::de::Error,
+    },
+}
+
+impl fmt::Display for ThumbnailError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ThumbnailError::Image { path, error } => {
+                write!(f, "failed to load image {}: {}", path.display(), error)
+            }
+            ThumbnailError::Io { path, error } => {
+                write!(f, "failed to read or write {}: {}", path.display(), error)
+            }
+            ThumbnailError::SpriteSheet { path, error } => {
+                write!(
+                    f,
+                    "failed to parse sprite sheet {}: {}",
+                    path.display(),
+                    error
+                )
+            }
+        }
+    }
+}
+
+/// The textures of a single sprite sheet, decoded into memory, and the sprites on it.
+struct SpriteSheetImage {
+    texture: RgbaImage,
+    sprites: Vec<Sprite>,
+    /// The dimensions declared for the sheet in the `LoadingConfig`.
+    dimens: Pos,
+}
+
+/// The still images of the game, decoded in memory so that levels can be drawn without a GPU.
+/// This is the software counterpart of the sprite sheets in `Assets`.
+pub struct SpriteSheets {
+    sheets: HashMap<SpriteType, SpriteSheetImage>,
+}
+
+impl SpriteSheets {
+    /// Loads every still listed in the `LoadingConfig`. Paths are relative to the assets directory,
+    /// as they are for the game.
+    pub fn load(assets_dir: &Path, config: &LoadingConfig) -> Result<Self, ThumbnailError> {
+        let mut sheets = HashMap::new();
+        for still in &config.stills {
+            let texture_path = assets_dir.join(&still.texture);
+            let texture = image::open(&texture_path)
+                .map_err(|error| ThumbnailError::Image {
+                    path: texture_path,
+                    error,
+                })?
+                .to_rgba();
+            let sprite_sheet_path = assets_dir.join(&still.sprite_sheet);
+            let sprites = fs::read_to_string(&sprite_sheet_path)
+                .map_err(|error| ThumbnailError::Io {
+                    path: sprite_sheet_path.clone(),
+                    error,
+                })
+                .and_then(|text| {
+                    ron::de::from_str::<Sprites>(&text).map_err(|error| {
+                        ThumbnailError::SpriteSheet {
+                            path: sprite_sheet_path,
+                            error,
+                        }
+                    })
+                })?;
+            let sprites = match sprites {
+                Sprites::List(list) => list.build_sprites(),
+                Sprites::Grid(grid) => grid.build_sprites(),
+            };
+            sheets.insert(
+                still.id.clone(),
+                SpriteSheetImage {
+                    texture,
+                    sprites,
+                    dimens: still.dimens,
+                },
+            );
+        }
+        Ok(SpriteSheets { sheets })
+    }
+
+    /// Returns the texture, the sprite and the sheet dimensions for a still asset. Like
+    /// `Assets::get_still`, this falls back to the `NotFound` sprite if the asset was not loaded.
+    /// Animated assets have no still image, so those get the fallback as well.
+    fn get(&self, asset: &AssetType) -> Option<(&RgbaImage, &Sprite, Pos)> {
+        let find = |sprite_type: &SpriteType, sprite_nr: usize| {
+            self.sheets.get(sprite_type).and_then(|sheet| {
+                sheet
+                    .sprites
+                    .get(sprite_nr)
+                    .map(|sprite| (&sheet.texture, sprite, sheet.dimens))
+            })
+        };
+        match asset {
+            AssetType::Still(sprite_type, sprite_nr) => find(sprite_type, *sprite_nr),
+            AssetType::Animated(_) => None,
+        }
+        .or_else(|| find(&SpriteType::NOT_FOUND, 0))
+    }
+}
+
+/// Draws the whole level, as it is shown at the start of the game, into an image.
+/// Every tile is drawn with its preview asset, because animations have no still image.
+/// Each tile in the world is `tile_size` by `tile_size` pixels in the image. The image covers the
+/// world bounds; positions outside them are not drawn and empty positions are transparent.
+#[must_use]
+pub fn render_thumbnail(
+    level: &LevelSave,
+    tile_defs: &TileDefinitions,
+    sprite_sheets: &SpriteSheets,
+    tile_size: u32,
+) -> RgbaImage {
+    let bounds = &level.world_bounds;
+    let mut thumbnail = RgbaImage::new(
+        bounds.width() as u32 * tile_size,
+        bounds.height() as u32 * tile_size,
+    );
+    let mut tiles = level
+        .tiles
+        .iter()
+        .map(|(pos, key)| (*pos, level.tile_def(*pos, key, tile_defs)))
+        .collect::<Vec<_>>();
+    // Draw back to front. Tiles on the same layer are sorted by position to keep the result stable.
+    tiles.sort_by(|(pos1, def1), (pos2, def2)| {
+        def1.depth
+            .z()
+            .partial_cmp(&def2.depth.z())
+            .unwrap_or(std::cmp::Ordering::Equal)
+            .then_with(|| (pos1.x, pos1.y).cmp(&(pos2.x, pos2.y)))
+    });
+    let tile_size = tile_size as f32;
+    for (pos, tile_def) in tiles {
+        let asset = tile_def.get_preview();
+        let (texture, sprite, asset_dimens) = match sprite_sheets.get(&asset) {
+            Some(found) => found,
+            None => continue,
+        };
+        // The sprite is scaled like the game does in `load_transform`: the asset dimensions are
+        // stretched to fill the tile, and the sprite is centred on the tile.
+        let width = sprite.width * tile_def.dimens.x as f32 * tile_size / asset_dimens.x as f32;
+        let height = sprite.height * tile_def.dimens.y as f32 * tile_size / asset_dimens.y as f32;
+        let centre_x =
+            (pos.x - bounds.x()) as f32 * tile_size + tile_def.dimens.x as f32 * tile_size * 0.5;
+        // The world's y-axis points up, the image's y-axis points down.
+        let centre_y = (bounds.y() + bounds.height() - pos.y) as f32 * tile_size
+            - tile_def.dimens.y as f32 * tile_size * 0.5;
+        draw_sprite(
+            &mut thumbnail,
+            texture,
+            sprite,
+            (centre_x - width * 0.5, centre_y - height * 0.5),
+            (width, height),
+        );
+    }
+    thumbnail
+}
+
+/// Renders the level and writes the thumbnail to the given PNG file.
+pub fn save_thumbnail(
+    level: &LevelSave,
+    tile_defs: &TileDefinitions,
+    sprite_sheets: &SpriteSheets,
+    tile_size: u32,
+    path: &Path,
+) -> Result<(), ThumbnailError> {
+    render_thumbnail(level, tile_defs, sprite_sheets, tile_size)
+        .save(path)
+        .map_err(|error| ThumbnailError::Io {
+            path: path.to_path_buf(),
+            error,
+        })
+}
+
+/// Draws the sprite onto the rectangle with the given top-left corner and size in the target.
+/// Each target pixel averages a grid of samples taken from the sprite.
+fn draw_sprite(
+    target: &mut RgbaImage,
+    texture: &RgbaImage,
+    sprite: &Sprite,
+    (left, top): (f32, f32),
+    (width, height): (f32, f32),
+) {
+    if width <= 0. || height <= 0. {
+        return;
+    }
+    let coords = &sprite.tex_coords;
+    let samples_x = ((sprite.width / width).ceil() as u32).clamp(1, MAX_SAMPLES);
+    let samples_y = ((sprite.height / height).ceil() as u32).clamp(1, MAX_SAMPLES);
+    let x_range = left.floor().max(0.) as u32..((left + width).ceil() as u32).min(target.width());
+    let y_range = top.floor().max(0.) as u32..((top + height).ceil() as u32).min(target.height());
+    for y in y_range {
+        for x in x_range.clone() {
+            let mut sum = [0.; 4];
+            for sample_y in 0..samples_y {
+                let v = (y as f32 + (sample_y as f32 + 0.5) / samples_y as f32 - top) / height;
+                for sample_x in 0..samples_x {
+                    let u = (x as f32 + (sample_x as f32 + 0.5) / samples_x as f32 - left) / width;
+                    // Samples outside the sprite are transparent.
+                    if !(0. ..1.).contains(&u) || !(0. ..1.).contains(&v) {
+                        continue;
+                    }
+                    // Texture coordinates are swapped for flipped sprites, so interpolating
+                    // between them flips the image as well.
+                    let texel = texel(
+                        texture,
+                        coords.left + (coords.right - coords.left) * u,
+                        coords.top + (coords.bottom - coords.top) * v,
+                    );
+                    let alpha = f32::from(texel[3]) / 255.;
+                    sum[0] += f32::from(texel[0]) * alpha;
+                    sum[1] += f32::from(texel[1]) * alpha;
+                    sum[2] += f32::from(texel[2]) * alpha;
+                    sum[3] += alpha;
+                }
+            }
+            let count = (samples_x * samples_y) as f32;
+            blend(
+                target.get_pixel_mut(x, y),
+                [sum[0] / count, sum[1] / count, sum[2] / count],
+                sum[3] / count,
+            );
+        }
+    }
+}
+
+/// Looks up the pixel at the given normalised texture coordinates.
+fn texel(texture: &RgbaImage, u: f32, v: f32) -> Rgba<u8> {
+    let x = ((u * texture.width() as f32) as u32).min(texture.width() - 1);
+    let y = ((v * texture.height() as f32) as u32).min(texture.height() - 1);
+    *texture.get_pixel(x, y)
+}
+
+/// Draws a colour with premultiplied alpha over the pixel.
+fn blend(pixel: &mut Rgba<u8>, colour: [f32; 3], alpha: f32) {
+    if alpha <= 0. {
+        return;
+    }
+    let below_alpha = f32::from(pixel[3]) / 255. * (1. - alpha);
+    let out_alpha = alpha + below_alpha;
+    for channel in 0..3 {
+        let value = (colour[channel] + f32::from(pixel[channel]) * below_alpha) / out_alpha;
+        pixel[channel] = value.round().clamp(0., 255.) as u8;
+    }
+    pixel[3] = (out_alpha * 255.).round().clamp(0., 255.) as u8;
+}
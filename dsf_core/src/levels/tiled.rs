@@ -0,0 +1,847 @@
+//! Import of levels from Tiled maps (`.tmx` or `.json`) and export of levels to Tiled JSON maps.
+//!
+//! A `TiledMapping` decides which Tiled tiles become which tile definitions. When importing, every
+//! tile layer and object layer is read:
+//!
+//! - A tile in a tile layer becomes a tile anchored at that cell. Tiles that are larger than one
+//!   cell may be painted either just in their bottom-left cell, as Tiled draws them, or over their
+//!   whole footprint; cells covered by the footprint of a tile with the same key are skipped.
+//! - An object becomes a tile anchored at the bottom-left cell of the object. Tile objects are
+//!   mapped like tiles, other objects by their class. The custom property `properties` holds the
+//!   instance properties of the tile, in RON.
+//!
+//! When exporting, every tile that the mapping has a Tiled tile for ends up in the tile layer. All
+//! other tiles, as well as tiles with instance properties, become objects. The world bounds and
+//! the metadata of the level are stored in custom properties of the map, so a level survives a
+//! round trip through Tiled unchanged.
+
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use xml::reader::{EventReader, XmlEvent};
+
+use crate::components::Pos;
+use crate::levels::{Difficulty, LevelSave};
+use crate::resources::{TileDefinitions, TileProperties, WorldBounds};
+
+/// Tiled stores whether a tile is flipped in the highest bits of its global tile id.
+const GID_MASK: u32 = 0x0fff_ffff;
+/// The custom property of an object that holds the instance properties of the tile.
+const PROPERTIES_PROPERTY: &str = "properties";
+
+/// Decides which tile definition each Tiled tile and object becomes.
+/// Loaded from `assets/world/tiled_mapping.ron`.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(default)]
+#[serde(deny_unknown_fields)]
+pub struct TiledMapping {
+    /// The name of the Tiled tileset whose tiles are mapped. Only needed if a map uses more than
+    /// one tileset. Tilesets in separate files are recognised by their file name without
+    /// extension.
+    pub tileset: String,
+    /// The tileset file that exported maps refer to, relative to the exported map.
+    pub tileset_source: String,
+    /// The size in pixels of a cell in exported maps.
+    pub tile_width: u32,
+    pub tile_height: u32,
+    /// Maps the ids of tiles within the tileset to tile definition keys.
+    /// If several tiles map to the same key, the lowest id is used when exporting.
+    pub tiles: BTreeMap<u32, String>,
+    /// Maps the classes of objects to tile definition keys. Objects whose class is not in here,
+    /// but is a tile definition key, become a tile of that key.
+    pub objects: BTreeMap<String, String>,
+}
+
+impl Default for TiledMapping {
+    fn default() -> Self {
+        TiledMapping {
+            tileset: String::default(),
+            tileset_source: String::default(),
+            tile_width: 32,
+            tile_height: 32,
+            tiles: BTreeMap::default(),
+            objects: BTreeMap::default(),
+        }
+    }
+}
+
+impl TiledMapping {
+    /// Returns the lowest tile id that maps to the given key.
+    fn tile_id(&self, key: &str) -> Option<u32> {
+        self.tiles
+            .iter()
+            .find(|(_, other)| *other == key)
+            .map(|(id, _)| *id)
+    }
+}
+
+/// The reasons a Tiled map could not be imported or exported.
+#[derive(Debug)]
+pub enum TiledError {
+    Io(io::Error),
+    Json(serde_json::Error),
+    Xml(xml::reader::Error),
+    /// The map uses a feature of Tiled that cannot be imported, such as infinite maps or
+    /// compressed layer data.
+    Unsupported(String),
+    /// The map is malformed.
+    Invalid(String),
+    /// The mapping has no tile definition for this tile.
+    UnmappedTile {
+        tileset: String,
+        id: u32,
+    },
+    /// The mapping has no tile definition for this object class, and it is not a tile definition
+    /// key either.
+    UnmappedObject {
+        class: String,
+    },
+}
+
+impl fmt::Display for TiledError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TiledError::Io(error) => write!(f, "failed to read or write the map: {}", error),
+            TiledError::Json(error) => write!(f, "failed to parse the JSON map: {}", error),
+            TiledError::Xml(error) => write!(f, "failed to parse the TMX map: {}", error),
+            TiledError::Unsupported(feature) => {
+                write!(f, "the map uses an unsupported feature: {}", feature)
+            }
+            TiledError::Invalid(reason) => write!(f, "the map is invalid: {}", reason),
+            TiledError::UnmappedTile { tileset, id } => write!(
+                f,
+                "tile {} of tileset {:?} is not mapped to a tile definition",
+                id, tileset
+            ),
+            TiledError::UnmappedObject { class } => write!(
+                f,
+                "objects of class {:?} are not mapped to a tile definition",
+                class
+            ),
+        }
+    }
+}
+
+impl From<io::Error> for TiledError {
+    fn from(error: io::Error) -> Self {
+        TiledError::Io(error)
+    }
+}
+
+impl From<serde_json::Error> for TiledError {
+    fn from(error: serde_json::Error) -> Self {
+        TiledError::Json(error)
+    }
+}
+
+impl From<xml::reader::Error> for TiledError {
+    fn from(error: xml::reader::Error) -> Self {
+        TiledError::Xml(error)
+    }
+}
+
+/// A Tiled map, as far as it is relevant to levels. Follows the Tiled JSON map format.
+#[derive(Debug, Default, Deserialize, Serialize)]
+#[serde(default)]
+struct TiledMap {
+    #[serde(rename = "type")]
+    map_type: String,
+    version: String,
+    orientation: String,
+    renderorder: String,
+    infinite: bool,
+    width: i32,
+    height: i32,
+    tilewidth: u32,
+    tileheight: u32,
+    nextlayerid: u32,
+    nextobjectid: u32,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    properties: Vec<TiledProperty>,
+    tilesets: Vec<TiledTileset>,
+    layers: Vec<TiledLayer>,
+}
+
+#[derive(Debug, Default, Deserialize, Serialize)]
+#[serde(default)]
+struct TiledTileset {
+    firstgid: u32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    source: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    name: Option<String>,
+}
+
+impl TiledTileset {
+    /// The name of the tileset, or the file name of an external tileset.
+    fn name(&self) -> String {
+        self.name.clone().unwrap_or_else(|| {
+            self.source
+                .as_ref()
+                .and_then(|source| Path::new(source).file_stem())
+                .map(|stem| stem.to_string_lossy().to_string())
+                .unwrap_or_default()
+        })
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(tag = "type")]
+enum TiledLayer {
+    #[serde(rename = "tilelayer")]
+    Tiles(TileLayer),
+    #[serde(rename = "objectgroup")]
+    Objects(ObjectLayer),
+    #[serde(rename = "group")]
+    Group(GroupLayer),
+    /// Image layers have no meaning for levels.
+    #[serde(other)]
+    Other,
+}
+
+#[derive(Debug, Default, Deserialize, Serialize)]
+#[serde(default)]
+struct TileLayer {
+    id: u32,
+    name: String,
+    width: i32,
+    height: i32,
+    x: i32,
+    y: i32,
+    opacity: f32,
+    visible: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    encoding: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    compression: Option<String>,
+    /// The global tile ids of the cells, row by row from the top-left.
+    data: Vec<u32>,
+}
+
+#[derive(Debug, Default, Deserialize, Serialize)]
+#[serde(default)]
+struct ObjectLayer {
+    id: u32,
+    name: String,
+    draworder: String,
+    x: i32,
+    y: i32,
+    opacity: f32,
+    visible: bool,
+    objects: Vec<TiledObject>,
+}
+
+#[derive(Debug, Default, Deserialize, Serialize)]
+#[serde(default)]
+struct GroupLayer {
+    layers: Vec<TiledLayer>,
+}
+
+#[derive(Debug, Default, Deserialize, Serialize)]
+#[serde(default)]
+struct TiledObject {
+    id: u32,
+    name: String,
+    /// The class of the object. Tiled 1.9 called this `class`, other versions call it `type`.
+    #[serde(rename = "type", alias = "class")]
+    class: String,
+    x: f64,
+    y: f64,
+    width: f64,
+    height: f64,
+    rotation: f64,
+    visible: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    gid: Option<u32>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    properties: Vec<TiledProperty>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+struct TiledProperty {
+    name: String,
+    #[serde(rename = "type", default = "default_property_type")]
+    property_type: String,
+    value: Value,
+}
+
+fn default_property_type() -> String {
+    "string".to_string()
+}
+
+impl TiledProperty {
+    fn string(name: &str, value: &str) -> Self {
+        TiledProperty {
+            name: name.to_string(),
+            property_type: default_property_type(),
+            value: Value::from(value),
+        }
+    }
+
+    fn int(name: &str, value: i64) -> Self {
+        TiledProperty {
+            name: name.to_string(),
+            property_type: "int".to_string(),
+            value: Value::from(value),
+        }
+    }
+}
+
+fn find_property<'a>(properties: &'a [TiledProperty], name: &str) -> Option<&'a Value> {
+    properties
+        .iter()
+        .find(|property| property.name == name)
+        .map(|property| &property.value)
+}
+
+fn string_property(properties: &[TiledProperty], name: &str) -> String {
+    match find_property(properties, name) {
+        Some(Value::String(value)) => value.clone(),
+        Some(other) if !other.is_null() => other.to_string(),
+        _ => String::new(),
+    }
+}
+
+fn int_property(properties: &[TiledProperty], name: &str) -> Result<Option<i64>, TiledError> {
+    match find_property(properties, name) {
+        None => Ok(None),
+        Some(Value::Number(number)) => number.as_i64().map(Some).ok_or_else(|| {
+            TiledError::Invalid(format!("property {:?} is not a whole number", name))
+        }),
+        Some(Value::String(text)) => {
+            text.trim().parse().map(Some).map_err(|_| {
+                TiledError::Invalid(format!("property {:?} is not a whole number", name))
+            })
+        }
+        Some(_) => Err(TiledError::Invalid(format!(
+            "property {:?} is not a whole number",
+            name
+        ))),
+    }
+}
+
+fn u32_property(properties: &[TiledProperty], name: &str) -> Result<Option<u32>, TiledError> {
+    int_property(properties, name)?
+        .map(|value| {
+            if value < 0 || value > i64::from(u32::MAX) {
+                Err(TiledError::Invalid(format!(
+                    "property {:?} is out of range",
+                    name
+                )))
+            } else {
+                Ok(value as u32)
+            }
+        })
+        .transpose()
+}
+
+/// Imports a Tiled map. Files with the `.tmx` extension are read as TMX, all others as JSON.
+pub fn import_tiled_file(
+    path: &Path,
+    mapping: &TiledMapping,
+    tile_defs: &TileDefinitions,
+) -> Result<LevelSave, TiledError> {
+    let contents = fs::read_to_string(path)?;
+    if path
+        .extension()
+        .map_or(false, |extension| extension == "tmx")
+    {
+        import_tmx(&contents, mapping, tile_defs)
+    } else {
+        import_tiled_json(&contents, mapping, tile_defs)
+    }
+}
+
+/// Imports a map in the Tiled JSON map format.
+pub fn import_tiled_json(
+    contents: &str,
+    mapping: &TiledMapping,
+    tile_defs: &TileDefinitions,
+) -> Result<LevelSave, TiledError> {
+    import_map(&serde_json::from_str(contents)?, mapping, tile_defs)
+}
+
+/// Imports a map in the Tiled TMX map format. Tile layer data must be CSV encoded, which is the
+/// default in Tiled.
+pub fn import_tmx(
+    contents: &str,
+    mapping: &TiledMapping,
+    tile_defs: &TileDefinitions,
+) -> Result<LevelSave, TiledError> {
+    import_map(&parse_tmx(contents)?, mapping, tile_defs)
+}
+
+/// Exports the level as a map in the Tiled JSON map format.
+#[must_use]
+pub fn export_tiled_json(
+    level: &LevelSave,
+    mapping: &TiledMapping,
+    tile_defs: &TileDefinitions,
+) -> String {
+    let map = export_map(level, mapping, tile_defs);
+    serde_json::to_string_pretty(&map).expect("Failed to serialise Tiled map.")
+}
+
+/// Exports the level to a file in the Tiled JSON map format.
+pub fn export_tiled_file(
+    level: &LevelSave,
+    path: &Path,
+    mapping: &TiledMapping,
+    tile_defs: &TileDefinitions,
+) -> Result<(), TiledError> {
+    fs::write(path, export_tiled_json(level, mapping, tile_defs))?;
+    Ok(())
+}
+
+fn dimens_of(key: &str, tile_defs: &TileDefinitions) -> Pos {
+    tile_defs
+        .map
+        .get(key)
+        .map_or_else(|| Pos::new(1, 1), |tile_def| tile_def.dimens)
+}
+
+fn import_map(
+    map: &TiledMap,
+    mapping: &TiledMapping,
+    tile_defs: &TileDefinitions,
+) -> Result<LevelSave, TiledError> {
+    if map.infinite {
+        return Err(TiledError::Unsupported("infinite maps".to_string()));
+    }
+    if map.width <= 0 || map.height <= 0 || map.tilewidth == 0 || map.tileheight == 0 {
+        return Err(TiledError::Invalid("the map has no size".to_string()));
+    }
+    let world_bounds = WorldBounds::new(
+        int_property(&map.properties, "world_x")?.unwrap_or(0) as i32,
+        int_property(&map.properties, "world_y")?.unwrap_or(0) as i32,
+        map.width,
+        map.height,
+    );
+    let mut level = LevelSave {
+        world_bounds,
+        ..LevelSave::default()
+    };
+    let metadata = &mut level.metadata;
+    metadata.title = string_property(&map.properties, "title");
+    metadata.author = string_property(&map.properties, "author");
+    metadata.description = string_property(&map.properties, "description");
+    let difficulty = string_property(&map.properties, "difficulty");
+    if !difficulty.is_empty() {
+        metadata.difficulty =
+            Some(Difficulty::parse(&difficulty).ok_or_else(|| {
+                TiledError::Invalid(format!("unknown difficulty {:?}", difficulty))
+            })?);
+    }
+    metadata.par_moves = u32_property(&map.properties, "par_moves")?;
+    metadata.par_seconds = u32_property(&map.properties, "par_seconds")?;
+
+    let mut importer = Importer {
+        map,
+        mapping,
+        tile_defs,
+        level,
+        covered_by: HashMap::new(),
+    };
+    importer.import_layers(&map.layers)?;
+    Ok(importer.level)
+}
+
+struct Importer<'a> {
+    map: &'a TiledMap,
+    mapping: &'a TiledMapping,
+    tile_defs: &'a TileDefinitions,
+    level: LevelSave,
+    /// For every cell, the anchor and the key of the tile whose footprint covers it.
+    covered_by: HashMap<Pos, (Pos, String)>,
+}
+
+impl<'a> Importer<'a> {
+    fn import_layers(&mut self, layers: &[TiledLayer]) -> Result<(), TiledError> {
+        for layer in layers {
+            match layer {
+                TiledLayer::Tiles(layer) => self.import_tile_layer(layer)?,
+                TiledLayer::Objects(layer) => self.import_object_layer(layer)?,
+                TiledLayer::Group(group) => self.import_layers(&group.layers)?,
+                TiledLayer::Other => (),
+            }
+        }
+        Ok(())
+    }
+
+    fn import_tile_layer(&mut self, layer: &TileLayer) -> Result<(), TiledError> {
+        if layer
+            .encoding
+            .as_deref()
+            .map_or(false, |encoding| encoding != "csv")
+            || layer
+                .compression
+                .as_deref()
+                .map_or(false, |c| !c.is_empty())
+        {
+            return Err(TiledError::Unsupported(format!(
+                "layer {:?} is not CSV encoded",
+                layer.name
+            )));
+        }
+        let (width, height) = (self.map.width, self.map.height);
+        if layer.data.len() != (width * height) as usize {
+            return Err(TiledError::Invalid(format!(
+                "layer {:?} does not have one tile for every cell",
+                layer.name
+            )));
+        }
+        // Visit the cells from the bottom-left, so anchors come before the rest of their footprint.
+        for row in (0..height).rev() {
+            for column in 0..width {
+                let gid = layer.data[(row * width + column) as usize] & GID_MASK;
+                if gid == 0 {
+                    continue;
+                }
+                let key = self.key_of_gid(gid)?;
+                let pos = self.cell_pos(column, row);
+                let already_covered = self
+                    .covered_by
+                    .get(&pos)
+                    .map_or(false, |(_, other)| *other == key);
+                if !already_covered {
+                    self.put(pos, key, None);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn import_object_layer(&mut self, layer: &ObjectLayer) -> Result<(), TiledError> {
+        for object in &layer.objects {
+            let key = if let Some(gid) = object.gid {
+                self.key_of_gid(gid & GID_MASK)?
+            } else if let Some(key) = self.mapping.objects.get(&object.class) {
+                key.clone()
+            } else if self.tile_defs.map.contains_key(&object.class) {
+                object.class.clone()
+            } else {
+                return Err(TiledError::UnmappedObject {
+                    class: object.class.clone(),
+                });
+            };
+            // Tile objects are positioned by their bottom-left corner, other objects by their
+            // top-left corner.
+            let bottom = if object.gid.is_some() {
+                object.y
+            } else {
+                object.y + object.height
+            };
+            let column = (object.x / f64::from(self.map.tilewidth)).round() as i32;
+            let row = (bottom / f64::from(self.map.tileheight)).round() as i32 - 1;
+            let properties = string_property(&object.properties, PROPERTIES_PROPERTY);
+            let properties = if properties.is_empty() {
+                None
+            } else {
+                Some(TileProperties::parse(&properties).map_err(|error| {
+                    TiledError::Invalid(format!(
+                        "object {} has invalid tile properties: {}",
+                        object.id, error
+                    ))
+                })?)
+            };
+            self.put(self.cell_pos(column, row), key, properties);
+        }
+        Ok(())
+    }
+
+    /// Converts a Tiled cell, counted from the top-left, to a position in the world.
+    fn cell_pos(&self, column: i32, row: i32) -> Pos {
+        let bounds = &self.level.world_bounds;
+        Pos::new(bounds.x() + column, bounds.y() + self.map.height - 1 - row)
+    }
+
+    fn key_of_gid(&self, gid: u32) -> Result<String, TiledError> {
+        let tileset = self
+            .map
+            .tilesets
+            .iter()
+            .filter(|tileset| tileset.firstgid <= gid)
+            .max_by_key(|tileset| tileset.firstgid)
+            .ok_or_else(|| TiledError::Invalid(format!("tile {} is in no tileset", gid)))?;
+        let name = tileset.name();
+        let id = gid - tileset.firstgid;
+        let is_mapped_tileset = self.map.tilesets.len() == 1
+            || self.mapping.tileset.is_empty()
+            || self.mapping.tileset == name;
+        self.mapping
+            .tiles
+            .get(&id)
+            .filter(|_| is_mapped_tileset)
+            .cloned()
+            .ok_or(TiledError::UnmappedTile { tileset: name, id })
+    }
+
+    fn put(&mut self, pos: Pos, key: String, properties: Option<TileProperties>) {
+        let dimens = dimens_of(&key, self.tile_defs);
+        for x in 0..dimens.x {
+            for y in 0..dimens.y {
+                self.covered_by
+                    .insert(pos.append_xy(x, y), (pos, key.clone()));
+            }
+        }
+        self.level.tiles.insert(pos, key);
+        match properties {
+            Some(properties) => self.level.properties.insert(pos, properties),
+            None => self.level.properties.remove(&pos),
+        };
+    }
+}
+
+fn export_map(level: &LevelSave, mapping: &TiledMapping, tile_defs: &TileDefinitions) -> TiledMap {
+    let bounds = &level.world_bounds;
+    let (width, height) = (bounds.width(), bounds.height());
+    let mut data = vec![0; (width.max(0) * height.max(0)) as usize];
+    let mut objects = vec![];
+    let tiles = level.tiles.iter().collect::<BTreeMap<_, _>>();
+    for (pos, key) in tiles {
+        let column = pos.x - bounds.x();
+        let row = height - 1 - (pos.y - bounds.y());
+        let properties = level.properties.get(pos);
+        let in_bounds = column >= 0 && column < width && row >= 0 && row < height;
+        match mapping.tile_id(key) {
+            Some(id) if in_bounds && properties.is_none() => {
+                data[(row * width + column) as usize] = id + 1;
+            }
+            _ => {
+                let dimens = dimens_of(key, tile_defs);
+                let object_height = dimens.y * mapping.tile_height as i32;
+                objects.push(TiledObject {
+                    id: objects.len() as u32 + 1,
+                    class: key.clone(),
+                    x: f64::from(column * mapping.tile_width as i32),
+                    y: f64::from((row + 1) * mapping.tile_height as i32 - object_height),
+                    width: f64::from(dimens.x * mapping.tile_width as i32),
+                    height: f64::from(object_height),
+                    visible: true,
+                    properties: properties
+                        .map(|properties| {
+                            vec![TiledProperty::string(
+                                PROPERTIES_PROPERTY,
+                                &properties.to_ron(),
+                            )]
+                        })
+                        .unwrap_or_default(),
+                    ..TiledObject::default()
+                });
+            }
+        }
+    }
+
+    let metadata = &level.metadata;
+    let mut properties = vec![
+        TiledProperty::int("world_x", i64::from(bounds.x())),
+        TiledProperty::int("world_y", i64::from(bounds.y())),
+    ];
+    for (name, value) in &[
+        ("title", &metadata.title),
+        ("author", &metadata.author),
+        ("description", &metadata.description),
+    ] {
+        if !value.is_empty() {
+            properties.push(TiledProperty::string(name, value));
+        }
+    }
+    if let Some(difficulty) = metadata.difficulty {
+        properties.push(TiledProperty::string("difficulty", &difficulty.to_string()));
+    }
+    for (name, value) in &[
+        ("par_moves", metadata.par_moves),
+        ("par_seconds", metadata.par_seconds),
+    ] {
+        if let Some(value) = value {
+            properties.push(TiledProperty::int(name, i64::from(*value)));
+        }
+    }
+
+    let nextobjectid = objects.len() as u32 + 1;
+    TiledMap {
+        map_type: "map".to_string(),
+        version: "1.10".to_string(),
+        orientation: "orthogonal".to_string(),
+        renderorder: "right-down".to_string(),
+        infinite: false,
+        width,
+        height,
+        tilewidth: mapping.tile_width,
+        tileheight: mapping.tile_height,
+        nextlayerid: 3,
+        nextobjectid,
+        properties,
+        tilesets: vec![TiledTileset {
+            firstgid: 1,
+            source: Some(mapping.tileset_source.clone()),
+            name: None,
+        }],
+        layers: vec![
+            TiledLayer::Tiles(TileLayer {
+                id: 1,
+                name: "tiles".to_string(),
+                width,
+                height,
+                opacity: 1.0,
+                visible: true,
+                data,
+                ..TileLayer::default()
+            }),
+            TiledLayer::Objects(ObjectLayer {
+                id: 2,
+                name: "objects".to_string(),
+                draworder: "topdown".to_string(),
+                opacity: 1.0,
+                visible: true,
+                objects,
+                ..ObjectLayer::default()
+            }),
+        ],
+    }
+}
+
+/// Reads a TMX map into the same structure that JSON maps are read into.
+fn parse_tmx(contents: &str) -> Result<TiledMap, TiledError> {
+    let mut map = TiledMap::default();
+    // The names of the elements that are currently open.
+    let mut open = Vec::<String>::new();
+    let mut layer: Option<TileLayer> = None;
+    let mut objects: Option<ObjectLayer> = None;
+    let mut object: Option<TiledObject> = None;
+    for event in EventReader::from_str(contents) {
+        match event? {
+            XmlEvent::StartElement {
+                name, attributes, ..
+            } => {
+                let attribute = |key: &str| {
+                    attributes
+                        .iter()
+                        .find(|attribute| attribute.name.local_name == key)
+                        .map(|attribute| attribute.value.clone())
+                };
+                let number = |key: &str| -> Result<f64, TiledError> {
+                    attribute(key).map_or(Ok(0.0), |value| {
+                        value.parse().map_err(|_| {
+                            TiledError::Invalid(format!("attribute {:?} is not a number", key))
+                        })
+                    })
+                };
+                let parent = open.last().map(String::as_str);
+                match (parent, name.local_name.as_str()) {
+                    (None, "map") => {
+                        map.infinite = attribute("infinite").map_or(false, |value| value == "1");
+                        map.width = number("width")? as i32;
+                        map.height = number("height")? as i32;
+                        map.tilewidth = number("tilewidth")? as u32;
+                        map.tileheight = number("tileheight")? as u32;
+                    }
+                    (Some("map"), "tileset") => map.tilesets.push(TiledTileset {
+                        firstgid: number("firstgid")? as u32,
+                        source: attribute("source"),
+                        name: attribute("name"),
+                    }),
+                    (_, "layer") => {
+                        layer = Some(TileLayer {
+                            name: attribute("name").unwrap_or_default(),
+                            ..TileLayer::default()
+                        })
+                    }
+                    (Some("layer"), "data") => {
+                        if let Some(layer) = &mut layer {
+                            layer.encoding = attribute("encoding");
+                            layer.compression = attribute("compression");
+                        }
+                    }
+                    (Some("data"), "chunk") => {
+                        return Err(TiledError::Unsupported("infinite maps".to_string()))
+                    }
+                    (Some("data"), "tile") => {
+                        return Err(TiledError::Unsupported(
+                            "layer data stored as XML".to_string(),
+                        ))
+                    }
+                    (_, "objectgroup") => {
+                        objects = Some(ObjectLayer {
+                            name: attribute("name").unwrap_or_default(),
+                            ..ObjectLayer::default()
+                        })
+                    }
+                    (Some("objectgroup"), "object") => {
+                        object = Some(TiledObject {
+                            id: number("id")? as u32,
+                            name: attribute("name").unwrap_or_default(),
+                            class: attribute("type")
+                                .or_else(|| attribute("class"))
+                                .unwrap_or_default(),
+                            x: number("x")?,
+                            y: number("y")?,
+                            width: number("width")?,
+                            height: number("height")?,
+                            gid: attribute("gid")
+                                .map(|_| number("gid").map(|gid| gid as u32))
+                                .transpose()?,
+                            ..TiledObject::default()
+                        })
+                    }
+                    (Some("properties"), "property") => {
+                        let property = TiledProperty {
+                            name: attribute("name").unwrap_or_default(),
+                            property_type: attribute("type").unwrap_or_else(default_property_type),
+                            value: Value::from(attribute("value").unwrap_or_default()),
+                        };
+                        // Only the properties of the map and of objects are of interest.
+                        match open.get(open.len().wrapping_sub(2)).map(String::as_str) {
+                            Some("map") => map.properties.push(property),
+                            Some("object") => {
+                                if let Some(object) = &mut object {
+                                    object.properties.push(property);
+                                }
+                            }
+                            _ => (),
+                        }
+                    }
+                    _ => (),
+                }
+                open.push(name.local_name);
+            }
+            XmlEvent::Characters(text) if open.last().map(String::as_str) == Some("data") => {
+                if let Some(layer) = &mut layer {
+                    for value in text.split(',').map(str::trim).filter(|v| !v.is_empty()) {
+                        layer.data.push(value.parse().map_err(|_| {
+                            TiledError::Invalid(format!("{:?} is not a tile id", value))
+                        })?);
+                    }
+                }
+            }
+            XmlEvent::EndElement { name } => {
+                open.pop();
+                match name.local_name.as_str() {
+                    "layer" => {
+                        if let Some(layer) = layer.take() {
+                            map.layers.push(TiledLayer::Tiles(layer));
+                        }
+                    }
+                    "object" => {
+                        if let (Some(object), Some(objects)) = (object.take(), &mut objects) {
+                            objects.objects.push(object);
+                        }
+                    }
+                    "objectgroup" => {
+                        if let Some(objects) = objects.take() {
+                            map.layers.push(TiledLayer::Objects(objects));
+                        }
+                    }
+                    _ => (),
+                }
+            }
+            _ => (),
+        }
+    }
+    Ok(map)
+}
@@ -0,0 +1,170 @@
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::fmt;
+
+use crate::components::Pos;
+use crate::levels::LevelSave;
+use crate::resources::{Archetype, TileDefinitions};
+
+/// Something that is wrong with a level. A level with any of these issues might not be playable,
+/// or might not play the way its author intended.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum LevelIssue {
+    /// The level contains no tile with this mandatory tile definition.
+    MissingMandatory { key: String },
+    /// The level contains more than one tile with this unique tile definition.
+    DuplicateUnique { key: String, positions: Vec<Pos> },
+    /// The footprints of the tiles anchored at these two positions overlap.
+    Overlap { first: Pos, second: Pos },
+    /// The footprint of the tile anchored at this position is not completely within the world
+    /// bounds.
+    OutOfBounds { pos: Pos, key: String },
+    /// The tile at this position refers to a tile definition that does not exist.
+    UnknownKey { pos: Pos, key: String },
+    /// There are instance properties for this position, but there is no tile anchored there.
+    PropertiesWithoutTile { pos: Pos },
+    /// The level contains no keys, so there is nothing for the player to collect.
+    NoKeys,
+}
+
+impl fmt::Display for LevelIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LevelIssue::MissingMandatory { key } => {
+                write!(f, "the level must contain at least one {:?}", key)
+            }
+            LevelIssue::DuplicateUnique { key, positions } => write!(
+                f,
+                "the level may contain only one {:?}, but it has {} at {:?}",
+                key,
+                positions.len(),
+                positions
+            ),
+            LevelIssue::Overlap { first, second } => write!(
+                f,
+                "the tiles at {:?} and {:?} overlap",
+                (first.x, first.y),
+                (second.x, second.y)
+            ),
+            LevelIssue::OutOfBounds { pos, key } => write!(
+                f,
+                "the {:?} at {:?} is (partially) outside the world bounds",
+                key,
+                (pos.x, pos.y)
+            ),
+            LevelIssue::UnknownKey { pos, key } => write!(
+                f,
+                "the tile at {:?} refers to unknown tile definition {:?}",
+                (pos.x, pos.y),
+                key
+            ),
+            LevelIssue::PropertiesWithoutTile { pos } => write!(
+                f,
+                "there are tile properties at {:?}, but there is no tile",
+                (pos.x, pos.y)
+            ),
+            LevelIssue::NoKeys => write!(f, "the level contains no keys"),
+        }
+    }
+}
+
+/// Checks the level against the rules laid down by the tile definitions and returns every issue
+/// found. An empty list means the level is valid.
+///
+/// The issues are returned in a deterministic order: grouped by kind, then sorted by position or
+/// tile definition key.
+#[must_use]
+pub fn validate_level(level: &LevelSave, tile_defs: &TileDefinitions) -> Vec<LevelIssue> {
+    let mut issues = vec![];
+    // Sort the tiles, so the issues are always reported in the same order.
+    let tiles: BTreeMap<&Pos, &String> = level.tiles.iter().collect();
+
+    let mut unknown = vec![];
+    let mut out_of_bounds = vec![];
+    let mut occupied: HashMap<Pos, Pos> = HashMap::new();
+    let mut overlaps = BTreeSet::new();
+    let mut positions_by_key: HashMap<&str, Vec<Pos>> = HashMap::new();
+    let mut key_count = 0;
+    for (pos, key) in tiles {
+        positions_by_key.entry(key).or_default().push(*pos);
+        // Unknown tiles are replaced by a 1 by 1 fallback tile when the level is loaded.
+        let dimens = if tile_defs.map.contains_key(key) {
+            let tile_def = level.tile_def(*pos, key, tile_defs);
+            if tile_def.archetype == Some(Archetype::Key) {
+                key_count += 1;
+            }
+            tile_def.dimens
+        } else {
+            unknown.push(LevelIssue::UnknownKey {
+                pos: *pos,
+                key: key.clone(),
+            });
+            Pos::new(1, 1)
+        };
+        if !level.world_bounds.encloses(*pos, dimens) {
+            out_of_bounds.push(LevelIssue::OutOfBounds {
+                pos: *pos,
+                key: key.clone(),
+            });
+        }
+        for x in 0..dimens.x {
+            for y in 0..dimens.y {
+                if let Some(other) = occupied.insert(pos.append_xy(x, y), *pos) {
+                    overlaps.insert((other, *pos));
+                }
+            }
+        }
+    }
+
+    let mut mandatory_keys: Vec<&String> = tile_defs
+        .map
+        .iter()
+        .filter(|(_, tile_def)| tile_def.mandatory)
+        .map(|(key, _)| key)
+        .collect();
+    mandatory_keys.sort();
+    issues.extend(
+        mandatory_keys
+            .into_iter()
+            .filter(|key| !positions_by_key.contains_key(key.as_str()))
+            .map(|key| LevelIssue::MissingMandatory { key: key.clone() }),
+    );
+
+    let mut duplicates: Vec<(&str, Vec<Pos>)> = positions_by_key
+        .into_iter()
+        .filter(|(key, positions)| {
+            positions.len() > 1 && tile_defs.map.get(*key).map_or(false, |def| def.unique)
+        })
+        .collect();
+    duplicates.sort();
+    issues.extend(
+        duplicates
+            .into_iter()
+            .map(|(key, positions)| LevelIssue::DuplicateUnique {
+                key: key.to_string(),
+                positions,
+            }),
+    );
+
+    issues.extend(
+        overlaps
+            .into_iter()
+            .map(|(first, second)| LevelIssue::Overlap { first, second }),
+    );
+    issues.extend(out_of_bounds);
+    issues.extend(unknown);
+    let mut orphaned_properties: Vec<&Pos> = level
+        .properties
+        .keys()
+        .filter(|pos| !level.tiles.contains_key(pos))
+        .collect();
+    orphaned_properties.sort();
+    issues.extend(
+        orphaned_properties
+            .into_iter()
+            .map(|pos| LevelIssue::PropertiesWithoutTile { pos: *pos }),
+    );
+    if key_count == 0 {
+        issues.push(LevelIssue::NoKeys);
+    }
+    issues
+}
@@ -9,8 +9,8 @@ use amethyst::renderer::resources::Tint;
 use serde::{Deserialize, Serialize};
 
 use crate::components::{MapCursor, Pos};
-use crate::levels::{load_asset_from_world, load_transform, LevelSave};
-use crate::resources::{AssetType, DepthLayer, SpriteType, UserCache};
+use crate::levels::{load_asset_from_world, load_transform, LevelMetadata, LevelSave};
+use crate::resources::{AssetType, Assets, DepthLayer, SpriteType, UserCache};
 use crate::utility::files::{get_adventures_dir, get_levels_dir};
 
 #[derive(Copy, Clone, Debug, Deserialize, Serialize, Default)]
@@ -29,6 +29,10 @@ impl PositionOnMap {
 #[derive(Debug, Deserialize, Serialize, Default)]
 pub struct Adventure {
     pub(crate) nodes: HashMap<Pos, MapElement>,
+    /// The metadata of the level behind each level node. Read from the level files when the
+    /// adventure is loaded, so it can be shown when the map cursor hovers over a node.
+    #[serde(skip)]
+    pub(crate) level_metadata: HashMap<Pos, LevelMetadata>,
 }
 
 #[derive(Debug, Deserialize, Serialize)]
@@ -129,13 +133,14 @@ fn level_files() -> Vec<String> {
 }
 
 pub fn load_adventure(path: &Path, world: &mut World) -> Result<(), ConfigError> {
-    let adventure = Adventure::load(path)?;
+    let mut adventure = Adventure::load(path)?;
     for (pos, map_element) in &adventure.nodes {
         match map_element {
             MapElement::Road => load_road(*pos, world),
             MapElement::Node(node) => load_node(*pos, node, world),
         }
     }
+    adventure.level_metadata = load_level_metadata(&adventure);
     let initial_cursor_pos = {
         let last_known_pos = cursor_position(path, world);
         if adventure.nodes.contains_key(&last_known_pos) {
@@ -150,6 +155,28 @@ pub fn load_adventure(path: &Path, world: &mut World) -> Result<(), ConfigError>
     Ok(())
 }
 
+/// Reads the metadata of every level in the adventure. Levels that fail to load are logged and
+/// left out.
+fn load_level_metadata(adventure: &Adventure) -> HashMap<Pos, LevelMetadata> {
+    adventure
+        .nodes
+        .iter()
+        .filter_map(|(pos, map_element)| match map_element {
+            MapElement::Node(AdventureNode {
+                details: NodeDetails::Level(file_name),
+                ..
+            }) => match LevelSave::load(get_levels_dir().join(file_name)) {
+                Ok(level) => Some((*pos, level.metadata)),
+                Err(error) => {
+                    error!("Failed to load level {:?}: {}", file_name, error);
+                    None
+                }
+            },
+            _ => None,
+        })
+        .collect()
+}
+
 fn cursor_position(path: &Path, world: &mut World) -> Pos {
     world.read_resource::<UserCache>().get_initial_cursor_pos(
         path.file_name()
@@ -160,12 +187,13 @@ fn cursor_position(path: &Path, world: &mut World) -> Pos {
 }
 
 fn load_cursor(world: &mut World, pos: Pos) {
-    let sprite_render = load_asset_from_world(SpriteType::LevelSelect, 3, world);
+    let sprite_render = load_asset_from_world(SpriteType::LEVEL_SELECT, 3, world);
     let transform = load_transform(
         pos,
         DepthLayer::Player,
         Pos::new(1, 1),
-        &AssetType::Still(SpriteType::LevelSelect, 3),
+        &AssetType::Still(SpriteType::LEVEL_SELECT, 3),
+        &world.read_resource::<Assets>(),
     );
     world
         .create_entity()
@@ -177,12 +205,13 @@ fn load_cursor(world: &mut World, pos: Pos) {
 }
 
 fn load_road(pos: Pos, world: &mut World) {
-    let sprite_render_road = load_asset_from_world(SpriteType::LevelSelect, 1, world);
+    let sprite_render_road = load_asset_from_world(SpriteType::LEVEL_SELECT, 1, world);
     let transform = load_transform(
         pos,
         DepthLayer::Blocks,
         Pos::new(1, 1),
-        &AssetType::Still(SpriteType::LevelSelect, 1),
+        &AssetType::Still(SpriteType::LEVEL_SELECT, 1),
+        &world.read_resource::<Assets>(),
     );
     world
         .create_entity()
@@ -192,12 +221,13 @@ fn load_road(pos: Pos, world: &mut World) {
 }
 
 fn load_node(pos: Pos, _node: &AdventureNode, world: &mut World) {
-    let sprite_render_node = load_asset_from_world(SpriteType::LevelSelect, 0, world);
+    let sprite_render_node = load_asset_from_world(SpriteType::LEVEL_SELECT, 0, world);
     let transform = load_transform(
         pos,
         DepthLayer::Blocks,
         Pos::new(1, 1),
-        &AssetType::Still(SpriteType::LevelSelect, 0),
+        &AssetType::Still(SpriteType::LEVEL_SELECT, 0),
+        &world.read_resource::<Assets>(),
     );
     world
         .create_entity()
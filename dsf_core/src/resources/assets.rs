@@ -7,19 +7,28 @@ use amethyst::{
 use dsf_precompile::MyPrefabData;
 use rand::Rng;
 use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
 use std::collections::HashMap;
 
+/// The loaded graphics and sounds of the game. Sprite sheets and animations are stored by the id
+/// they were registered under in the `LoadingConfig`, along with the pixel dimensions that were
+/// declared for them there.
 #[derive(Default, Debug)]
 pub struct Assets {
-    stills: HashMap<SpriteType, Handle<SpriteSheet>>,
-    animated: HashMap<AnimType, Handle<Prefab<MyPrefabData>>>,
+    stills: HashMap<SpriteType, (Handle<SpriteSheet>, Pos)>,
+    animated: HashMap<AnimType, (Handle<Prefab<MyPrefabData>>, Pos)>,
     sounds: HashMap<SoundType, Vec<SourceHandle>>,
 }
 
 impl Assets {
     #[must_use]
-    pub fn put_still(mut self, asset_type: SpriteType, asset: Handle<SpriteSheet>) -> Self {
-        self.stills.insert(asset_type, asset);
+    pub fn put_still(
+        mut self,
+        asset_type: SpriteType,
+        asset: Handle<SpriteSheet>,
+        dimens: Pos,
+    ) -> Self {
+        self.stills.insert(asset_type, (asset, dimens));
         self
     }
 
@@ -28,8 +37,9 @@ impl Assets {
         mut self,
         asset_type: AnimType,
         asset: Handle<Prefab<MyPrefabData>>,
+        dimens: Pos,
     ) -> Self {
-        self.animated.insert(asset_type, asset);
+        self.animated.insert(asset_type, (asset, dimens));
         self
     }
 
@@ -43,29 +53,44 @@ impl Assets {
     }
 
     #[must_use]
-    pub fn get_still(&self, asset_type: SpriteType) -> Handle<SpriteSheet> {
-        (*self
-            .stills
-            .get(&asset_type)
+    pub fn get_still(&self, asset_type: &SpriteType) -> Handle<SpriteSheet> {
+        self.find_still(asset_type).0.clone()
+    }
+
+    #[must_use]
+    pub fn get_animated(&self, asset_type: &AnimType) -> Handle<Prefab<MyPrefabData>> {
+        self.find_animated(asset_type).0.clone()
+    }
+
+    /// Returns the dimensions in pixels of a still or animated asset, as they were declared in the
+    /// `LoadingConfig`. Required to calculate the correct scale factor for the entity to make it
+    /// fit within its in-world bounds.
+    #[must_use]
+    pub fn get_dimensions(&self, asset: &AssetType) -> Pos {
+        match asset {
+            AssetType::Still(sprite_type, _) => self.find_still(sprite_type).1,
+            AssetType::Animated(anim_type) => self.find_animated(anim_type).1,
+        }
+    }
+
+    fn find_still(&self, asset_type: &SpriteType) -> &(Handle<SpriteSheet>, Pos) {
+        self.stills
+            .get(asset_type)
             .or_else(|| {
                 error!("Spritesheet asset {:?} is missing!", asset_type);
-                self.stills.get(&SpriteType::NotFound)
+                self.stills.get(&SpriteType::NOT_FOUND)
             })
-            .expect("Fallback asset also missing."))
-        .clone()
+            .expect("Fallback asset also missing.")
     }
 
-    #[must_use]
-    pub fn get_animated(&self, asset_type: AnimType) -> Handle<Prefab<MyPrefabData>> {
-        (*self
-            .animated
-            .get(&asset_type)
+    fn find_animated(&self, asset_type: &AnimType) -> &(Handle<Prefab<MyPrefabData>>, Pos) {
+        self.animated
+            .get(asset_type)
             .or_else(|| {
                 error!("Animation asset {:?} is missing!", asset_type);
-                self.animated.get(&AnimType::NotFound)
+                self.animated.get(&AnimType::NOT_FOUND)
             })
-            .expect("Fallback asset also missing!"))
-        .clone()
+            .expect("Fallback asset also missing!")
     }
 
     #[must_use]
@@ -84,10 +109,10 @@ impl Assets {
     }
 }
 
-#[derive(Debug, Copy, Clone, Deserialize, Serialize)]
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
 pub enum AssetType {
     /// A static, non-animated image.
-    /// Contains both a handle to the sprite sheet and the number of the sprite on the sheet.
+    /// Contains both the id of the sprite sheet and the number of the sprite on the sheet.
     Still(SpriteType, usize),
     /// An animated image.
     Animated(AnimType),
@@ -99,49 +124,65 @@ impl Default for AssetType {
     }
 }
 
-#[derive(Debug, Copy, Clone, Deserialize, Serialize, PartialEq, Eq, Hash)]
-pub enum SpriteType {
+/// Identifies a sprite sheet by the id it was registered under in the `LoadingConfig`. New sprite
+/// sheets can be added by registering them in `loading.ron`, without changing any code.
+///
+/// The constants are the sprite sheets that the game refers to itself, so they must always be
+/// registered.
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, Eq, Hash)]
+#[serde(transparent)]
+pub struct SpriteType(Cow<'static, str>);
+
+impl SpriteType {
     /// This is the fallback sprite to use if the desired sprite cannot be found.
-    NotFound,
-    Ladder,
-    Frame,
-    Blocks,
-    Tools,
-    Door,
-    Selection,
-    LevelSelect,
-    EditorUiIcons,
-    Miner,
+    pub const NOT_FOUND: SpriteType = SpriteType(Cow::Borrowed("NotFound"));
+    pub const FRAME: SpriteType = SpriteType(Cow::Borrowed("Frame"));
+    pub const BLOCKS: SpriteType = SpriteType(Cow::Borrowed("Blocks"));
+    pub const SELECTION: SpriteType = SpriteType(Cow::Borrowed("Selection"));
+    pub const LEVEL_SELECT: SpriteType = SpriteType(Cow::Borrowed("LevelSelect"));
+    pub const EDITOR_UI_ICONS: SpriteType = SpriteType(Cow::Borrowed("EditorUiIcons"));
+
+    #[must_use]
+    pub fn new(id: &str) -> Self {
+        SpriteType(Cow::Owned(id.to_string()))
+    }
+
+    #[must_use]
+    pub fn id(&self) -> &str {
+        &self.0
+    }
 }
 
 impl Default for SpriteType {
     fn default() -> Self {
-        SpriteType::NotFound
+        SpriteType::NOT_FOUND
     }
 }
 
-#[derive(Debug, Copy, Clone, Deserialize, Serialize, PartialEq, Eq, Hash)]
-pub enum AnimType {
+/// Identifies an animation by the id it was registered under in the `LoadingConfig`.
+/// Like sprite sheets, new animations can be added through `loading.ron` alone.
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, Eq, Hash)]
+#[serde(transparent)]
+pub struct AnimType(Cow<'static, str>);
+
+impl AnimType {
     /// The fallback animated asset to use if the desired asset could not be found.
-    NotFound,
-    Miner,
+    pub const NOT_FOUND: AnimType = AnimType(Cow::Borrowed("NotFound"));
+
+    #[must_use]
+    pub fn new(id: &str) -> Self {
+        AnimType(Cow::Owned(id.to_string()))
+    }
+
+    #[must_use]
+    pub fn id(&self) -> &str {
+        &self.0
+    }
 }
 
-/// Matches a still or animated asset to its dimensions in pixels. Required to calculate the
-/// correct scale factor for the entity to make it fit within its in-world bounds.
-#[allow(clippy::match_single_binding)]
-#[must_use]
-pub fn get_asset_dimensions(asset: &AssetType) -> Pos {
-    match asset {
-        AssetType::Still(sprite_type, _) => match sprite_type {
-            SpriteType::Frame => Pos::new(50, 50),
-            SpriteType::Ladder => Pos::new(128, 64),
-            SpriteType::Door => Pos::new(256, 256),
-            _ => Pos::new(128, 128),
-        },
-        AssetType::Animated(anim_type) => match anim_type {
-            _ => Pos::new(128, 128),
-        },
+impl Default for AnimType {
+    fn default() -> Self {
+        AnimType::NOT_FOUND
     }
 }
 
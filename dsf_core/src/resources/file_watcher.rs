@@ -0,0 +1,91 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant, SystemTime};
+
+/// How often the modification times of the watched files are checked, unless the watcher was
+/// created with a different interval.
+const POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Watches files on disk, so they can be reloaded while the game runs. This lets designers see
+/// their changes to the tile definitions, the movement config or a level without restarting.
+///
+/// Like the hot reloading of Amethyst's assets, this polls the modification times of the files
+/// rather than relying on events from the operating system.
+///
+/// The state on top of the stack decides which files are watched, by calling `watch` when it
+/// starts or resumes, and asks for the files that changed in its update.
+#[derive(Debug)]
+pub struct FileWatcher {
+    /// The watched files, with the modification time they had when they were last checked.
+    /// None if the file did not exist or its modification time could not be read.
+    files: HashMap<PathBuf, Option<SystemTime>>,
+    last_poll: Option<Instant>,
+    poll_interval: Duration,
+}
+
+impl Default for FileWatcher {
+    fn default() -> Self {
+        FileWatcher::with_poll_interval(POLL_INTERVAL)
+    }
+}
+
+impl FileWatcher {
+    /// Creates a watcher that checks the files at most once per the given interval.
+    /// With an interval of zero, the files are checked on every call to `changed_files`.
+    #[must_use]
+    pub fn with_poll_interval(poll_interval: Duration) -> Self {
+        FileWatcher {
+            files: HashMap::new(),
+            last_poll: None,
+            poll_interval,
+        }
+    }
+
+    /// Stops watching the previous files and starts watching these. Changes that were made to the
+    /// files before this call are not reported.
+    pub fn watch(&mut self, files: Vec<PathBuf>) {
+        self.files = files
+            .into_iter()
+            .map(|file| {
+                let modified = modified(&file);
+                (file, modified)
+            })
+            .collect();
+        self.last_poll = Some(Instant::now());
+    }
+
+    /// Returns the watched files that were changed, created or deleted since the last time they
+    /// were checked. To keep this cheap enough to call every frame, the files are checked at most
+    /// once per poll interval; in between, this returns nothing.
+    pub fn changed_files(&mut self) -> Vec<PathBuf> {
+        let now = Instant::now();
+        if self.last_poll.map_or(false, |last_poll| {
+            now.duration_since(last_poll) < self.poll_interval
+        }) {
+            return vec![];
+        }
+        self.last_poll = Some(now);
+        let mut changed = self
+            .files
+            .iter_mut()
+            .filter_map(|(file, last_modified)| {
+                let modified = modified(file);
+                if modified == *last_modified {
+                    None
+                } else {
+                    *last_modified = modified;
+                    Some(file.clone())
+                }
+            })
+            .collect::<Vec<_>>();
+        changed.sort();
+        changed
+    }
+}
+
+fn modified(file: &Path) -> Option<SystemTime> {
+    fs::metadata(file)
+        .and_then(|metadata| metadata.modified())
+        .ok()
+}
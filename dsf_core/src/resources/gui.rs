@@ -46,6 +46,12 @@ pub enum UiType {
     WinMessage,
     /// Dialog that pops up when you want to save a level in the editor.
     Save,
+    /// Dialog in the editor that shows a title and a message, such as the list of levels to open.
+    EditorDialog,
+    /// Dialog in the editor to edit the title, author, hint, difficulty and par of the level.
+    LevelInfo,
+    /// Dialog in the editor to edit the instance properties of a single tile.
+    TileProperties,
     /// Ui for the level editor.
     Editor,
     /// The paused menu. Not currently in use, but will be implemented in the future.
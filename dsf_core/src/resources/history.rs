@@ -1,4 +1,5 @@
 use crate::components::Pos;
+use amethyst::ecs::Entity;
 
 /// Holds the full history of the current game. Used to rewind games to an earlier point.
 ///
@@ -33,15 +34,20 @@ impl History {
     }
 }
 
-#[derive(Copy, Clone, Debug)]
+#[derive(Clone, Debug)]
 pub struct Frame {
     pub player_position: Pos,
+    /// The positions of all pushable blocks.
+    pub pushables: Vec<(Entity, Pos)>,
 }
 
 impl Frame {
     #[must_use]
-    pub fn new(player_position: Pos) -> Self {
-        Frame { player_position }
+    pub fn new(player_position: Pos, pushables: Vec<(Entity, Pos)>) -> Self {
+        Frame {
+            player_position,
+            pushables,
+        }
     }
 }
 
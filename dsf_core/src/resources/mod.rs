@@ -2,6 +2,7 @@ mod adventure;
 mod assets;
 mod config;
 mod event_readers;
+mod file_watcher;
 mod gui;
 mod history;
 mod music;
@@ -16,6 +17,7 @@ pub use self::adventure::*;
 pub use self::assets::*;
 pub use self::config::*;
 pub use self::event_readers::*;
+pub use self::file_watcher::*;
 pub use self::gui::*;
 pub use self::history::*;
 pub use self::music::*;
@@ -2,15 +2,19 @@ use crate::components::Pos;
 use crate::resources::{AssetType, SpriteType};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::convert::TryFrom;
+use std::fmt;
 
 /// This resource stores tile definitions. It is used in both the level editor and the actual game.
 /// Definitions are loaded from a file. Each tile definition describes the properties of a type of
 /// tile or entity that can be encountered in a level. Level files only contain string references to
 /// tile definitions. This protects level files from becoming outdated when tile definitions are
 /// updated.
+///
+/// In the file, a definition can extend a parent definition, see `TileDefinitionsFile`. The
+/// inheritance is resolved when the definitions are loaded.
 #[derive(Debug, Deserialize, Serialize, Clone)]
-#[serde(default)]
-#[serde(deny_unknown_fields)]
+#[serde(try_from = "TileDefinitionsFile")]
 pub struct TileDefinitions {
     /// Fallback value returned if a requested value cannot be found.
     fallback: TileDefinition,
@@ -39,12 +43,182 @@ impl<'a> TileDefinitions {
     }
 }
 
+/// The tile definitions as they are written in `tile_references.ron`. Each definition may name a
+/// `parent`: it then starts out as a copy of that definition and only overrides the fields that it
+/// sets itself. Parents can be other definitions in the map, or templates. Templates are only
+/// there to be extended, they cannot be placed in levels themselves.
+#[derive(Debug, Deserialize, Clone)]
+#[serde(default)]
+#[serde(deny_unknown_fields)]
+pub struct TileDefinitionsFile {
+    pub fallback: TileDefinition,
+    pub templates: HashMap<String, InheritingTileDefinition>,
+    pub map: HashMap<String, InheritingTileDefinition>,
+}
+
+impl Default for TileDefinitionsFile {
+    fn default() -> Self {
+        TileDefinitionsFile {
+            fallback: TileDefinition::fallback(),
+            templates: HashMap::default(),
+            map: HashMap::default(),
+        }
+    }
+}
+
+/// A tile definition in `TileDefinitionsFile`. Fields that are not set are taken from the parent,
+/// or from the default `TileDefinition` if there is no parent.
+///
+/// Like with `TileProperties`, an optional field such as the collision can be replaced but not
+/// cleared. Leave it out of the parent instead.
+#[derive(Debug, Clone, Deserialize, Default)]
+#[serde(default)]
+#[serde(deny_unknown_fields)]
+pub struct InheritingTileDefinition {
+    /// The key of the definition or template that this definition extends.
+    pub parent: Option<String>,
+    pub depth: Option<DepthLayer>,
+    pub dimens: Option<Pos>,
+    pub unique: Option<bool>,
+    pub mandatory: Option<bool>,
+    pub climbable: Option<bool>,
+    pub collision: Option<CollisionDefinition>,
+    pub asset: Option<AssetType>,
+    pub preview_asset: Option<AssetType>,
+    pub archetype: Option<Archetype>,
+    pub sturdiness: Option<Sturdiness>,
+}
+
+impl InheritingTileDefinition {
+    fn apply_to(&self, parent: TileDefinition) -> TileDefinition {
+        TileDefinition {
+            depth: self.depth.unwrap_or(parent.depth),
+            dimens: self.dimens.unwrap_or(parent.dimens),
+            unique: self.unique.unwrap_or(parent.unique),
+            mandatory: self.mandatory.unwrap_or(parent.mandatory),
+            climbable: self.climbable.unwrap_or(parent.climbable),
+            collision: self.collision.or(parent.collision),
+            asset: self.asset.clone().or(parent.asset),
+            preview_asset: self.preview_asset.clone().or(parent.preview_asset),
+            archetype: self.archetype.or(parent.archetype),
+            sturdiness: self.sturdiness.unwrap_or(parent.sturdiness),
+        }
+    }
+}
+
+/// The reasons why the inheritance in a `TileDefinitionsFile` cannot be resolved.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TileDefinitionError {
+    /// The definition or template extends a parent that does not exist.
+    UnknownParent { key: String, parent: String },
+    /// The key is used both for a definition and for a template, so it is unclear which one a
+    /// child extends.
+    AmbiguousKey(String),
+    /// The definitions extend each other in a loop. Contains the keys along the loop, starting
+    /// and ending with the same key.
+    Cycle(Vec<String>),
+}
+
+impl fmt::Display for TileDefinitionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TileDefinitionError::UnknownParent { key, parent } => write!(
+                f,
+                "tile definition {:?} extends {:?}, but there is no definition or template with that key",
+                key, parent
+            ),
+            TileDefinitionError::AmbiguousKey(key) => write!(
+                f,
+                "{:?} is the key of both a tile definition and a template",
+                key
+            ),
+            TileDefinitionError::Cycle(keys) => write!(
+                f,
+                "tile definitions extend each other in a cycle: {}",
+                keys.iter()
+                    .map(|key| format!("{:?}", key))
+                    .collect::<Vec<_>>()
+                    .join(" -> ")
+            ),
+        }
+    }
+}
+
+impl TryFrom<TileDefinitionsFile> for TileDefinitions {
+    type Error = TileDefinitionError;
+
+    fn try_from(file: TileDefinitionsFile) -> Result<Self, Self::Error> {
+        if let Some(key) = file
+            .map
+            .keys()
+            .find(|key| file.templates.contains_key(*key))
+        {
+            return Err(TileDefinitionError::AmbiguousKey(key.clone()));
+        }
+        let mut resolved = HashMap::new();
+        for key in file.templates.keys().chain(file.map.keys()) {
+            resolve_tile_definition(key, &file, &mut resolved, &mut vec![])?;
+        }
+        let map = file
+            .map
+            .keys()
+            .map(|key| (key.clone(), resolved[key].clone()))
+            .collect();
+        Ok(TileDefinitions {
+            fallback: file.fallback,
+            map,
+        })
+    }
+}
+
+/// Resolves the definition with the given key and all of its ancestors, memoising the results.
+/// The chain contains the keys that are being resolved further down the call stack; finding the
+/// key in there again means the definitions extend each other in a cycle.
+fn resolve_tile_definition(
+    key: &str,
+    file: &TileDefinitionsFile,
+    resolved: &mut HashMap<String, TileDefinition>,
+    chain: &mut Vec<String>,
+) -> Result<TileDefinition, TileDefinitionError> {
+    if let Some(tile_def) = resolved.get(key) {
+        return Ok(tile_def.clone());
+    }
+    if let Some(start) = chain.iter().position(|other| other == key) {
+        let mut cycle = chain[start..].to_vec();
+        cycle.push(key.to_string());
+        return Err(TileDefinitionError::Cycle(cycle));
+    }
+    let definition = file
+        .map
+        .get(key)
+        .or_else(|| file.templates.get(key))
+        .unwrap_or_else(|| unreachable!("Parents are checked before they are resolved."));
+    let parent = match &definition.parent {
+        Some(parent) => {
+            if !file.map.contains_key(parent) && !file.templates.contains_key(parent) {
+                return Err(TileDefinitionError::UnknownParent {
+                    key: key.to_string(),
+                    parent: parent.clone(),
+                });
+            }
+            chain.push(key.to_string());
+            let parent = resolve_tile_definition(parent, file, resolved, chain)?;
+            chain.pop();
+            parent
+        }
+        None => TileDefinition::default(),
+    };
+    let tile_def = definition.apply_to(parent);
+    resolved.insert(key.to_string(), tile_def.clone());
+    Ok(tile_def)
+}
+
 ///TODO:
 /// Name of struct. Tile?Block? What do we call the dimens unit? Tile? Meter? Grid space?
 /// asset not necessarily the same as the asset in editor. (Mob spawn)
 /// this tile map resource that is created, doesn't need to contain ALL tiles.
 ///
-#[derive(Debug, Copy, Clone, Deserialize, Serialize, Default)]
+#[derive(Debug, Clone, Deserialize, Serialize, Default)]
 #[serde(default)]
 #[serde(deny_unknown_fields)]
 pub struct TileDefinition {
@@ -89,7 +263,7 @@ impl TileDefinition {
             mandatory: false,
             climbable: false,
             collision: None,
-            asset: Some(AssetType::Still(SpriteType::NotFound, 0)),
+            asset: Some(AssetType::Still(SpriteType::NOT_FOUND, 0)),
             preview_asset: None,
             archetype: None,
             sturdiness: Sturdiness::Invulnerable,
@@ -136,11 +310,75 @@ impl TileDefinition {
     /// Mainly used by the editor.
     #[must_use]
     pub fn get_preview(&self) -> AssetType {
-        self.preview_asset.or(self.asset).unwrap_or_default()
+        self.preview_asset
+            .as_ref()
+            .or(self.asset.as_ref())
+            .cloned()
+            .unwrap_or_default()
+    }
+}
+
+/// Overrides for the properties of a single tile in a level. Every field that is set replaces the
+/// corresponding field of the tile's `TileDefinition`, fields that are not set keep the value of
+/// the definition. This allows two tiles with the same definition to differ, for example a tool
+/// that breaks more blocks than usual.
+///
+/// The dimensions of a tile and whether it is unique or mandatory cannot be overridden, because
+/// the layout and the validation of the level depend on them.
+#[derive(Debug, Clone, Deserialize, Serialize, Default, PartialEq)]
+#[serde(default)]
+#[serde(deny_unknown_fields)]
+pub struct TileProperties {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub depth: Option<DepthLayer>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub climbable: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub collision: Option<CollisionDefinition>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub asset: Option<AssetType>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub archetype: Option<Archetype>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sturdiness: Option<Sturdiness>,
+}
+
+impl TileProperties {
+    /// True if no property is overridden.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        *self == TileProperties::default()
+    }
+
+    /// Returns a copy of the tile definition with these properties applied over it.
+    #[must_use]
+    pub fn apply_to(&self, tile_def: TileDefinition) -> TileDefinition {
+        TileDefinition {
+            depth: self.depth.unwrap_or(tile_def.depth),
+            climbable: self.climbable.unwrap_or(tile_def.climbable),
+            collision: self.collision.or(tile_def.collision),
+            asset: self.asset.clone().or(tile_def.asset),
+            archetype: self.archetype.or(tile_def.archetype),
+            sturdiness: self.sturdiness.unwrap_or(tile_def.sturdiness),
+            ..tile_def
+        }
+    }
+
+    /// Parses properties written in RON, for example `(depth: Player, climbable: true)`.
+    /// Values may be written with or without wrapping them in `Some`, but not both at once.
+    pub fn parse(text: &str) -> Result<TileProperties, ron::de::Error> {
+        ron::de::from_str(text)
+            .or_else(|_| ron::de::from_str(&format!("#![enable(implicit_some)]\n{}", text)))
+    }
+
+    /// Writes the properties in RON, leaving out the properties that are not overridden.
+    #[must_use]
+    pub fn to_ron(&self) -> String {
+        ron::ser::to_string(self).expect("Failed to serialise tile properties.")
     }
 }
 
-#[derive(Debug, Copy, Clone, Deserialize, Serialize)]
+#[derive(Debug, Copy, Clone, Deserialize, Serialize, PartialEq)]
 pub enum DepthLayer {
     Background,
     DebugLines,
@@ -202,6 +440,9 @@ pub enum Archetype {
     Door,
     /// An item that can be picked up and equipped by the player.
     Tool(ToolType),
+    /// A block that the player can push one tile horizontally, which falls when it is no longer
+    /// supported. The tile should collide, so that it can be stood on and pushed against.
+    Pushable,
 }
 
 /// What it takes to break this block.
@@ -221,7 +462,7 @@ impl Default for Sturdiness {
     }
 }
 
-#[derive(Debug, Copy, Clone, Deserialize, Serialize, PartialEq)]
+#[derive(Debug, Copy, Clone, Deserialize, Serialize, PartialEq, Eq, Hash)]
 #[serde(deny_unknown_fields)]
 pub enum ToolType {
     /// This tool will break the blocks that the player is facing, n layers deep.
@@ -236,6 +477,31 @@ pub enum ToolType {
     /// If the player is facing right and occupies blocks (0, 0) to (1, 1) inclusive, the blocks
     /// that are targeted are: (1, -1) to (2, -depth) inclusive.
     BreakBlocksBelow(u8),
+    /// Dynamite, which the player places at their feet on the side they are facing. When the fuse
+    /// has burnt down, it breaks every breakable block in a cross around it. Each arm of the cross
+    /// reaches `radius` tiles out and is stopped by the first invulnerable tile in its way.
+    ///
+    /// If the player is facing right and occupies blocks (0, 0) to (1, 1) inclusive, the dynamite
+    /// is placed at (1, 0).
+    Dynamite {
+        /// How many tiles the explosion reaches in each of the four directions.
+        radius: u8,
+        /// The number of fixed ticks until the dynamite explodes, counting the tick it is placed.
+        fuse: u32,
+    },
+    /// Places a ladder of `length` segments in front of the player, on the side they are facing.
+    /// The ladder either goes up from the player's feet, or down from just below their feet, for
+    /// example to climb down from a ledge. The ladder is only placed if nothing is in its way.
+    ///
+    /// If the player is facing right and occupies blocks (0, 0) to (1, 1) inclusive, an upwards
+    /// ladder of one-high segments covers (2, 0) to (3, length - 1) inclusive. A downwards ladder
+    /// covers (2, -1) to (3, -length) inclusive.
+    PlaceLadder {
+        /// How many ladder segments are placed.
+        length: u8,
+        /// Whether the ladder goes up from the player's feet, rather than down.
+        upwards: bool,
+    },
 }
 
 impl Default for ToolType {
@@ -244,7 +510,7 @@ impl Default for ToolType {
     }
 }
 
-#[derive(Debug, Copy, Clone, Deserialize, Serialize)]
+#[derive(Debug, Copy, Clone, Deserialize, Serialize, PartialEq)]
 #[serde(deny_unknown_fields)]
 pub struct CollisionDefinition {
     /// Player can stand on these tiles. Examples include regular blocks and ladders.
@@ -1,6 +1,6 @@
 use crate::components::Pos;
 use crate::levels::LevelSave;
-use crate::resources::{TileDefinition, TileDefinitions, WorldBounds};
+use crate::resources::{TileDefinition, TileDefinitions, TileProperties, WorldBounds};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
@@ -9,6 +9,10 @@ pub struct TileMap {
     pub world_bounds: WorldBounds,
     pub tiles: HashMap<Pos, Tile>,
     pub tile_defs: TileDefinitions,
+    /// The instance properties of tiles, by the anchor position of the tile they belong to.
+    properties: HashMap<Pos, TileProperties>,
+    /// The definitions of the tiles that have instance properties, with those properties applied.
+    instance_defs: HashMap<Pos, TileDefinition>,
 }
 
 impl TileMap {
@@ -26,15 +30,21 @@ impl TileMap {
 
     fn new(level: &LevelSave, tile_defs: TileDefinitions, apply_filter: bool) -> Self {
         let mut tiles = HashMap::new();
+        let mut properties = HashMap::new();
+        let mut instance_defs = HashMap::new();
         level.tiles.iter()
             .map(|(pos, key)| {
-                (pos, key, tile_defs.get(key))
+                (pos, key, level.tile_def(*pos, key, &tile_defs))
             })
             .filter(|(_, _, tile_def)| {
                 // Make sure we only add relevant stuff to the tile map.
                 !apply_filter || tile_def.climbable || tile_def.collision.is_some() || tile_def.is_breakable()
             })
             .for_each(|(pos, key, tile_def)| {
+                if let Some(tile_properties) = level.properties.get(pos) {
+                    properties.insert(*pos, tile_properties.clone());
+                    instance_defs.insert(*pos, tile_def.clone());
+                }
                 let dimens = tile_def.dimens;
                 for x in 0..dimens.x {
                     for y in 0..dimens.y {
@@ -57,25 +67,54 @@ impl TileMap {
             world_bounds: level.world_bounds,
             tiles,
             tile_defs,
+            properties,
+            instance_defs,
         }
     }
 
+    /// Returns the definition of the tile covering the given position, with the instance
+    /// properties of that tile applied.
     #[must_use]
     pub fn get_tile(&self, pos: Pos) -> Option<&TileDefinition> {
-        self.tiles
-            .get(&pos)
-            .and_then(|tile| match tile {
-                Tile::Dummy(pos) => match self.tiles.get(pos) {
-                    Some(Tile::TileDefKey(key)) => Some(key),
-                    _ => {
-                        error!("Error! Dummy position lookup failed for tile {:?}", tile);
-                        None
-                    }
-                },
-                Tile::TileDefKey(key) => Some(key),
-                _ => None,
-            })
-            .map(|tile_def_key| self.tile_defs.get(tile_def_key))
+        let anchor = self.get_actual_pos(pos)?;
+        match self.tiles.get(&anchor) {
+            Some(Tile::TileDefKey(key)) => Some(
+                self.instance_defs
+                    .get(&anchor)
+                    .unwrap_or_else(|| self.tile_defs.get(key)),
+            ),
+            tile => {
+                error!("Error! Dummy position lookup failed for tile {:?}", tile);
+                None
+            }
+        }
+    }
+
+    /// The instance properties of all tiles, by the anchor position of the tile they belong to.
+    #[must_use]
+    pub fn properties(&self) -> &HashMap<Pos, TileProperties> {
+        &self.properties
+    }
+
+    /// Replaces the instance properties of the tile anchored at the given position.
+    /// Passing None or empty properties removes them. Does nothing if there is no tile anchored
+    /// at the given position.
+    pub fn set_properties(&mut self, anchor: Pos, properties: Option<TileProperties>) {
+        let key = match self.tiles.get(&anchor) {
+            Some(Tile::TileDefKey(key)) => key,
+            _ => return,
+        };
+        match properties.filter(|properties| !properties.is_empty()) {
+            Some(properties) => {
+                let tile_def = properties.apply_to(self.tile_defs.get(key).clone());
+                self.properties.insert(anchor, properties);
+                self.instance_defs.insert(anchor, tile_def);
+            }
+            None => {
+                self.properties.remove(&anchor);
+                self.instance_defs.remove(&anchor);
+            }
+        }
     }
 
     #[must_use]
@@ -103,6 +142,8 @@ impl TileMap {
                     self.tiles.remove(&actual_pos.append_xy(x, y));
                 });
             });
+            self.properties.remove(&actual_pos);
+            self.instance_defs.remove(&actual_pos);
         }
         actual_pos
     }
@@ -121,6 +162,42 @@ impl TileMap {
         }
     }
 
+    /// Moves the tile anchored at `from`, including its instance properties, so that it is
+    /// anchored at `to`. The tile may overlap its own old position, but not any other tile.
+    ///
+    /// Returns true iff the tile was moved. Nothing happens if there is no tile anchored at
+    /// `from` or if another tile is in the way.
+    pub fn move_tile(&mut self, from: Pos, to: Pos) -> bool {
+        let key = match self.tiles.get(&from) {
+            Some(Tile::TileDefKey(key)) => key.clone(),
+            _ => return false,
+        };
+        let dimens = self
+            .get_tile(from)
+            .expect("The tile is anchored at `from`.")
+            .dimens;
+        let blocked = (0..dimens.x).any(|x| {
+            (0..dimens.y).any(|y| {
+                self.get_actual_pos(to.append_xy(x, y))
+                    .map_or(false, |anchor| anchor != from)
+            })
+        });
+        if blocked {
+            return false;
+        }
+        let properties = self.properties.remove(&from);
+        let instance_def = self.instance_defs.remove(&from);
+        self.remove_tile(from);
+        self.put_tile(to, key, dimens);
+        if let Some(properties) = properties {
+            self.properties.insert(to, properties);
+        }
+        if let Some(instance_def) = instance_def {
+            self.instance_defs.insert(to, instance_def);
+        }
+        true
+    }
+
     pub fn put_tile(&mut self, pos: Pos, tile_def_key: String, dimensions: Pos) {
         self.tiles.insert(pos, Tile::TileDefKey(tile_def_key));
         (0..dimensions.x).for_each(|x| {
@@ -133,7 +210,7 @@ impl TileMap {
     }
 }
 
-#[derive(Debug, Clone, Deserialize, Serialize)]
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
 pub enum Tile {
     /// A dummy tile, points towards its anchor point, where the real tile is stored.
     /// Dummy tiles are used when a tile is bigger than 1 by 1. The bottom-left position within the
@@ -0,0 +1,20 @@
+use amethyst::prelude::{Config, World};
+
+use crate::resources::MovementConfig;
+use crate::utility::files::get_movement_config_file;
+
+/// Reloads the movement config from file, for when it was changed while the game runs.
+/// If the file cannot be loaded, for example because it is only half edited, the current config
+/// is kept.
+pub fn reload_movement_config(world: &mut World) {
+    match MovementConfig::load(get_movement_config_file()) {
+        Ok(config) => {
+            info!("Reloaded the movement config.");
+            world.insert(config);
+        }
+        Err(error) => error!(
+            "Failed to reload the movement config, keeping the current one: {}",
+            error
+        ),
+    }
+}
@@ -1,9 +1,11 @@
+pub mod hot_reload;
 mod level_select;
 mod paused;
 mod play;
 mod settings;
 pub mod window_event_handler;
 
+pub use self::hot_reload::*;
 pub use self::level_select::*;
 pub use self::paused::*;
 pub use self::play::*;
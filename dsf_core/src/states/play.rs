@@ -7,7 +7,7 @@ use amethyst::{
     animation::{
         get_animation_set, AnimationCommand, AnimationControlSet, AnimationSet, EndControl,
     },
-    core::timing::Time,
+    core::{timing::Time, Transform},
     ecs::{prelude::World, Entities, Join, ReadStorage, WriteStorage},
     input::{is_close_requested, is_key_down, InputEvent, VirtualKeyCode},
     renderer::sprite::SpriteRender,
@@ -17,13 +17,14 @@ use amethyst::{GameData, SimpleState, SimpleTrans};
 
 use dsf_precompile::AnimationId;
 
+use crate::components::CameraFrame;
 use crate::entities::create_camera;
-use crate::levels::load_level;
-use crate::resources::{CurrentState, DebugSettings, SoundType, UiHandles, UiType};
-use crate::states::window_event_handler;
+use crate::levels::{load_level, load_tile_definitions, LevelFormatError, LevelSave};
+use crate::resources::{CurrentState, DebugSettings, FileWatcher, SoundType, UiHandles, UiType};
+use crate::states::{reload_movement_config, window_event_handler};
 use crate::systems;
 use crate::systems::SoundEvent;
-use crate::utility::files::get_levels_dir;
+use crate::utility::files::{get_levels_dir, get_movement_config_file, get_tile_definitions_file};
 use amethyst::core::ecs::{Dispatcher, DispatcherBuilder};
 use amethyst::core::shrev::EventChannel;
 use amethyst::core::SystemExt;
@@ -52,10 +53,15 @@ impl<'a, 'b> PlayState {
                     "player_system",
                     &[],
                 )
+                .with(
+                    systems::PushSystem.pausable(CurrentState::Running),
+                    "push_system",
+                    &["player_system"],
+                )
                 .with(
                     systems::SteeringSystem::default().pausable(CurrentState::Running),
                     "steering_system",
-                    &["player_system"],
+                    &["push_system"],
                 )
                 .with(
                     systems::MovementSystem.pausable(CurrentState::Running),
@@ -81,7 +87,17 @@ impl<'a, 'b> PlayState {
                 .with(systems::KeyCollectionSystem, "key_collection_system", &[])
                 .with(systems::PickupSystem, "pickup_system", &[])
                 .with(systems::UseToolSystem, "use_tool_system", &[])
+                .with(
+                    systems::DynamiteSystem.pausable(CurrentState::Running),
+                    "dynamite_system",
+                    &["use_tool_system"],
+                )
                 .with(systems::LevelWrappingSystem, "level_wrapping_system", &[])
+                .with(
+                    systems::PushableTileSystem,
+                    "pushable_tile_system",
+                    &["level_wrapping_system", "rewind_system"],
+                )
                 .with(systems::WinSystem, "win_system", &[])
                 .build(),
         }
@@ -120,6 +136,65 @@ impl<'a, 'b> PlayState {
         create_camera(world);
         load_level(&self.level_file, world).expect("Failed to load level!");
     }
+
+    /// Watch the files that the level is built from, so it can be rebuilt when they change.
+    fn watch_files(&self, world: &mut World) {
+        world.write_resource::<FileWatcher>().watch(vec![
+            get_tile_definitions_file(),
+            get_movement_config_file(),
+            self.level_file.clone(),
+        ]);
+    }
+
+    fn reload_changed_files(&self, world: &mut World) {
+        let changed = world.write_resource::<FileWatcher>().changed_files();
+        if changed.contains(&get_movement_config_file()) {
+            reload_movement_config(world);
+        }
+        if changed.contains(&get_tile_definitions_file()) || changed.contains(&self.level_file) {
+            self.reload_level(world);
+        }
+    }
+
+    /// Restarts the level with the tile definitions and the level file as they are on disk now.
+    /// The camera keeps its position, so the view does not jump.
+    /// If either file cannot be loaded, for example because it is only half edited, the current
+    /// level keeps running.
+    fn reload_level(&self, world: &mut World) {
+        let loaded = load_tile_definitions()
+            .map_err(LevelFormatError::from)
+            .and_then(|_| LevelSave::load(&self.level_file));
+        if let Err(error) = loaded {
+            error!(
+                "Failed to reload level {:?}, keeping the current one: {}",
+                self.level_file, error
+            );
+            return;
+        }
+        let camera = world.exec(
+            |(frames, transforms): (ReadStorage<'_, CameraFrame>, ReadStorage<'_, Transform>)| {
+                (&frames, &transforms)
+                    .join()
+                    .next()
+                    .map(|(frame, transform)| (*frame, transform.clone()))
+            },
+        );
+        self.reset_level(world);
+        if let Some((old_frame, old_transform)) = camera {
+            world.exec(
+                |(mut frames, mut transforms): (
+                    WriteStorage<'_, CameraFrame>,
+                    WriteStorage<'_, Transform>,
+                )| {
+                    for (frame, transform) in (&mut frames, &mut transforms).join() {
+                        *frame = old_frame;
+                        *transform = old_transform.clone();
+                    }
+                },
+            );
+        }
+        info!("Reloaded level {:?}.", self.level_file);
+    }
 }
 
 impl SimpleState for PlayState {
@@ -127,6 +202,7 @@ impl SimpleState for PlayState {
         info!("PlayState on_start");
         self.dispatcher.setup(data.world);
         self.reset_level(data.world);
+        self.watch_files(data.world);
     }
 
     fn on_stop(&mut self, data: StateData<'_, GameData<'_, '_>>) {
@@ -169,6 +245,7 @@ impl SimpleState for PlayState {
     }
 
     fn update(&mut self, data: &mut StateData<'_, GameData<'_, '_>>) -> SimpleTrans {
+        self.reload_changed_files(data.world);
         // Execute a pass similar to a system
         data.world.exec(
             |(entities, animation_sets, mut control_sets): (
@@ -98,8 +98,8 @@ fn move_cursor(
     }
 }
 
-/// Updates the UI label on the adventure and level select screen. The label must always display the
-/// name of the currently selected node.
+/// Updates the UI labels on the adventure and level select screen. The labels must always display
+/// the title and details of the currently selected node.
 #[derive(Copy, Clone, Debug)]
 pub struct LevelSelectUiUpdateSystem;
 
@@ -112,20 +112,29 @@ impl<'s> System<'s> for LevelSelectUiUpdateSystem {
     );
 
     fn run(&mut self, (mut ui_text, finder, adventure, pos_on_map): Self::SystemData) {
-        let label_title = {
-            let label_title_entity = finder.find("label_node_title");
-            label_title_entity.and_then(|fps_entity| ui_text.get_mut(fps_entity))
+        let selected = adventure.nodes.get(&pos_on_map.pos);
+        let metadata = adventure.level_metadata.get(&pos_on_map.pos);
+        let (selected_title, details) = match selected {
+            Some(MapElement::Node(AdventureNode {
+                details: NodeDetails::Level(file_name),
+                ..
+            })) => match metadata {
+                Some(metadata) => (metadata.display_title(file_name), metadata.details()),
+                None => (file_name.as_str(), String::new()),
+            },
+            _ => ("Nothing", String::new()),
         };
-        if let Some(mut label_title) = label_title {
-            let selected = adventure.nodes.get(&pos_on_map.pos);
-            let selected_title = match selected {
-                Some(MapElement::Node(AdventureNode {
-                    details: NodeDetails::Level(file_name),
-                    ..
-                })) => file_name,
-                _ => "Nothing",
-            };
-            label_title.text = format!("Selected: {:?}", selected_title);
+        if let Some(label_title) = finder
+            .find("label_node_title")
+            .and_then(|entity| ui_text.get_mut(entity))
+        {
+            label_title.text = format!("Selected: {}", selected_title);
+        }
+        if let Some(label_details) = finder
+            .find("label_node_details")
+            .and_then(|entity| ui_text.get_mut(entity))
+        {
+            label_details.text = details;
         }
     }
 }
@@ -1,5 +1,6 @@
-use crate::components::{Steering, SteeringMode};
+use crate::components::Steering;
 use crate::resources::TileMap;
+use crate::systems::wrap_around;
 use amethyst::core::ecs::{Join, Read, System, WriteStorage};
 use amethyst::core::Transform;
 
@@ -19,46 +20,10 @@ impl<'s> System<'s> for LevelWrappingSystem {
 
     fn run(&mut self, (mut steerings, mut transforms, tile_map): Self::SystemData) {
         for (transform, steering) in (&mut transforms, &mut steerings).join() {
-            if transform.translation().x < tile_map.world_bounds.x() as f32 {
-                transform.set_translation_x(
-                    transform.translation().x + tile_map.world_bounds.width() as f32,
-                );
-                steering.pos.x += tile_map.world_bounds.width();
-                steering.destination.x += tile_map.world_bounds.width();
-            } else if transform.translation().x > (tile_map.world_bounds.upper_x()) as f32 {
-                transform.set_translation_x(
-                    transform.translation().x - tile_map.world_bounds.width() as f32,
-                );
-                steering.pos.x -= tile_map.world_bounds.width();
-                steering.destination.x -= tile_map.world_bounds.width();
-            }
-
-            if transform.translation().y < tile_map.world_bounds.y() as f32 {
-                transform.set_translation_y(
-                    transform.translation().y + tile_map.world_bounds.height() as f32,
-                );
-                steering.pos.y += tile_map.world_bounds.height();
-                steering.destination.y += tile_map.world_bounds.height();
-                // Ignore warning, we'll want to add more patterns in the future.
-                #[allow(clippy::single_match)]
-                match steering.mode {
-                    SteeringMode::Falling {
-                        x_movement,
-                        starting_y_pos,
-                        duration,
-                    } => {
-                        steering.mode = SteeringMode::Falling {
-                            x_movement,
-                            starting_y_pos: starting_y_pos + tile_map.world_bounds.height() as f32,
-                            duration,
-                        }
-                    }
-                    _ => (),
-                };
-            } else if transform.translation().y > (tile_map.world_bounds.upper_y()) as f32 {
-                steering.pos.y -= tile_map.world_bounds.height();
-                steering.destination.y -= tile_map.world_bounds.height();
-            }
+            let mut translation = (transform.translation().x, transform.translation().y);
+            wrap_around(steering, &mut translation, &tile_map.world_bounds);
+            transform.set_translation_x(translation.0);
+            transform.set_translation_y(translation.1);
         }
     }
 }
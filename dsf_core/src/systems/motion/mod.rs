@@ -1,9 +1,13 @@
 mod level_wrapping;
 mod movement;
 mod player;
+mod push;
+mod simulation;
 mod steering;
 
 pub use self::level_wrapping::*;
 pub use self::movement::*;
 pub use self::player::*;
+pub use self::push::*;
+pub use self::simulation::*;
 pub use self::steering::*;
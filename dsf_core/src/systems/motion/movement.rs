@@ -1,6 +1,7 @@
-use crate::components::{Direction1D, Steering, SteeringMode, Velocity};
+use crate::components::{Direction1D, Steering, Velocity};
 
 use crate::resources::MovementConfig;
+use crate::systems::{apply_steering, apply_velocity};
 
 use amethyst::core::num::FloatConst;
 use amethyst::{
@@ -23,10 +24,10 @@ impl<'s> System<'s> for VelocitySystem {
 
     fn run(&mut self, (mut transforms, velocities, time): Self::SystemData) {
         for (transform, velocity) in (&mut transforms, &velocities).join() {
-            transform
-                .set_translation_x(transform.translation().x + time.fixed_seconds() * velocity.x);
-            transform
-                .set_translation_y(transform.translation().y + time.fixed_seconds() * velocity.y);
+            let mut translation = (transform.translation().x, transform.translation().y);
+            apply_velocity(&mut translation, *velocity, time.fixed_seconds());
+            transform.set_translation_x(translation.0);
+            transform.set_translation_y(translation.1);
         }
     }
 }
@@ -57,58 +58,10 @@ impl<'s> System<'s> for MovementSystem {
                 0.
             });
 
-            let (centered_x, centered_y) = steering.to_centered_coords(steering.pos);
-            let (desired_pos_x, desired_pos_y) = steering.to_centered_coords(steering.destination);
-            match steering.mode {
-                SteeringMode::Grounded => {
-                    // If grounded, correct y translation and zero out y velocity.
-                    transform.set_translation_y(centered_y);
-                    velocity.y = 0.0;
-                }
-                SteeringMode::Climbing => {
-                    // If climbing, correct x translation and zero out x velocity.
-                    transform.set_translation_x(centered_x);
-                    velocity.x = 0.0;
-                    // If climbing:
-                    let delta = desired_pos_y - transform.translation().y;
-                    if steering.facing.y.aligns_with(delta) {
-                        velocity.y = steering.facing.y.signum() * config.player_speed;
-                    } else {
-                        velocity.y = 0.0;
-                        transform.set_translation_y(centered_y);
-                    }
-                }
-                SteeringMode::Falling {
-                    starting_y_pos,
-                    duration,
-                    ..
-                } => {
-                    // Set y-position directly, based on movement function. We don't use velocity for this.
-                    velocity.y = 0.0;
-                    transform
-                        .set_translation_y(starting_y_pos + steering.mode.calc_delta_y(duration));
-                }
-                SteeringMode::Jumping {
-                    starting_y_pos,
-                    duration,
-                    ..
-                } => {
-                    // Set y-position directly, based on movement function. We don't use velocity for this.
-                    velocity.y = 0.0;
-                    transform
-                        .set_translation_y(starting_y_pos + steering.mode.calc_delta_y(duration));
-                }
-            }
-
-            // Set x-velocity based on current and desired position.
-            // If necessary, adjust x-position, snap to grid.
-            let delta = desired_pos_x - transform.translation().x;
-            if steering.facing.x.aligns_with(delta) {
-                velocity.x = steering.facing.x.signum() * config.player_speed;
-            } else {
-                velocity.x = 0.0;
-                transform.set_translation_x(centered_x);
-            }
+            let mut translation = (transform.translation().x, transform.translation().y);
+            apply_steering(steering, &mut translation, velocity, config.player_speed);
+            transform.set_translation_x(translation.0);
+            transform.set_translation_y(translation.1);
         }
     }
 }
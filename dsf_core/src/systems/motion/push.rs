@@ -0,0 +1,168 @@
+use crate::components::{
+    Block, Direction1D, Direction2D, Player, Pos, Pushable, Steering, SteeringIntent,
+};
+use crate::resources::{Archetype, TileMap};
+use amethyst::core::ecs::{Join, ReadStorage, System, Write, WriteStorage};
+use amethyst::core::Transform;
+
+/// Lets players push blocks by walking into them. Runs before the `SteeringSystem`, so that the
+/// player can step into the space the block leaves behind during the same tick.
+#[derive(Copy, Clone, Default, Debug)]
+pub struct PushSystem;
+
+impl<'s> System<'s> for PushSystem {
+    type SystemData = (
+        ReadStorage<'s, Player>,
+        ReadStorage<'s, SteeringIntent>,
+        ReadStorage<'s, Transform>,
+        ReadStorage<'s, Pushable>,
+        WriteStorage<'s, Steering>,
+        WriteStorage<'s, Block>,
+        Write<'s, TileMap>,
+    );
+
+    fn run(
+        &mut self,
+        (players, intents, transforms, pushables, mut steerings, mut blocks, mut tile_map): Self::SystemData,
+    ) {
+        let pushes = (&players, &intents, &transforms, &steerings)
+            .join()
+            .map(|(_, intent, transform, steering)| {
+                let translation = (transform.translation().x, transform.translation().y);
+                (
+                    intent.walk,
+                    tiles_to_push(steering, translation, intent.walk, &tile_map),
+                )
+            })
+            .filter(|(_, anchors)| !anchors.is_empty())
+            .collect::<Vec<_>>();
+        for (walk, anchors) in pushes {
+            let mut pushed = (&pushables, &mut blocks, &mut steerings)
+                .join()
+                .filter(|(_, block, _)| anchors.contains(&block.pos))
+                .map(|(_, block, steering)| (block, steering))
+                .collect::<Vec<_>>();
+            // Blocks that are still moving cannot be pushed. All blocks must be at rest, otherwise
+            // none of them are pushed.
+            let all_at_rest = pushed.len() == anchors.len()
+                && pushed.iter().all(|(_, steering)| {
+                    steering.is_grounded() && steering.destination == steering.pos
+                });
+            if !all_at_rest {
+                continue;
+            }
+            for (block, steering) in &mut pushed {
+                let new_pos = block.pos.append_x(walk.signum_i());
+                if tile_map.move_tile(block.pos, new_pos) {
+                    block.pos = new_pos;
+                    steering.destination = steering.pos.append_x(walk.signum_i());
+                    steering.facing = Direction2D::from(walk, Direction1D::Neutral);
+                }
+            }
+        }
+    }
+}
+
+/// Keeps the tiles of pushable blocks in the `TileMap` at the same position as the blocks
+/// themselves while they fall or wrap around the level, or after they are rewound.
+#[derive(Copy, Clone, Default, Debug)]
+pub struct PushableTileSystem;
+
+impl<'s> System<'s> for PushableTileSystem {
+    type SystemData = (
+        ReadStorage<'s, Pushable>,
+        ReadStorage<'s, Steering>,
+        WriteStorage<'s, Block>,
+        Write<'s, TileMap>,
+    );
+
+    fn run(&mut self, (pushables, steerings, mut blocks, mut tile_map): Self::SystemData) {
+        for (_, steering, block) in (&pushables, &steerings, &mut blocks).join() {
+            // While a block is being pushed, its tile is already at the destination.
+            let tile_pos = if steering.is_grounded() {
+                steering.destination
+            } else {
+                steering.pos
+            };
+            if tile_pos != block.pos && tile_map.move_tile(block.pos, tile_pos) {
+                block.pos = tile_pos;
+            }
+        }
+    }
+}
+
+/// Returns the anchor positions of the pushable tiles that the entity pushes when it walks in the
+/// given direction, or an empty list if it does not push anything this tick.
+///
+/// The entity must be grounded and at the start of a step. It pushes only if every tile in front
+/// of it that is in the way is pushable, and every one of those tiles has empty space behind it
+/// inside the world bounds.
+/// The translation is the center point of the entity.
+#[must_use]
+pub fn tiles_to_push(
+    steering: &Steering,
+    translation: (f32, f32),
+    walk: Direction1D,
+    tile_map: &TileMap,
+) -> Vec<Pos> {
+    if !steering.is_grounded() || walk.is_neutral() {
+        return vec![];
+    }
+    let (anchored_x, anchored_y) = (
+        translation.0 - 0.5 * steering.dimens.x as f32,
+        translation.1 - 0.5 * steering.dimens.y as f32,
+    );
+    let offset_from_destination = steering.destination.x as f32 - anchored_x;
+    let starts_step = if walk.is_positive() {
+        offset_from_destination < f32::EPSILON
+    } else {
+        offset_from_destination > -f32::EPSILON
+    };
+    if !starts_step {
+        return vec![];
+    }
+    let pos = Pos::new(anchored_x.round() as i32, anchored_y.round() as i32);
+    let front_x = if walk.is_positive() {
+        pos.x + steering.dimens.x
+    } else {
+        pos.x - 1
+    };
+    let mut anchors: Vec<Pos> = vec![];
+    for y in pos.y..(pos.y + steering.dimens.y) {
+        let front = Pos::new(front_x, y);
+        match tile_map.get_tile(front) {
+            Some(tile_def) if tile_def.archetype == Some(Archetype::Pushable) => {
+                let anchor = tile_map
+                    .get_actual_pos(front)
+                    .expect("Every occupied position belongs to an anchor.");
+                if !anchors.contains(&anchor) {
+                    anchors.push(anchor);
+                }
+            }
+            Some(tile_def) if tile_def.collides_horizontally() => return vec![],
+            _ => (),
+        }
+    }
+    // Blocks are never pushed out of the level, not even partially.
+    let has_space_behind = |anchor: &Pos| {
+        let dimens = tile_map
+            .get_tile(*anchor)
+            .expect("Pushed tiles are looked up by their anchor.")
+            .dimens;
+        let behind_x = if walk.is_positive() {
+            anchor.x + dimens.x
+        } else {
+            anchor.x - 1
+        };
+        tile_map
+            .world_bounds
+            .encloses(anchor.append_x(walk.signum_i()), dimens)
+            && (anchor.y..(anchor.y + dimens.y))
+                .all(|y| tile_map.get_tile(Pos::new(behind_x, y)).is_none())
+    };
+    if anchors.iter().all(has_space_behind) {
+        anchors
+    } else {
+        vec![]
+    }
+}
@@ -0,0 +1,400 @@
+use crate::components::{
+    Direction1D, Direction2D, Pos, Steering, SteeringIntent, SteeringMode, Velocity,
+};
+use crate::resources::{SoundType, TileMap, WorldBounds};
+use crate::systems::{
+    above_air, can_climb_down, can_climb_up, is_against_wall_left, is_against_wall_right,
+    is_grounded, is_underneath_ceiling, on_solid_ground,
+};
+
+/// The complete movement state of a single entity with steering, without any ties to the ECS.
+///
+/// `simulate_tick` advances this state by one tick. The `SteeringSystem`, `MovementSystem`,
+/// `VelocitySystem` and `LevelWrappingSystem` all delegate to the same functions, so stepping a
+/// `PlayerSim` gives exactly the same results as playing the game.
+#[derive(Copy, Clone, Debug, Default)]
+pub struct PlayerSim {
+    pub steering: Steering,
+    /// The center point of the entity. This is equivalent to the translation of its `Transform`.
+    pub translation: (f32, f32),
+    pub velocity: Velocity,
+    /// The speed at which the entity walks and climbs, in tiles per second.
+    /// For the player, this is `MovementConfig::player_speed`.
+    pub speed: f32,
+}
+
+impl PlayerSim {
+    /// Creates a simulation of an entity that is standing still at the given position.
+    #[must_use]
+    pub fn new(pos: Pos, dimens: Pos, speed: f32) -> Self {
+        let steering = Steering::new(pos, dimens);
+        PlayerSim {
+            steering,
+            translation: steering.to_centered_coords(pos),
+            velocity: Velocity::default(),
+            speed,
+        }
+    }
+}
+
+/// Everything that happened during a single tick that the simulation itself does not act upon.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct TickEvents {
+    /// The sound effects that should be played.
+    pub sounds: Vec<SoundType>,
+    /// If true, the entity started climbing while it intended to walk. The caller should set
+    /// `SteeringIntent::walk_invalidated` before the next tick.
+    pub walk_invalidated: bool,
+}
+
+/// Advances the movement of the entity by `delta_seconds`.
+///
+/// This performs the work of the `SteeringSystem`, `MovementSystem`, `VelocitySystem` and
+/// `LevelWrappingSystem`, in that order.
+pub fn simulate_tick(
+    sim: &mut PlayerSim,
+    intent: &SteeringIntent,
+    tile_map: &TileMap,
+    delta_seconds: f32,
+) -> TickEvents {
+    let events = steer(
+        &mut sim.steering,
+        sim.translation,
+        intent,
+        tile_map,
+        delta_seconds,
+    );
+    apply_steering(
+        &sim.steering,
+        &mut sim.translation,
+        &mut sim.velocity,
+        sim.speed,
+    );
+    apply_velocity(&mut sim.translation, sim.velocity, delta_seconds);
+    wrap_around(
+        &mut sim.steering,
+        &mut sim.translation,
+        &tile_map.world_bounds,
+    );
+    events
+}
+
+/// Decides how the entity should move, based on its intent and the tiles surrounding it.
+/// Updates the steering mode, facing and destination, but does not actually move the entity.
+///
+/// The translation is the center point of the entity.
+pub fn steer(
+    steering: &mut Steering,
+    translation: (f32, f32),
+    intent: &SteeringIntent,
+    tile_map: &TileMap,
+    delta_seconds: f32,
+) -> TickEvents {
+    let mut events = TickEvents::default();
+    let anchored_x = translation.0 - 0.5 * steering.dimens.x as f32;
+    let anchored_y = translation.1 - 0.5 * steering.dimens.y as f32;
+    steering.pos = Pos::new(anchored_x.round() as i32, anchored_y.round() as i32);
+
+    if steering.is_mid_air() {
+        steering.mode = steering.mode.add_to_duration(delta_seconds);
+    }
+
+    if steering.is_grounded() && !intent.face.is_neutral() {
+        steering.facing.x = intent.face;
+    }
+
+    // The following if-else construction checks if the steering mode should be changed.
+    let has_ground_beneath_feet = is_grounded(steering, tile_map);
+    if steering.is_falling()
+        && anchored_y <= steering.pos.y as f32
+        && has_ground_beneath_feet
+        && on_solid_ground(steering, tile_map)
+    {
+        // If falling and you reached the floor, set to grounded.
+        steering.mode = SteeringMode::Grounded;
+        steering.destination = steering.pos;
+    } else if (steering.is_grounded()
+        && !has_ground_beneath_feet
+        && aligned_with_grid(steering.destination.x as f32, anchored_x, intent.walk))
+        || (steering.is_climbing() && intent.jump)
+    {
+        steering.mode = SteeringMode::Falling {
+            x_movement: Direction1D::Neutral,
+            starting_y_pos: translation.1,
+            duration: 0.,
+        };
+    } else if steering.is_grounded() && intent.jump {
+        if is_underneath_ceiling(steering, tile_map) {
+            events.sounds.push(SoundType::CannotPerformAction);
+        } else {
+            events.sounds.push(SoundType::Jump);
+            steering.mode = SteeringMode::Jumping {
+                x_movement: intent.face,
+                starting_y_pos: translation.1,
+                duration: 0.,
+            };
+        }
+    } else if steering.jump_has_peaked() {
+        steering.mode = steering.mode.jump_to_fall();
+    } else if steering.is_grounded()
+        && aligned_with_grid(steering.destination.x as f32, anchored_x, intent.walk)
+        && ((intent.climb.is_positive() && can_climb_up(steering, tile_map))
+            || (intent.climb.is_negative() && can_climb_down(steering, tile_map)))
+    {
+        steering.mode = SteeringMode::Climbing;
+        if !intent.walk.is_neutral() {
+            events.walk_invalidated = true;
+        }
+    } else if steering.is_climbing()
+        && aligned_with_grid(steering.destination.y as f32, anchored_y, intent.climb)
+        && !intent.walk_invalidated
+        && ((intent.walk.is_positive()
+            && !is_against_wall_right(steering, steering.pos.y as f32, tile_map))
+            || (intent.walk.is_negative()
+                && !is_against_wall_left(steering, steering.pos.y as f32, tile_map)))
+    {
+        steering.mode = SteeringMode::Grounded;
+    }
+
+    // This match will adjust the steering based on the current steering mode.
+    match steering.mode {
+        SteeringMode::Grounded => {
+            if !intent.walk.is_neutral() {
+                steering.facing = Direction2D::from(intent.walk, Direction1D::Neutral);
+                let offset_from_destination = steering.destination.x as f32 - anchored_x;
+                if offset_from_destination < f32::EPSILON && intent.walk.is_positive() {
+                    if !is_against_wall_right(steering, steering.pos.y as f32, tile_map) {
+                        steering.destination.x = steering.pos.x + 1;
+                        events.sounds.push(SoundType::Step);
+                    }
+                } else if offset_from_destination > -f32::EPSILON && intent.walk.is_negative() {
+                    if !is_against_wall_left(steering, steering.pos.y as f32, tile_map) {
+                        steering.destination.x = steering.pos.x - 1;
+                        events.sounds.push(SoundType::Step);
+                    }
+                } else if !intent
+                    .walk
+                    .aligns_with((steering.destination.x - steering.pos.x) as f32)
+                {
+                    // TODO: Maybe remove, this doesn't seem to do anything.
+                    // Player wants to go back where they came from.
+                    steering.destination.x = steering.pos.x;
+                }
+            }
+        }
+        SteeringMode::Climbing => {
+            if !intent.climb.is_neutral() {
+                steering.facing = Direction2D::from(Direction1D::Neutral, intent.climb);
+                let offset_from_discrete_pos = steering.destination.y as f32 - anchored_y;
+                if offset_from_discrete_pos < f32::EPSILON && intent.climb.is_positive() {
+                    if can_climb_up(steering, tile_map) {
+                        events.sounds.push(SoundType::LadderStep);
+                        steering.destination.y = steering.pos.y + 1;
+                    } else {
+                        steering.mode = SteeringMode::Grounded;
+                    }
+                } else if offset_from_discrete_pos > -f32::EPSILON && intent.climb.is_negative() {
+                    if can_climb_down(steering, tile_map) {
+                        events.sounds.push(SoundType::LadderStep);
+                        steering.destination.y = steering.pos.y - 1;
+                    } else if above_air(steering, tile_map) {
+                        steering.mode = SteeringMode::Falling {
+                            x_movement: Direction1D::Neutral,
+                            starting_y_pos: translation.1,
+                            duration: 0.,
+                        };
+                    } else {
+                        steering.mode = SteeringMode::Grounded;
+                    }
+                } else if !intent
+                    .climb
+                    .aligns_with((steering.destination.y - steering.pos.y) as f32)
+                {
+                    // TODO: Maybe remove, this doesn't seem to do anything.
+                    // Player wants to go back where they came from.
+                    steering.destination.y = steering.pos.y;
+                }
+            }
+        }
+        SteeringMode::Falling {
+            x_movement,
+            starting_y_pos,
+            duration,
+        } => {
+            if x_movement.is_neutral() {
+                // No horizontal movement.
+                steering.destination.x = steering.pos.x;
+            } else if x_movement.is_positive() {
+                // Moving towards the right.
+                if is_against_wall_right(steering, anchored_y, tile_map) {
+                    steering.mode = SteeringMode::Falling {
+                        x_movement: Direction1D::Neutral,
+                        starting_y_pos,
+                        duration,
+                    };
+                } else if aligned_with_grid(steering.destination.x as f32, anchored_x, x_movement) {
+                    steering.destination.x = steering.pos.x + 1;
+                }
+            } else {
+                // Moving towards the left.
+                if is_against_wall_left(steering, anchored_y, tile_map) {
+                    steering.mode = SteeringMode::Falling {
+                        x_movement: Direction1D::Neutral,
+                        starting_y_pos,
+                        duration,
+                    };
+                } else if aligned_with_grid(steering.destination.x as f32, anchored_x, x_movement) {
+                    steering.destination.x = steering.pos.x - 1;
+                }
+            }
+        }
+        SteeringMode::Jumping {
+            x_movement,
+            starting_y_pos,
+            duration,
+        } => {
+            if !intent.jump_direction.is_neutral() {
+                steering.mode = SteeringMode::Jumping {
+                    x_movement: intent.jump_direction,
+                    starting_y_pos,
+                    duration,
+                };
+                steering.facing = Direction2D::from(intent.jump_direction, Direction1D::Neutral);
+            }
+            if x_movement.is_neutral() {
+                // No horizontal movement.
+                steering.destination.x = steering.pos.x;
+            } else if x_movement.is_positive() {
+                // Moving towards the right.
+                if aligned_with_grid(steering.destination.x as f32, anchored_x, x_movement)
+                    && !is_against_wall_right(steering, steering.pos.y as f32, tile_map)
+                {
+                    steering.destination.x = steering.pos.x + 1;
+                }
+            } else {
+                // Moving towards the left.
+                if aligned_with_grid(steering.destination.x as f32, anchored_x, x_movement)
+                    && !is_against_wall_left(steering, steering.pos.y as f32, tile_map)
+                {
+                    steering.destination.x = steering.pos.x - 1;
+                }
+            }
+        }
+    };
+    events
+}
+
+/// Moves the entity towards its destination, based on its steering. Sets the velocity for modes
+/// that use velocity, and sets the translation directly for modes that follow a movement curve.
+pub fn apply_steering(
+    steering: &Steering,
+    translation: &mut (f32, f32),
+    velocity: &mut Velocity,
+    speed: f32,
+) {
+    let (centered_x, centered_y) = steering.to_centered_coords(steering.pos);
+    let (desired_pos_x, desired_pos_y) = steering.to_centered_coords(steering.destination);
+    match steering.mode {
+        SteeringMode::Grounded => {
+            // If grounded, correct y translation and zero out y velocity.
+            translation.1 = centered_y;
+            velocity.y = 0.0;
+        }
+        SteeringMode::Climbing => {
+            // If climbing, correct x translation and zero out x velocity.
+            translation.0 = centered_x;
+            velocity.x = 0.0;
+            // If climbing:
+            let delta = desired_pos_y - translation.1;
+            if steering.facing.y.aligns_with(delta) {
+                velocity.y = steering.facing.y.signum() * speed;
+            } else {
+                velocity.y = 0.0;
+                translation.1 = centered_y;
+            }
+        }
+        SteeringMode::Falling {
+            starting_y_pos,
+            duration,
+            ..
+        }
+        | SteeringMode::Jumping {
+            starting_y_pos,
+            duration,
+            ..
+        } => {
+            // Set y-position directly, based on movement function. We don't use velocity for this.
+            velocity.y = 0.0;
+            translation.1 = starting_y_pos + steering.mode.calc_delta_y(duration);
+        }
+    }
+
+    // Set x-velocity based on current and desired position.
+    // If necessary, adjust x-position, snap to grid.
+    let delta = desired_pos_x - translation.0;
+    if steering.facing.x.aligns_with(delta) {
+        velocity.x = steering.facing.x.signum() * speed;
+    } else {
+        velocity.x = 0.0;
+        translation.0 = centered_x;
+    }
+}
+
+/// Updates the translation according to the velocity.
+pub fn apply_velocity(translation: &mut (f32, f32), velocity: Velocity, delta_seconds: f32) {
+    translation.0 += delta_seconds * velocity.x;
+    translation.1 += delta_seconds * velocity.y;
+}
+
+/// Implements wrapping behaviour for levels.
+///
+/// IE: if character falls out the bottom, they appear at top. If character goes off to the left,
+/// they wrap around to the right.
+pub fn wrap_around(steering: &mut Steering, translation: &mut (f32, f32), bounds: &WorldBounds) {
+    if translation.0 < bounds.x() as f32 {
+        translation.0 += bounds.width() as f32;
+        steering.pos.x += bounds.width();
+        steering.destination.x += bounds.width();
+    } else if translation.0 > (bounds.upper_x()) as f32 {
+        translation.0 -= bounds.width() as f32;
+        steering.pos.x -= bounds.width();
+        steering.destination.x -= bounds.width();
+    }
+
+    if translation.1 < bounds.y() as f32 {
+        translation.1 += bounds.height() as f32;
+        steering.pos.y += bounds.height();
+        steering.destination.y += bounds.height();
+        // Ignore warning, we'll want to add more patterns in the future.
+        #[allow(clippy::single_match)]
+        match steering.mode {
+            SteeringMode::Falling {
+                x_movement,
+                starting_y_pos,
+                duration,
+            } => {
+                steering.mode = SteeringMode::Falling {
+                    x_movement,
+                    starting_y_pos: starting_y_pos + bounds.height() as f32,
+                    duration,
+                }
+            }
+            _ => (),
+        };
+    } else if translation.1 > (bounds.upper_y()) as f32 {
+        steering.pos.y -= bounds.height();
+        steering.destination.y -= bounds.height();
+    }
+}
+
+/// Returns true iff the player is aligned with the grid.
+/// This function can be used for both horizontal and vertical coordinates.
+fn aligned_with_grid(destination_pos: f32, actual_pos: f32, input: Direction1D) -> bool {
+    let offset = actual_pos - destination_pos;
+    // Actual pos equal or greater than destination. Moving towards the positive.
+    (offset > -f32::EPSILON && input.is_positive())
+        // Actual pos equal or smaller than destination. Moving towards the negative.
+        || (offset < f32::EPSILON && input.is_negative())
+        // Actual pos basically equal to the destination.
+        || (offset.abs() < f32::EPSILON)
+}
@@ -1,4 +1,4 @@
-use crate::components::{Player, Steering};
+use crate::components::{Player, Steering, SteeringMode};
 use crate::resources::{CurrentState, DebugSettings, History, Rewind};
 use amethyst::{
     core::timing::Time,
@@ -75,6 +75,19 @@ impl<'s> System<'s> for RewindSystem {
                     steering.pos = frame.player_position;
                     steering.destination = frame.player_position;
                 }
+                for (entity, pos) in frame.pushables {
+                    if let (Some(transform), Some(steering)) =
+                        (transforms.get_mut(entity), steerings.get_mut(entity))
+                    {
+                        let (centered_x, centered_y) = steering.to_centered_coords(pos);
+                        transform.set_translation_x(centered_x);
+                        transform.set_translation_y(centered_y);
+                        steering.pos = pos;
+                        steering.destination = pos;
+                        // A block that was falling starts its fall over from this position.
+                        steering.mode = SteeringMode::Grounded;
+                    }
+                }
             }
         }
     }
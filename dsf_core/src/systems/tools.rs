@@ -6,9 +6,11 @@ use amethyst::{
     input::{InputHandler, StringBindings},
 };
 
-use crate::components::{Block, EquippedTag, Player, Pos, Steering, Tool};
-use crate::levels::load_asset_from_world;
-use crate::resources::{SoundType, TileDefinition, TileMap, ToolType};
+use crate::components::{Block, Dynamite, EquippedTag, Player, Pos, Steering, Tool};
+use crate::levels::{create_tile_entity, load_asset_from_world, load_transform};
+use crate::resources::{
+    AssetType, Assets, DepthLayer, SoundType, TileDefinition, TileMap, ToolType,
+};
 use crate::systems::SoundEvent;
 use amethyst::core::ecs::shrev::EventChannel;
 use amethyst::core::Parent;
@@ -19,6 +21,9 @@ use amethyst::prelude::{Builder, WorldExt};
 const TOOL_WIDTH: f32 = 2.;
 const TOOL_HEIGHT: f32 = 2.;
 
+/// The tile definition that is used for ladders placed with a tool.
+pub const LADDER_TILE: &str = "Ladder";
+
 /// Checks if the player intersects any tools.
 /// If so, the tool will equipped by the player and will be removed from the game.
 #[derive(Copy, Clone, Default, Debug)]
@@ -30,6 +35,7 @@ impl<'s> System<'s> for PickupSystem {
         WriteStorage<'s, Player>,
         ReadStorage<'s, Steering>,
         ReadStorage<'s, Tool>,
+        ReadStorage<'s, EquippedTag>,
         ReadStorage<'s, Transform>,
         Read<'s, LazyUpdate>,
         Entities<'s>,
@@ -37,7 +43,16 @@ impl<'s> System<'s> for PickupSystem {
 
     fn run(
         &mut self,
-        (mut sound_channel, mut players, steerings, tools, transforms, lazy, entities): Self::SystemData,
+        (
+            mut sound_channel,
+            mut players,
+            steerings,
+            tools,
+            equipped_tags,
+            transforms,
+            lazy,
+            entities,
+        ): Self::SystemData,
     ) {
         let player = (&mut players, &entities, &steerings, &transforms)
             .join()
@@ -55,9 +70,9 @@ impl<'s> System<'s> for PickupSystem {
                 return;
             }
             // Find the first tool that intersects with the player:
-            let tool_opt = (&tools, &transforms, &entities)
+            let tool_opt = (&tools, &transforms, &entities, !&equipped_tags)
                 .join()
-                .find(|(_, transform, _)| {
+                .find(|(_, transform, _, _)| {
                     let key_x = transform.translation().x;
                     let key_y = transform.translation().y;
                     pos.x - dimens.x / 2. < key_x + TOOL_WIDTH / 3.
@@ -65,18 +80,19 @@ impl<'s> System<'s> for PickupSystem {
                         && pos.y - dimens.y / 2. < key_y + TOOL_HEIGHT / 3.
                         && pos.y + dimens.y / 2. > key_y - TOOL_HEIGHT / 3.
                 });
-            if let Some((tool, _, tool_entity)) = tool_opt {
+            if let Some((tool, _, tool_entity, _)) = tool_opt {
                 sound_channel.single_write(SoundEvent::new(SoundType::ToolPickup));
                 player.equipped = Some(tool.tool_type);
-                let (sprite, sprite_nr) = (tool.sprite, tool.sprite_nr);
+                let tool = tool.clone();
                 lazy.exec_mut(move |world| {
                     world
                         .delete_entity(tool_entity)
                         .expect("Tried to delete tool, but failed.");
-                    let render = load_asset_from_world(sprite, sprite_nr, world);
+                    let render = load_asset_from_world(tool.sprite.clone(), tool.sprite_nr, world);
                     world
                         .create_entity()
                         .with(EquippedTag)
+                        .with(tool)
                         .with(Transform::default())
                         .with(Parent {
                             entity: player_entity,
@@ -97,10 +113,16 @@ impl<'s> System<'s> for UseToolSystem {
         Write<'s, EventChannel<SoundEvent>>,
         WriteStorage<'s, Player>,
         ReadStorage<'s, Steering>,
-        ReadStorage<'s, EquippedTag>,
+        WriteStorage<'s, EquippedTag>,
+        WriteStorage<'s, Tool>,
         ReadStorage<'s, Block>,
+        WriteStorage<'s, Dynamite>,
+        WriteStorage<'s, Transform>,
+        WriteStorage<'s, Parent>,
         Read<'s, InputHandler<StringBindings>>,
         Write<'s, TileMap>,
+        Read<'s, Assets>,
+        Read<'s, LazyUpdate>,
         Entities<'s>,
     );
 
@@ -110,10 +132,16 @@ impl<'s> System<'s> for UseToolSystem {
             mut sound_channel,
             mut players,
             steerings,
-            equipped_tags,
+            mut equipped_tags,
+            mut tools,
             blocks,
+            mut dynamites,
+            mut transforms,
+            mut parents,
             input,
             mut tile_map,
+            assets,
+            lazy,
             entities,
         ): Self::SystemData,
     ) {
@@ -136,7 +164,70 @@ impl<'s> System<'s> for UseToolSystem {
                     }
                 }
                 Some(ToolType::BreakBlocksBelow(depth)) => Some(tiles_below(depth, steering)),
-                _ => None,
+                Some(ToolType::Dynamite { radius, fuse }) => {
+                    sound_channel.single_write(SoundEvent::new(SoundType::Mining));
+                    player.equipped = None;
+                    let pos = dynamite_position(steering);
+                    // The sprite of the equipped tool stays behind as the placed dynamite. It fills
+                    // the single tile that the dynamite explodes from.
+                    let equipped = (&entities, &equipped_tags, &tools)
+                        .join()
+                        .map(|(entity, _, tool)| {
+                            (
+                                entity,
+                                AssetType::Still(tool.sprite.clone(), tool.sprite_nr),
+                            )
+                        })
+                        .next();
+                    let dynamite = match equipped {
+                        Some((entity, asset)) => {
+                            let transform = load_transform(
+                                pos,
+                                DepthLayer::FloatingBlocks,
+                                Pos::new(1, 1),
+                                &asset,
+                                &assets,
+                            );
+                            transforms
+                                .insert(entity, transform)
+                                .expect("Failed to place dynamite.");
+                            entity
+                        }
+                        None => entities.create(),
+                    };
+                    equipped_tags.remove(dynamite);
+                    tools.remove(dynamite);
+                    parents.remove(dynamite);
+                    dynamites
+                        .insert(dynamite, Dynamite::new(pos, radius, fuse))
+                        .expect("Failed to place dynamite.");
+                    None
+                }
+                Some(ToolType::PlaceLadder { length, upwards }) => {
+                    let ladder = tile_map.tile_defs.get(LADDER_TILE).clone();
+                    let positions = ladder_positions(steering, length, upwards, ladder.dimens);
+                    if !positions.is_empty()
+                        && fits_in_tile_map(&positions, ladder.dimens, &tile_map)
+                    {
+                        sound_channel.single_write(SoundEvent::new(SoundType::LadderStep));
+                        player.equipped = None;
+                        for (_, entity) in (&equipped_tags, &entities).join() {
+                            entities
+                                .delete(entity)
+                                .expect("Failed to delete equipped tool sprite.");
+                        }
+                        for pos in &positions {
+                            place_ladder(&mut tile_map, *pos);
+                        }
+                        lazy.exec_mut(move |world| {
+                            for pos in positions {
+                                create_tile_entity(world, pos, &ladder).build();
+                            }
+                        });
+                    }
+                    None
+                }
+                None => None,
             };
             if let Some(targeted_blocks) = targeted_blocks {
                 let at_least_one_is_breakable =
@@ -145,26 +236,156 @@ impl<'s> System<'s> for UseToolSystem {
                 if at_least_one_is_breakable && none_are_unbreakable {
                     sound_channel.single_write(SoundEvent::new(SoundType::Mining));
                     player.equipped = None;
-                    for pos in &targeted_blocks {
-                        tile_map.remove_tile(*pos);
-                    }
                     for (_, entity) in (&equipped_tags, &entities).join() {
                         entities
                             .delete(entity)
                             .expect("Failed to delete equipped tool sprite.");
                     }
-                    for (block, entity) in (&blocks, &entities).join() {
-                        if targeted_blocks.contains(&block.pos) {
-                            entities.delete(entity).expect("Failed to delete block!");
-                        }
-                    }
+                    break_blocks(&targeted_blocks, &mut tile_map, &blocks, &entities);
                 }
             }
         }
     }
 }
 
-fn at_least_one_is_breakable(blocks: &[Pos], tile_map: &TileMap) -> bool {
+/// Burns down the fuses of placed dynamite, and makes the dynamite explode when its fuse has
+/// burnt down.
+#[derive(Copy, Clone, Default, Debug)]
+pub struct DynamiteSystem;
+
+impl<'s> System<'s> for DynamiteSystem {
+    type SystemData = (
+        Write<'s, EventChannel<SoundEvent>>,
+        WriteStorage<'s, Dynamite>,
+        ReadStorage<'s, Block>,
+        Write<'s, TileMap>,
+        Entities<'s>,
+    );
+
+    fn run(
+        &mut self,
+        (mut sound_channel, mut dynamites, blocks, mut tile_map, entities): Self::SystemData,
+    ) {
+        let mut exploding = vec![];
+        for (dynamite, entity) in (&mut dynamites, &entities).join() {
+            dynamite.fuse = dynamite.fuse.saturating_sub(1);
+            if dynamite.fuse == 0 {
+                exploding.push((entity, *dynamite));
+            }
+        }
+        // Explode in a fixed order, so that the outcome never depends on the order of storage.
+        exploding.sort_by_key(|(_, dynamite)| (dynamite.pos.x, dynamite.pos.y));
+        for (entity, dynamite) in exploding {
+            sound_channel.single_write(SoundEvent::new(SoundType::Mining));
+            let targets = explosion_targets(dynamite.pos, dynamite.radius, &tile_map);
+            break_blocks(&targets, &mut tile_map, &blocks, &entities);
+            entities
+                .delete(entity)
+                .expect("Failed to delete exploded dynamite.");
+        }
+    }
+}
+
+/// Removes the tiles at the given positions from the tile map and deletes the entities of the
+/// blocks at those positions.
+pub fn break_blocks(
+    targets: &[Pos],
+    tile_map: &mut TileMap,
+    blocks: &ReadStorage<'_, Block>,
+    entities: &Entities<'_>,
+) {
+    let anchors = targets
+        .iter()
+        .filter_map(|pos| tile_map.remove_tile(*pos))
+        .collect::<Vec<_>>();
+    for (block, entity) in (blocks, entities).join() {
+        if targets.contains(&block.pos) || anchors.contains(&block.pos) {
+            entities.delete(entity).expect("Failed to delete block!");
+        }
+    }
+}
+
+/// Returns the position where the entity places dynamite: at its feet, on the side it is facing.
+#[must_use]
+pub fn dynamite_position(steering: &Steering) -> Pos {
+    let facing_offset = if steering.facing.x.is_positive() {
+        steering.dimens.x - 1
+    } else {
+        0
+    };
+    Pos::new(steering.pos.x + facing_offset, steering.pos.y)
+}
+
+/// Returns the anchor positions of the segments of a ladder that the entity places, from the
+/// bottom up. The ladder is placed right in front of the entity, on the side it is facing.
+/// An upwards ladder starts at the entity's feet, a downwards ladder starts just below them.
+#[must_use]
+pub fn ladder_positions(
+    steering: &Steering,
+    length: u8,
+    upwards: bool,
+    ladder_dimens: Pos,
+) -> Vec<Pos> {
+    let x = if steering.facing.x.is_positive() {
+        steering.pos.x + steering.dimens.x
+    } else {
+        steering.pos.x - ladder_dimens.x
+    };
+    let bottom_y = if upwards {
+        steering.pos.y
+    } else {
+        steering.pos.y - i32::from(length) * ladder_dimens.y
+    };
+    (0..i32::from(length))
+        .map(|segment| Pos::new(x, bottom_y + segment * ladder_dimens.y))
+        .collect()
+}
+
+/// Puts a ladder segment into the tile map, anchored at the given position.
+pub fn place_ladder(tile_map: &mut TileMap, pos: Pos) {
+    let dimens = tile_map.tile_defs.get(LADDER_TILE).dimens;
+    tile_map.put_tile(pos, LADDER_TILE.to_string(), dimens);
+}
+
+/// Returns true iff tiles of the given dimensions can be placed at all given positions without
+/// overlapping any existing tile.
+#[must_use]
+pub fn fits_in_tile_map(positions: &[Pos], dimens: Pos, tile_map: &TileMap) -> bool {
+    positions.iter().all(|pos| {
+        (0..dimens.x)
+            .all(|x| (0..dimens.y).all(|y| tile_map.get_actual_pos(pos.append_xy(x, y)).is_none()))
+    })
+}
+
+/// Returns the positions of the tiles that are broken by an explosion centred on the given
+/// position. The explosion covers its centre and reaches `radius` tiles out in a straight line
+/// to the right, left, top and bottom. Every breakable tile it covers is broken. Each of the four
+/// arms ends at the first invulnerable tile in its way, which is left intact.
+#[must_use]
+pub fn explosion_targets(center: Pos, radius: u8, tile_map: &TileMap) -> Vec<Pos> {
+    let mut targets = vec![];
+    if tile_map
+        .get_tile(center)
+        .map_or(false, TileDefinition::is_breakable)
+    {
+        targets.push(center);
+    }
+    for (x, y) in &[(1, 0), (-1, 0), (0, 1), (0, -1)] {
+        for distance in 1..=i32::from(radius) {
+            let pos = center.append_xy(x * distance, y * distance);
+            match tile_map.get_tile(pos) {
+                Some(tile_def) if tile_def.is_breakable() => targets.push(pos),
+                Some(_) => break,
+                None => (),
+            }
+        }
+    }
+    targets
+}
+
+/// Returns true iff at least one of the given positions contains a breakable tile.
+#[must_use]
+pub fn at_least_one_is_breakable(blocks: &[Pos], tile_map: &TileMap) -> bool {
     blocks.iter().any(|pos| {
         tile_map
             .get_tile(*pos)
@@ -172,7 +393,10 @@ fn at_least_one_is_breakable(blocks: &[Pos], tile_map: &TileMap) -> bool {
     })
 }
 
-fn none_are_unbreakable(blocks: &[Pos], tile_map: &TileMap) -> bool {
+/// Returns true iff none of the given positions contain a tile that cannot be broken.
+/// Positions without any tile are fine.
+#[must_use]
+pub fn none_are_unbreakable(blocks: &[Pos], tile_map: &TileMap) -> bool {
     blocks.iter().all(|pos| {
         tile_map
             .get_tile(*pos)
@@ -180,7 +404,9 @@ fn none_are_unbreakable(blocks: &[Pos], tile_map: &TileMap) -> bool {
     })
 }
 
-fn tiles_to_side(depth: u8, steering: &Steering) -> Vec<Pos> {
+/// Returns the positions targeted by a tool that breaks blocks in front of the entity.
+#[must_use]
+pub fn tiles_to_side(depth: u8, steering: &Steering) -> Vec<Pos> {
     let facing_offset = if steering.facing.x.is_positive() {
         steering.dimens.x
     } else {
@@ -199,7 +425,9 @@ fn tiles_to_side(depth: u8, steering: &Steering) -> Vec<Pos> {
         .collect()
 }
 
-fn tiles_below(depth: u8, steering: &Steering) -> Vec<Pos> {
+/// Returns the positions targeted by a tool that breaks blocks below the entity.
+#[must_use]
+pub fn tiles_below(depth: u8, steering: &Steering) -> Vec<Pos> {
     let facing_offset = if steering.facing.x.is_positive() {
         steering.dimens.x - 1
     } else {
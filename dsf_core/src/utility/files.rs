@@ -22,6 +22,23 @@ pub fn get_levels_dir() -> PathBuf {
     create_if_missing(get_world_dir().join("levels/"))
 }
 
+/// Stamps are blueprints of reusable structures that can be pasted into any level in the editor.
+#[must_use]
+pub fn get_stamps_dir() -> PathBuf {
+    create_if_missing(get_world_dir().join("stamps/"))
+}
+
+/// The tile definitions that levels refer to by their keys.
+#[must_use]
+pub fn get_tile_definitions_file() -> PathBuf {
+    get_world_dir().join("tile_references.ron")
+}
+
+#[must_use]
+pub fn get_movement_config_file() -> PathBuf {
+    get_config_dir().join("movement.ron")
+}
+
 #[must_use]
 pub fn get_world_dir() -> PathBuf {
     get_assets_dir().join("world/")
@@ -1,3 +1,4 @@
+use crate::components::Pos;
 use crate::resources::{AnimType, SoundType, SpriteType, UiType};
 use serde::{Deserialize, Serialize};
 
@@ -7,22 +8,48 @@ use serde::{Deserialize, Serialize};
 #[serde(deny_unknown_fields)]
 pub struct LoadingConfig {
     pub uis: Vec<(UiType, String)>,
-    pub animations: Vec<(AnimType, String)>,
-    pub stills: Vec<(SpriteType, String, String)>,
+    pub animations: Vec<AnimationConfig>,
+    pub stills: Vec<StillConfig>,
     pub sound_effects: Vec<(SoundType, String)>,
     pub music_tracks: Vec<String>,
 }
 
+/// Registers a sprite sheet under an id. Tile definitions refer to the sheet by this id.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct StillConfig {
+    pub id: SpriteType,
+    pub texture: String,
+    pub sprite_sheet: String,
+    /// The size in pixels that a sprite on this sheet takes up when it fills a single tile.
+    pub dimens: Pos,
+}
+
+/// Registers an animation prefab under an id. Tile definitions refer to the animation by this id.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct AnimationConfig {
+    pub id: AnimType,
+    pub prefab: String,
+    /// The size in pixels that the animation takes up when it fills a single tile.
+    pub dimens: Pos,
+}
+
 impl Default for LoadingConfig {
     fn default() -> Self {
         LoadingConfig {
             uis: vec![],
-            animations: vec![(AnimType::NotFound, "prefab/anim_not_found.ron".to_string())],
-            stills: vec![(
-                SpriteType::NotFound,
-                "textures/not_found.png".to_string(),
-                "prefab/still_not_found.ron".to_string(),
-            )],
+            animations: vec![AnimationConfig {
+                id: AnimType::NOT_FOUND,
+                prefab: "prefab/anim_not_found.ron".to_string(),
+                dimens: Pos::new(128, 128),
+            }],
+            stills: vec![StillConfig {
+                id: SpriteType::NOT_FOUND,
+                texture: "textures/not_found.png".to_string(),
+                sprite_sheet: "prefab/still_not_found.ron".to_string(),
+                dimens: Pos::new(128, 128),
+            }],
             sound_effects: vec![],
             music_tracks: vec![],
         }
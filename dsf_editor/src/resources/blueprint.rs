@@ -1,15 +1,20 @@
-use crate::resources::{EditorStatus, LevelEdit};
+use crate::resources::{Clipboard, EditorStatus, LevelEdit, Selection};
 use dsf_core::components::Pos;
+use dsf_core::levels::ordered_map;
 use dsf_core::resources::Tile;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
 /// Contains a tile map. Is a blueprint for a structure of tiles inside a level.
 /// If you copy a selection in the level editor, that selection is stored as a Blueprint.
-/// Blueprints can be pasted. Blueprints can potentially be imported and exported from the editor.
-#[derive(Debug, Deserialize, Serialize, Default)]
+/// Blueprints can be pasted. Blueprints can be saved to the stamps directory, to be reused across
+/// levels.
+#[derive(Debug, Clone, Deserialize, Serialize, Default)]
+#[serde(default)]
+#[serde(deny_unknown_fields)]
 pub struct Blueprint {
     pub dimensions: Pos,
+    #[serde(serialize_with = "ordered_map")]
     pub tiles: HashMap<Pos, Tile>,
 }
 
@@ -52,6 +57,63 @@ impl Blueprint {
         blueprint
     }
 
+    /// Create a new instance of Blueprint that is a copy of the tiles inside the selection.
+    ///
+    /// Tiles that stick out of the selection are not copied, because they cannot be copied in
+    /// full. If `copy_air` is true, all empty positions within the selection are stored as air
+    /// blocks. When pasting with force-place enabled, those clear out existing tiles.
+    #[must_use]
+    pub fn from_selection(selection: &Selection, level_edit: &LevelEdit, copy_air: bool) -> Self {
+        let lower_bounds = selection.lower_bounds();
+        let selection_dimens = selection.dimens();
+        let mut blueprint = Blueprint::new(selection_dimens);
+        for x in 0..selection_dimens.x {
+            for y in 0..selection_dimens.y {
+                let relative_pos = Pos::new(x, y);
+                let pos = lower_bounds + relative_pos;
+                match level_edit.tile_map.tiles.get(&pos) {
+                    Some(Tile::TileDefKey(key)) => {
+                        let dimens = level_edit.get_tile_def(key).dimens;
+                        if x + dimens.x <= selection_dimens.x && y + dimens.y <= selection_dimens.y
+                        {
+                            blueprint.insert_tile(
+                                relative_pos,
+                                dimens,
+                                Tile::TileDefKey(key.clone()),
+                            );
+                        }
+                    }
+                    None if copy_air => {
+                        blueprint.tiles.insert(relative_pos, Tile::AirBlock);
+                    }
+                    // Dummies are copied along with their anchor.
+                    _ => (),
+                }
+            }
+        }
+        blueprint
+    }
+
+    /// Returns the blueprint that is placed when the user presses `place_blocks`, along with the
+    /// position in the level of its lower-left corner.
+    ///
+    /// When pasting, that is the blueprint on the clipboard, placed at the cursor. Otherwise, it is
+    /// the selection filled with the tile on the brush.
+    #[must_use]
+    pub fn for_placement(
+        status: &EditorStatus,
+        level_edit: &LevelEdit,
+        clipboard: &Clipboard,
+    ) -> (Self, Pos) {
+        match &clipboard.blueprint {
+            Some(blueprint) if status.pasting => (blueprint.clone(), status.selection.end),
+            _ => (
+                Blueprint::from_placing_tiles(status, level_edit),
+                status.selection.lower_bounds(),
+            ),
+        }
+    }
+
     fn insert_tile(&mut self, pos: Pos, dimens: Pos, tile: Tile) {
         self.tiles.insert(pos, tile);
         for x in pos.x..(pos.x + dimens.x) {
@@ -70,4 +132,149 @@ impl Blueprint {
         (pos.x..(pos.x + dimens.x))
             .any(|x| (pos.y..(pos.y + dimens.y)).any(|y| self.tiles.get(&Pos::new(x, y)).is_some()))
     }
+
+    /// Returns a copy of this blueprint, mirrored so that left becomes right and vice versa.
+    /// Mirroring always succeeds; this returns an `Option` to match the rotations.
+    #[must_use]
+    pub fn mirror_horizontally(&self) -> Option<Self> {
+        let width = self.dimensions.x;
+        self.transform(self.dimensions, |anchor, dimens| {
+            Pos::new(width - anchor.x - dimens.x, anchor.y)
+        })
+    }
+
+    /// Returns a copy of this blueprint, mirrored so that top becomes bottom and vice versa.
+    /// Mirroring always succeeds; this returns an `Option` to match the rotations.
+    #[must_use]
+    pub fn mirror_vertically(&self) -> Option<Self> {
+        let height = self.dimensions.y;
+        self.transform(self.dimensions, |anchor, dimens| {
+            Pos::new(anchor.x, height - anchor.y - dimens.y)
+        })
+    }
+
+    /// Returns a copy of this blueprint, rotated 90 degrees clockwise.
+    ///
+    /// Tiles themselves cannot be rotated, only moved. A tile that is not square is anchored at
+    /// the lower-left corner of where its rotated footprint would be. If it then no longer fits in
+    /// the blueprint or overlaps another tile, the blueprint cannot be rotated and None is
+    /// returned.
+    #[must_use]
+    pub fn rotate_clockwise(&self) -> Option<Self> {
+        let width = self.dimensions.x;
+        let dimensions = Pos::new(self.dimensions.y, self.dimensions.x);
+        self.transform(dimensions, |anchor, dimens| {
+            Pos::new(anchor.y, width - anchor.x - dimens.x)
+        })
+    }
+
+    /// Returns a copy of this blueprint, rotated 90 degrees counterclockwise.
+    /// See `rotate_clockwise` for how tiles that are not square are treated.
+    #[must_use]
+    pub fn rotate_counterclockwise(&self) -> Option<Self> {
+        let height = self.dimensions.y;
+        let dimensions = Pos::new(self.dimensions.y, self.dimensions.x);
+        self.transform(dimensions, |anchor, dimens| {
+            Pos::new(height - anchor.y - dimens.y, anchor.x)
+        })
+    }
+
+    /// Builds a new blueprint with the given dimensions, moving each tile to the anchor returned by
+    /// `new_anchor`. The closure receives the old anchor and the dimensions of the tile.
+    /// The dummies are recreated around the new anchors.
+    ///
+    /// Returns None if any tile would end up outside of the blueprint or on top of another tile.
+    fn transform(&self, dimensions: Pos, new_anchor: impl Fn(Pos, Pos) -> Pos) -> Option<Self> {
+        let mut footprints = HashMap::new();
+        for (pos, tile) in &self.tiles {
+            match tile {
+                Tile::Dummy(anchor) => {
+                    let dimens = footprints.entry(*anchor).or_insert_with(|| Pos::new(1, 1));
+                    dimens.x = dimens.x.max(pos.x - anchor.x + 1);
+                    dimens.y = dimens.y.max(pos.y - anchor.y + 1);
+                }
+                _ => {
+                    footprints.entry(*pos).or_insert_with(|| Pos::new(1, 1));
+                }
+            }
+        }
+        let mut blueprint = Blueprint::new(dimensions);
+        for (pos, tile) in &self.tiles {
+            if let Tile::Dummy(_) = tile {
+                continue;
+            }
+            let dimens = footprints[pos];
+            let anchor = new_anchor(*pos, dimens);
+            let fits = anchor.x >= 0
+                && anchor.y >= 0
+                && anchor.x + dimens.x <= dimensions.x
+                && anchor.y + dimens.y <= dimensions.y;
+            if !fits || blueprint.overlaps(anchor, dimens) {
+                return None;
+            }
+            blueprint.insert_tile(anchor, dimens, tile.clone());
+        }
+        Some(blueprint)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key(key: &str) -> Tile {
+        Tile::TileDefKey(key.to_string())
+    }
+
+    /// A blueprint with a single 2 by 1 tile anchored at the given position.
+    fn with_wide_tile(dimensions: Pos, anchor: Pos) -> Blueprint {
+        let mut blueprint = Blueprint::new(dimensions);
+        blueprint.insert_tile(anchor, Pos::new(2, 1), key("Wide"));
+        blueprint
+    }
+
+    #[test]
+    fn mirroring_keeps_wide_tiles_whole() {
+        let mut blueprint = with_wide_tile(Pos::new(3, 2), Pos::new(0, 1));
+        blueprint.insert_tile(Pos::new(2, 0), Pos::new(1, 1), key("Small"));
+        let mirrored = blueprint.mirror_horizontally().expect("Failed to mirror.");
+        assert_eq!(mirrored.dimensions, Pos::new(3, 2));
+        assert_eq!(mirrored.tiles.len(), 3);
+        assert_eq!(mirrored.tiles[&Pos::new(1, 1)], key("Wide"));
+        assert_eq!(mirrored.tiles[&Pos::new(2, 1)], Tile::Dummy(Pos::new(1, 1)));
+        assert_eq!(mirrored.tiles[&Pos::new(0, 0)], key("Small"));
+
+        let mirrored = blueprint.mirror_vertically().expect("Failed to mirror.");
+        assert_eq!(mirrored.tiles.len(), 3);
+        assert_eq!(mirrored.tiles[&Pos::new(0, 0)], key("Wide"));
+        assert_eq!(mirrored.tiles[&Pos::new(1, 0)], Tile::Dummy(Pos::new(0, 0)));
+        assert_eq!(mirrored.tiles[&Pos::new(2, 1)], key("Small"));
+    }
+
+    #[test]
+    fn rotating_moves_wide_tiles_that_fit() {
+        let blueprint = with_wide_tile(Pos::new(2, 2), Pos::new(0, 0));
+        let rotated = blueprint.rotate_clockwise().expect("Failed to rotate.");
+        assert_eq!(rotated.dimensions, Pos::new(2, 2));
+        assert_eq!(rotated.tiles.len(), 2);
+        assert_eq!(rotated.tiles[&Pos::new(0, 0)], key("Wide"));
+        assert_eq!(rotated.tiles[&Pos::new(1, 0)], Tile::Dummy(Pos::new(0, 0)));
+
+        let blueprint = with_wide_tile(Pos::new(2, 2), Pos::new(0, 1));
+        let rotated = blueprint
+            .rotate_counterclockwise()
+            .expect("Failed to rotate.");
+        assert_eq!(rotated.tiles.len(), 2);
+        assert_eq!(rotated.tiles[&Pos::new(0, 0)], key("Wide"));
+        assert_eq!(rotated.tiles[&Pos::new(1, 0)], Tile::Dummy(Pos::new(0, 0)));
+    }
+
+    #[test]
+    fn rotating_is_refused_if_a_wide_tile_no_longer_fits() {
+        let blueprint = with_wide_tile(Pos::new(2, 1), Pos::new(0, 0));
+        assert!(blueprint.rotate_clockwise().is_none());
+        assert!(blueprint.rotate_counterclockwise().is_none());
+        let blueprint = with_wide_tile(Pos::new(2, 2), Pos::new(0, 1));
+        assert!(blueprint.rotate_clockwise().is_none());
+    }
 }
@@ -0,0 +1,9 @@
+use crate::resources::Blueprint;
+
+/// Holds the most recently copied or cut selection, so it can be pasted elsewhere.
+/// Like the `EditorStatus`, this persists through play testing and is only reset when the
+/// `EditorState` starts.
+#[derive(Debug, Default)]
+pub struct Clipboard {
+    pub blueprint: Option<Blueprint>,
+}
@@ -0,0 +1,17 @@
+/// Keeps track of which level file is open in the editor.
+///
+/// While a level is being edited, it always lives in the auto save file, so it survives play
+/// testing and crashes. This records the name of the level file it was opened from, which is where
+/// it is written to when the level is saved.
+///
+/// Like the `EditorStatus`, this persists through play testing and is only reset when the
+/// `EditorState` starts.
+#[derive(Debug, Default)]
+pub struct EditorFile {
+    /// The name of the level file that is open, without the extension.
+    /// None if the level was never saved under a name.
+    pub name: Option<String>,
+    /// Set by the dialogs when the editor must close as soon as it becomes active again.
+    /// For example: after the user chose to exit without saving their changes.
+    pub exit_requested: bool,
+}
@@ -0,0 +1,203 @@
+use crate::resources::LevelEdit;
+use dsf_core::components::Pos;
+use dsf_core::levels::LevelMetadata;
+use dsf_core::resources::{TileProperties, WorldBounds};
+
+/// A single, reversible change to the `LevelEdit`.
+#[derive(Debug, Clone)]
+pub enum Change {
+    /// A tile with the given key was placed, anchored at the given position.
+    AddTile { pos: Pos, key: String },
+    /// The tile with the given key, anchored at the given position, was removed.
+    /// Any instance properties it had are kept, so they are restored when the change is undone.
+    RemoveTile {
+        pos: Pos,
+        key: String,
+        properties: Option<TileProperties>,
+    },
+    /// The world bounds were adjusted.
+    Bounds {
+        before: WorldBounds,
+        after: WorldBounds,
+    },
+    /// The instance properties of the tile anchored at the given position were edited.
+    Properties {
+        pos: Pos,
+        before: Option<TileProperties>,
+        after: Option<TileProperties>,
+    },
+    /// The title, author, etc. of the level were edited.
+    Metadata {
+        before: LevelMetadata,
+        after: LevelMetadata,
+    },
+}
+
+/// Everything that changed as the result of a single user action, such as placing the brush over
+/// the whole selection or adjusting a border of the level. Is undone and redone as a whole.
+#[derive(Debug, Clone, Default)]
+pub struct Edit {
+    /// The changes, in the order in which they were applied.
+    changes: Vec<Change>,
+}
+
+impl Edit {
+    pub fn push(&mut self, change: Change) {
+        self.changes.push(change);
+    }
+
+    /// Appends all changes of the other edit to this one.
+    pub fn extend(&mut self, other: Edit) {
+        self.changes.extend(other.changes);
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+}
+
+/// The undo and redo stacks of the editor.
+/// Like the `EditorStatus`, this persists through play testing and is only reset when the
+/// `EditorState` starts.
+#[derive(Debug, Default)]
+pub struct EditHistory {
+    undo_stack: Vec<Edit>,
+    redo_stack: Vec<Edit>,
+    /// True if the level was edited since it was last opened or saved.
+    unsaved_changes: bool,
+}
+
+impl EditHistory {
+    /// Records an edit that was just applied to the level. Empty edits are ignored.
+    /// Recording a new edit discards everything that could have been redone.
+    pub fn record(&mut self, edit: Edit) {
+        if !edit.is_empty() {
+            self.undo_stack.push(edit);
+            self.redo_stack.clear();
+            self.unsaved_changes = true;
+        }
+    }
+
+    /// Reverts the most recent edit. Returns false if there was nothing to undo.
+    pub fn undo(&mut self, level_edit: &mut LevelEdit) -> bool {
+        if let Some(edit) = self.undo_stack.pop() {
+            edit.changes
+                .iter()
+                .rev()
+                .for_each(|change| level_edit.revert(change));
+            self.redo_stack.push(edit);
+            self.unsaved_changes = true;
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Re-applies the most recently undone edit. Returns false if there was nothing to redo.
+    pub fn redo(&mut self, level_edit: &mut LevelEdit) -> bool {
+        if let Some(edit) = self.redo_stack.pop() {
+            edit.changes
+                .iter()
+                .for_each(|change| level_edit.apply(change));
+            self.undo_stack.push(edit);
+            self.unsaved_changes = true;
+            true
+        } else {
+            false
+        }
+    }
+
+    /// True if the level was edited since it was last opened or saved.
+    #[must_use]
+    pub fn has_unsaved_changes(&self) -> bool {
+        self.unsaved_changes
+    }
+
+    /// Call this after the level was saved.
+    pub fn mark_saved(&mut self) {
+        self.unsaved_changes = false;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use dsf_core::levels::LevelSave;
+    use dsf_core::resources::TileDefinitions;
+
+    fn level_edit() -> LevelEdit {
+        LevelEdit::new(LevelSave::default(), TileDefinitions::default())
+    }
+
+    /// Gives the level the given title and records the edit.
+    fn rename(level_edit: &mut LevelEdit, history: &mut EditHistory, title: &str) {
+        let metadata = LevelMetadata {
+            title: title.to_string(),
+            ..level_edit.metadata.clone()
+        };
+        history.record(level_edit.set_metadata(metadata));
+    }
+
+    #[test]
+    fn undo_and_redo_happen_in_reverse_order() {
+        let (mut level_edit, mut history) = (level_edit(), EditHistory::default());
+        rename(&mut level_edit, &mut history, "First");
+        rename(&mut level_edit, &mut history, "Second");
+        assert!(history.undo(&mut level_edit));
+        assert_eq!(level_edit.metadata.title, "First");
+        assert!(history.undo(&mut level_edit));
+        assert_eq!(level_edit.metadata.title, "");
+        assert!(!history.undo(&mut level_edit));
+        assert!(history.redo(&mut level_edit));
+        assert_eq!(level_edit.metadata.title, "First");
+        assert!(history.redo(&mut level_edit));
+        assert_eq!(level_edit.metadata.title, "Second");
+        assert!(!history.redo(&mut level_edit));
+    }
+
+    #[test]
+    fn edit_is_undone_as_a_whole() {
+        let (mut level_edit, mut history) = (level_edit(), EditHistory::default());
+        let before = *level_edit.bounds();
+        let mut edit = level_edit.adjust_bounds(before.pos, 1, 0);
+        edit.extend(level_edit.adjust_bounds(before.pos, 0, 2));
+        history.record(edit);
+        assert_ne!(*level_edit.bounds(), before);
+        assert!(history.undo(&mut level_edit));
+        assert_eq!(*level_edit.bounds(), before);
+    }
+
+    #[test]
+    fn new_edit_clears_redo() {
+        let (mut level_edit, mut history) = (level_edit(), EditHistory::default());
+        rename(&mut level_edit, &mut history, "First");
+        assert!(history.undo(&mut level_edit));
+        rename(&mut level_edit, &mut history, "Other");
+        assert!(!history.redo(&mut level_edit));
+        assert_eq!(level_edit.metadata.title, "Other");
+    }
+
+    #[test]
+    fn empty_edit_is_not_recorded() {
+        let (mut level_edit, mut history) = (level_edit(), EditHistory::default());
+        rename(&mut level_edit, &mut history, "");
+        assert!(!history.has_unsaved_changes());
+        assert!(!history.undo(&mut level_edit));
+    }
+
+    #[test]
+    fn tracks_unsaved_changes() {
+        let (mut level_edit, mut history) = (level_edit(), EditHistory::default());
+        assert!(!history.has_unsaved_changes());
+        rename(&mut level_edit, &mut history, "First");
+        assert!(history.has_unsaved_changes());
+        history.mark_saved();
+        assert!(!history.has_unsaved_changes());
+        assert!(history.undo(&mut level_edit));
+        assert!(history.has_unsaved_changes());
+        history.mark_saved();
+        assert!(history.redo(&mut level_edit));
+        assert!(history.has_unsaved_changes());
+    }
+}
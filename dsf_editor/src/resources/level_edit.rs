@@ -1,12 +1,17 @@
+use crate::resources::{Change, Edit};
 use dsf_core::components::Pos;
-use dsf_core::levels::LevelSave;
-use dsf_core::resources::{Tile, TileDefinition, TileDefinitions, TileMap, WorldBounds};
+use dsf_core::levels::{LevelMetadata, LevelSave, CURRENT_LEVEL_VERSION};
+use dsf_core::resources::{
+    Tile, TileDefinition, TileDefinitions, TileMap, TileProperties, WorldBounds,
+};
 use std::collections::{HashMap, HashSet};
 
 /// The representation of a level in the level editor.
 #[derive(Debug, Default, Clone)]
 pub struct LevelEdit {
     pub tile_map: TileMap,
+    /// The title, author, etc. of the level. Edited through the `LevelInfoState`.
+    pub metadata: LevelMetadata,
     /// A list of tile positions that are marked are dirty and must be redrawn.
     /// Whenever you add, update or remove a tile in the editor, you must mark it as dirty.
     pub dirty: HashSet<Pos>,
@@ -26,8 +31,11 @@ impl From<LevelEdit> for LevelSave {
             }
         });
         LevelSave {
+            version: CURRENT_LEVEL_VERSION,
+            metadata: item.metadata,
             world_bounds: item.tile_map.world_bounds,
             tiles: map,
+            properties: item.tile_map.properties().clone(),
         }
     }
 }
@@ -37,10 +45,20 @@ impl LevelEdit {
         let initial_dirty = level_save.tiles.keys().copied().collect::<HashSet<Pos>>();
         LevelEdit {
             tile_map: TileMap::for_editing(&level_save, tile_defs),
+            metadata: level_save.metadata,
             dirty: initial_dirty,
         }
     }
 
+    /// Replaces the level and its tile definitions, for example because they were changed on disk.
+    /// Every position that held a tile before or holds one now is marked dirty, so that all
+    /// tiles are redrawn.
+    pub(crate) fn reload(&mut self, level_save: LevelSave, tile_defs: TileDefinitions) {
+        let old_positions = self.tile_map.tiles.keys().copied().collect::<Vec<_>>();
+        *self = LevelEdit::new(level_save, tile_defs);
+        self.dirty.extend(old_positions);
+    }
+
     /// Empty the set of dirty positions and collect them into a new collection.
     ///
     /// The process of re-collecting them before draining them (or otherwise iterating over them)
@@ -51,20 +69,134 @@ impl LevelEdit {
     }
 
     /// Attempt to place the given tile at the given position.
-    pub(crate) fn place_tile(&mut self, force_place: bool, pos: Pos, tile: Option<Tile>) {
+    /// Returns the changes that were made, so they can be recorded in the `EditHistory`.
+    pub(crate) fn place_tile(&mut self, force_place: bool, pos: Pos, tile: Option<Tile>) -> Edit {
         let mut dry_run = self.check_place_tile(force_place, pos, tile);
+        let mut edit = Edit::default();
         dry_run.to_be_removed.iter().for_each(|delete_pos| {
-            if let Some(removed_pos) = self.tile_map.remove_tile(*delete_pos) {
-                self.dirty.insert(removed_pos);
+            if let Some(change) = self.remove_tile(*delete_pos) {
+                edit.push(change);
             }
         });
         dry_run
             .to_be_added
             .drain(..)
             .for_each(|(pos, key, dimensions)| {
-                self.tile_map.put_tile(pos, key, dimensions);
+                self.tile_map.put_tile(pos, key.clone(), dimensions);
                 self.dirty.insert(pos);
+                edit.push(Change::AddTile { pos, key });
+            });
+        edit
+    }
+
+    /// Adjusts the borders of the level. See `WorldBounds::adjust_x` and `WorldBounds::adjust_y`.
+    /// Returns the change that was made, so it can be recorded in the `EditHistory`.
+    pub(crate) fn adjust_bounds(&mut self, from: Pos, delta_x: i32, delta_y: i32) -> Edit {
+        let before = *self.bounds();
+        self.bounds_mut().adjust_x(from.x, delta_x);
+        self.bounds_mut().adjust_y(from.y, delta_y);
+        let after = *self.bounds();
+        let mut edit = Edit::default();
+        if before.pos != after.pos || before.dimens != after.dimens {
+            edit.push(Change::Bounds { before, after });
+        }
+        edit
+    }
+
+    /// Replaces the metadata of the level.
+    /// Returns the change that was made, so it can be recorded in the `EditHistory`.
+    pub(crate) fn set_metadata(&mut self, metadata: LevelMetadata) -> Edit {
+        let mut edit = Edit::default();
+        if metadata != self.metadata {
+            let before = std::mem::replace(&mut self.metadata, metadata.clone());
+            edit.push(Change::Metadata {
+                before,
+                after: metadata,
             });
+        }
+        edit
+    }
+
+    /// Replaces the instance properties of the tile anchored at the given position. Empty
+    /// properties are removed. Returns the change that was made, so it can be recorded in the
+    /// `EditHistory`.
+    pub(crate) fn set_properties(&mut self, anchor: Pos, properties: TileProperties) -> Edit {
+        let mut edit = Edit::default();
+        let before = self.tile_map.properties().get(&anchor).cloned();
+        let after = Some(properties).filter(|properties| !properties.is_empty());
+        if before != after {
+            self.put_properties(anchor, after.clone());
+            edit.push(Change::Properties {
+                pos: anchor,
+                before,
+                after,
+            });
+        }
+        edit
+    }
+
+    /// Applies the change to the level. Used to redo changes.
+    pub(crate) fn apply(&mut self, change: &Change) {
+        match change {
+            Change::AddTile { pos, key } => self.put_tile(*pos, key),
+            Change::RemoveTile { pos, .. } => {
+                let _ = self.remove_tile(*pos);
+            }
+            Change::Bounds { after, .. } => *self.bounds_mut() = *after,
+            Change::Properties { pos, after, .. } => self.put_properties(*pos, after.clone()),
+            Change::Metadata { after, .. } => self.metadata = after.clone(),
+        }
+    }
+
+    /// Applies the opposite of the change to the level. Used to undo changes.
+    pub(crate) fn revert(&mut self, change: &Change) {
+        match change {
+            Change::AddTile { pos, .. } => {
+                let _ = self.remove_tile(*pos);
+            }
+            Change::RemoveTile {
+                pos,
+                key,
+                properties,
+            } => {
+                self.put_tile(*pos, key);
+                self.tile_map.set_properties(*pos, properties.clone());
+            }
+            Change::Bounds { before, .. } => *self.bounds_mut() = *before,
+            Change::Properties { pos, before, .. } => self.put_properties(*pos, before.clone()),
+            Change::Metadata { before, .. } => self.metadata = before.clone(),
+        }
+    }
+
+    /// Removes the tile covering the given position and marks it as dirty.
+    fn remove_tile(&mut self, pos: Pos) -> Option<Change> {
+        let anchor = self.tile_map.get_actual_pos(pos)?;
+        let key = match self.tile_map.tiles.get(&anchor) {
+            Some(Tile::TileDefKey(key)) => key.clone(),
+            _ => unreachable!("The actual position always contains a TileDefKey."),
+        };
+        let properties = self.tile_map.properties().get(&anchor).cloned();
+        self.tile_map.remove_tile(anchor);
+        self.dirty.insert(anchor);
+        Some(Change::RemoveTile {
+            pos: anchor,
+            key,
+            properties,
+        })
+    }
+
+    /// Replaces the instance properties of the tile anchored at the given position and marks it as
+    /// dirty, because the properties may change how the tile looks.
+    fn put_properties(&mut self, anchor: Pos, properties: Option<TileProperties>) {
+        self.tile_map.set_properties(anchor, properties);
+        self.dirty.insert(anchor);
+    }
+
+    /// Places the tile with the given key and marks it as dirty.
+    fn put_tile(&mut self, pos: Pos, key: &str) {
+        let dimensions = self.get_tile_def(key).dimens;
+        self.tile_map.put_tile(pos, key.to_string(), dimensions);
+        self.dirty.insert(pos);
     }
 
     /// Does a dry-run to check what would happen if we'd place the given tile right now.
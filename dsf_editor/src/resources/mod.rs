@@ -1,13 +1,19 @@
 mod blueprint;
+mod clipboard;
 mod config;
 mod debug_lines;
+mod editor_file;
+mod history;
 mod level_edit;
 mod status;
 mod tile_edit;
 
 pub use self::blueprint::*;
+pub use self::clipboard::*;
 pub use self::config::*;
 pub use self::debug_lines::*;
+pub use self::editor_file::*;
+pub use self::history::*;
 pub use self::level_edit::*;
 pub use self::status::*;
 pub use self::tile_edit::*;
@@ -7,13 +7,21 @@ pub struct Brush {
 }
 
 impl Brush {
+    /// Fills the palette with the keys of the tile definitions. The selected key stays selected if
+    /// it is still in the palette, otherwise the brush is emptied.
     pub fn set_palette(&mut self, defs: &TileDefinitions) {
+        let selected = self.palette.get(self.palette_index).cloned().flatten();
         self.palette.clear();
         self.palette.push(None);
         defs.map.keys().for_each(|key| {
             self.palette.push(Some(key.clone()));
         });
         self.palette.sort();
+        self.palette_index = self
+            .palette
+            .iter()
+            .position(|key| *key == selected)
+            .unwrap_or(0);
     }
     pub fn select_previous(&mut self) -> Option<String> {
         self.select(-1)
@@ -1,4 +1,4 @@
-use crate::resources::{Brush, Selection};
+use crate::resources::{Brush, Selection, StampPalette};
 
 /// Contains some transient data related to the status of the editor.
 /// Holds things like the position of the cursor.
@@ -9,6 +9,8 @@ pub struct EditorStatus {
     /// Contains information on which tile is currently selected to be placed. Also contains the
     /// palette: all possible tiles that the editor could use.
     pub brush: Brush,
+    /// The stamp library. Contains all stamps that can be selected to be pasted.
+    pub stamps: StampPalette,
     /// The area that is currently selected.
     pub selection: Selection,
     /// If true, air will be included when copying a selection. In combination with the
@@ -18,15 +20,20 @@ pub struct EditorStatus {
     /// If false, existing tiles will never be removed when placing tiles or pasting blueprints.
     ///     That means that it could happen that only part of the tiles are actually placed.
     pub force_place: bool,
+    /// If true, placing tiles pastes the clipboard at the cursor, instead of filling the selection
+    /// with the tile on the brush. Is switched off again as soon as a different brush is selected.
+    pub pasting: bool,
 }
 
 impl Default for EditorStatus {
     fn default() -> Self {
         EditorStatus {
             brush: Brush::default(),
+            stamps: StampPalette::default(),
             selection: Selection::default(),
             copy_air: true,
             force_place: true,
+            pasting: false,
         }
     }
 }
@@ -1,7 +1,9 @@
 mod brush;
 mod editor_status;
 mod selection;
+mod stamps;
 
 pub use self::brush::*;
 pub use self::editor_status::*;
 pub use self::selection::*;
+pub use self::stamps::*;
@@ -0,0 +1,57 @@
+use crate::resources::Blueprint;
+
+/// The stamp library: all blueprints stored in the stamps directory.
+/// Selecting a stamp puts it on the clipboard, so it can be pasted like any copied selection.
+#[derive(Debug, Default)]
+pub struct StampPalette {
+    /// The stamps, sorted by name.
+    stamps: Vec<(String, Blueprint)>,
+    /// The index of the stamp that was selected last. None if no stamp was selected yet.
+    palette_index: Option<usize>,
+}
+
+impl StampPalette {
+    pub fn set_stamps(&mut self, mut stamps: Vec<(String, Blueprint)>) {
+        stamps.sort_by(|(a, _), (b, _)| a.cmp(b));
+        self.stamps = stamps;
+        self.palette_index = None;
+    }
+
+    /// Adds a stamp to the palette, replacing any existing stamp with the same name.
+    pub fn add_stamp(&mut self, name: String, blueprint: Blueprint) {
+        self.stamps.retain(|(existing, _)| *existing != name);
+        self.stamps.push((name, blueprint));
+        self.stamps.sort_by(|(a, _), (b, _)| a.cmp(b));
+        self.palette_index = None;
+    }
+
+    #[must_use]
+    pub fn contains(&self, name: &str) -> bool {
+        self.stamps.iter().any(|(existing, _)| existing == name)
+    }
+
+    pub fn select_previous(&mut self) -> Option<&(String, Blueprint)> {
+        self.select(-1)
+    }
+
+    pub fn select_next(&mut self) -> Option<&(String, Blueprint)> {
+        self.select(1)
+    }
+
+    fn select(&mut self, offset: i32) -> Option<&(String, Blueprint)> {
+        if self.stamps.is_empty() {
+            info!("There are no stamps to select.");
+            return None;
+        }
+        let len = self.stamps.len() as i32;
+        let index = match self.palette_index {
+            Some(index) => (index as i32 + offset).rem_euclid(len),
+            None if offset < 0 => len - 1,
+            None => 0,
+        } as usize;
+        self.palette_index = Some(index);
+        let stamp = self.stamps.get(index);
+        info!("Selected stamp: {:?}", stamp.map(|(name, _)| name));
+        stamp
+    }
+}
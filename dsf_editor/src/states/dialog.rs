@@ -0,0 +1,52 @@
+use amethyst::core::ecs::{Entity, ReadStorage, WriteStorage};
+use amethyst::input::{InputEvent, VirtualKeyCode};
+use amethyst::prelude::World;
+use amethyst::ui::{UiFinder, UiText};
+use amethyst::StateEvent;
+
+/// Id of the label that holds the title of an editor dialog.
+pub const TITLE_LABEL_ID: &str = "title";
+/// Id of the label that holds the body of an editor dialog.
+pub const MESSAGE_LABEL_ID: &str = "message";
+
+/// Looks up a ui element by its id.
+/// Make sure the world was updated after the ui was added, otherwise the element won't exist yet.
+pub fn find_ui(world: &mut World, id: &str) -> Option<Entity> {
+    world.exec(|ui_finder: UiFinder<'_>| ui_finder.find(id))
+}
+
+/// Replaces the text of the given label. Does nothing if the label does not exist.
+pub fn set_text(world: &mut World, label: Option<Entity>, text: &str) {
+    if let Some(label) = label {
+        world.exec(|mut ui_texts: WriteStorage<'_, UiText>| {
+            if let Some(ui_text) = ui_texts.get_mut(label) {
+                ui_text.text = text.to_string();
+            }
+        });
+    }
+}
+
+/// Returns true if the user confirms the dialog: they released the Enter key.
+/// Dialogs act on the release rather than the press of the key, so the editor does not see Enter
+/// being held down when it resumes.
+#[must_use]
+pub fn is_confirm_released(event: &StateEvent) -> bool {
+    matches!(
+        event,
+        StateEvent::Input(InputEvent::KeyReleased {
+            key_code: VirtualKeyCode::Return,
+            scancode: _,
+        })
+    )
+}
+
+/// Returns the text of the given label, or an empty string if the label does not exist.
+pub fn get_text(world: &mut World, label: Option<Entity>) -> String {
+    label
+        .and_then(|label| {
+            world.exec(|ui_texts: ReadStorage<'_, UiText>| {
+                ui_texts.get(label).map(|ui_text| ui_text.text.clone())
+            })
+        })
+        .unwrap_or_default()
+}
@@ -13,13 +13,21 @@ use amethyst::{
 };
 use dsf_precompile::AnimationId;
 
-use crate::resources::{setup_debug_lines, EditorStatus, LevelEdit};
-use crate::states::file_actions::{auto_save, auto_save_file, load_auto_save};
+use crate::resources::{
+    setup_debug_lines, Clipboard, EditHistory, EditorFile, EditorStatus, LevelEdit,
+};
+use crate::states::file_actions::{
+    auto_save, auto_save_file, current_level, load_auto_save, load_stamps, opened_level_file,
+    reload_opened_level, save,
+};
+use crate::states::{
+    LevelBrowserState, LevelInfoState, SaveAsState, TilePropertiesState, UnsavedChangesState,
+};
 use crate::systems;
 
 use amethyst::core::ecs::shrev::EventChannel;
 use amethyst::core::ecs::{Dispatcher, DispatcherBuilder, Read, Write};
-use amethyst::input::StringBindings;
+use amethyst::input::{InputHandler, StringBindings};
 
 use crate::components::{Cursor, SelectionTag};
 use crate::systems::RefreshPreviewsEvent;
@@ -27,11 +35,12 @@ use amethyst::core::Transform;
 use amethyst::renderer::Transparent;
 use dsf_core::components::Pos;
 use dsf_core::entities::create_camera;
-use dsf_core::levels::{add_background, attach_graphics, load_tile_definitions};
+use dsf_core::levels::{add_background, attach_graphics, load_tile_definitions, LevelSave};
 use dsf_core::resources::{
-    AssetType, Assets, DepthLayer, EventReaders, SpriteType, UiHandles, UiType,
+    AssetType, Assets, DepthLayer, EventReaders, FileWatcher, SpriteType, UiHandles, UiType,
 };
-use dsf_core::states::{window_event_handler, PlayState};
+use dsf_core::states::{reload_movement_config, window_event_handler, PlayState};
+use dsf_core::utility::files::{get_movement_config_file, get_tile_definitions_file};
 
 pub struct EditorState {
     /// Whether this state is currently on top of the stack.
@@ -45,6 +54,8 @@ impl<'a, 'b> EditorState {
             is_active: false,
             dispatcher: DispatcherBuilder::new()
                 .with(systems::PlaceTilesSystem, "place_tile_system", &[])
+                .with(systems::UndoRedoSystem, "undo_redo_system", &[])
+                .with(systems::ClipboardSystem, "clipboard_system", &[])
                 .with_barrier()
                 .with(
                     systems::EditorUiUpdateSystem,
@@ -88,13 +99,15 @@ impl<'a, 'b> EditorState {
         setup_debug_lines(world);
         create_camera(world);
         let tile_defs = load_tile_definitions().expect("Tile definitions failed to load!");
-        world
-            .write_resource::<EditorStatus>()
-            .brush
-            .set_palette(&tile_defs);
+        {
+            let mut status = world.write_resource::<EditorStatus>();
+            status.brush.set_palette(&tile_defs);
+            status.stamps.set_stamps(load_stamps());
+        }
         let level_edit = LevelEdit::new(load_auto_save(), tile_defs);
         add_background(world, &level_edit.tile_map.world_bounds);
         world.insert(level_edit);
+        watch_files(world);
     }
 }
 
@@ -108,6 +121,9 @@ impl SimpleState for EditorState {
         data.world.insert(readers);
         self.dispatcher.setup(data.world);
         data.world.insert(EditorStatus::default());
+        data.world.insert(EditHistory::default());
+        data.world.insert(Clipboard::default());
+        data.world.insert(EditorFile::default());
         self.setup(data.world);
     }
 
@@ -141,7 +157,15 @@ impl SimpleState for EditorState {
             StateEvent::Window(event) => {
                 if is_close_requested(&event) || is_key_down(&event, VirtualKeyCode::Escape) {
                     auto_save(data.world).expect("Failed to auto-save level!");
-                    Trans::Pop
+                    if data
+                        .world
+                        .read_resource::<EditHistory>()
+                        .has_unsaved_changes()
+                    {
+                        Trans::Push(Box::new(UnsavedChangesState::default()))
+                    } else {
+                        Trans::Pop
+                    }
                 } else {
                     Trans::None
                 }
@@ -156,12 +180,35 @@ impl SimpleState for EditorState {
                     auto_save(data.world).expect("Failed to auto-save level!");
                     Trans::Push(Box::new(PlayState::new(auto_save_file())))
                 }
+                InputEvent::ActionPressed(action) if action == "save_stamp" => {
+                    save_clipboard_as_stamp(data.world)
+                }
+                InputEvent::ActionPressed(action) if action == "save" => save_level(data.world),
+                InputEvent::ActionPressed(action) if action == "open" => {
+                    auto_save(data.world).expect("Failed to auto-save level!");
+                    Trans::Push(Box::new(LevelBrowserState::default()))
+                }
+                InputEvent::ActionPressed(action) if action == "edit_level_info" => {
+                    auto_save(data.world).expect("Failed to auto-save level!");
+                    Trans::Push(Box::new(LevelInfoState::default()))
+                }
+                InputEvent::ActionPressed(action) if action == "edit_tile_properties" => {
+                    edit_tile_properties(data.world)
+                }
                 _ => Trans::None,
             },
         }
     }
 
     fn update(&mut self, data: &mut StateData<'_, GameData<'_, '_>>) -> SimpleTrans {
+        let exit_requested = {
+            let mut editor_file = data.world.write_resource::<EditorFile>();
+            std::mem::replace(&mut editor_file.exit_requested, false)
+        };
+        if exit_requested {
+            return Trans::Pop;
+        }
+        reload_changed_files(data.world);
         self.dispatcher.dispatch(data.world);
         // Execute a pass similar to a system
         data.world.exec(
@@ -207,12 +254,119 @@ impl SimpleState for EditorState {
     }
 }
 
+/// Watch the files that the level in the editor is built from, so it can be rebuilt when they
+/// change. Must be called again when a different level is opened.
+fn watch_files(world: &mut World) {
+    let mut files = vec![get_tile_definitions_file(), get_movement_config_file()];
+    files.extend(opened_level_file(world));
+    world.write_resource::<FileWatcher>().watch(files);
+}
+
+fn reload_changed_files(world: &mut World) {
+    let changed = world.write_resource::<FileWatcher>().changed_files();
+    if changed.contains(&get_movement_config_file()) {
+        reload_movement_config(world);
+    }
+    let tile_defs_changed = changed.contains(&get_tile_definitions_file());
+    let level_changed = opened_level_file(world).map_or(false, |file| changed.contains(&file));
+    let reloaded_level = if level_changed {
+        reload_opened_level(world)
+    } else {
+        None
+    };
+    if tile_defs_changed || reloaded_level.is_some() {
+        rebuild_level(world, reloaded_level);
+    }
+}
+
+/// Rebuilds the level in the editor with the tile definitions as they are on disk now, replacing
+/// the level itself if a new version is given. The cursor, the selection and the camera are kept.
+/// If the tile definitions cannot be loaded, for example because they are only half edited, the
+/// current ones are kept.
+fn rebuild_level(world: &mut World, level: Option<LevelSave>) {
+    let tile_defs = load_tile_definitions().unwrap_or_else(|error| {
+        error!(
+            "Failed to reload the tile definitions, keeping the current ones: {}",
+            error
+        );
+        world
+            .read_resource::<LevelEdit>()
+            .tile_map
+            .tile_defs
+            .clone()
+    });
+    let level = level.unwrap_or_else(|| current_level(world));
+    world
+        .write_resource::<EditorStatus>()
+        .brush
+        .set_palette(&tile_defs);
+    world.write_resource::<LevelEdit>().reload(level, tile_defs);
+    world.exec(
+        |mut channel: Write<'_, EventChannel<RefreshPreviewsEvent>>| {
+            channel.single_write(RefreshPreviewsEvent);
+        },
+    );
+    info!("Rebuilt the level with the files as they are on disk now.");
+}
+
+/// Saves the level under the name of the file it was opened from.
+/// Opens the save as dialog instead if the level was never saved under a name, or if shift is held
+/// (Ctrl+Shift+S).
+fn save_level(world: &mut World) -> SimpleTrans {
+    let shift = world
+        .read_resource::<InputHandler<StringBindings>>()
+        .action_is_down("shift")
+        .unwrap_or(false);
+    let name = world.read_resource::<EditorFile>().name.clone();
+    match name {
+        Some(name) if !shift => {
+            if let Err(error) = save(&name, world) {
+                error!("Failed to save level {:?}: {}", name, error);
+            }
+            Trans::None
+        }
+        _ => {
+            auto_save(world).expect("Failed to auto-save level!");
+            Trans::Push(Box::new(SaveAsState::new(false)))
+        }
+    }
+}
+
+/// Opens the dialog to edit the instance properties of the tile under the cursor, if any.
+fn edit_tile_properties(world: &mut World) -> SimpleTrans {
+    let cursor_pos = world.read_resource::<EditorStatus>().selection.end;
+    let anchor = world
+        .read_resource::<LevelEdit>()
+        .tile_map
+        .get_actual_pos(cursor_pos);
+    if let Some(anchor) = anchor {
+        auto_save(world).expect("Failed to auto-save level!");
+        Trans::Push(Box::new(TilePropertiesState::new(anchor)))
+    } else {
+        info!("There is no tile under the cursor to edit the properties of.");
+        Trans::None
+    }
+}
+
+/// Opens the dialog to store the blueprint on the clipboard in the stamp library, under a name of
+/// the user's choosing.
+fn save_clipboard_as_stamp(world: &mut World) -> SimpleTrans {
+    let blueprint = world.read_resource::<Clipboard>().blueprint.clone();
+    if let Some(blueprint) = blueprint {
+        auto_save(world).expect("Failed to auto-save level!");
+        Trans::Push(Box::new(SaveAsState::for_stamp(blueprint)))
+    } else {
+        info!("Nothing to save as a stamp, the clipboard is empty.");
+        Trans::None
+    }
+}
+
 /// TODO: Temporary function, clean up later.
 /// Adds a selection and a cursor entity.
 fn init_cursor(world: &mut World) {
     let sprite_handle = world
         .read_resource::<Assets>()
-        .get_still(SpriteType::Selection);
+        .get_still(&SpriteType::SELECTION);
     let mut selection_transform = Transform::default();
     selection_transform.set_translation_z((&DepthLayer::Selection).z());
     world
@@ -235,7 +389,7 @@ fn init_cursor(world: &mut World) {
     attach_graphics(
         world,
         cursor_entity,
-        &AssetType::Still(SpriteType::Selection, 0),
+        &AssetType::Still(SpriteType::SELECTION, 0),
         Pos::new(1, 1),
         None,
     );
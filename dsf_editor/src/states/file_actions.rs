@@ -1,14 +1,91 @@
-use crate::resources::LevelEdit;
+use crate::resources::{Blueprint, EditHistory, EditorFile, LevelEdit, StampPalette};
 use amethyst::config::ConfigError;
 use amethyst::prelude::{Config, World, WorldExt};
 
-use dsf_core::levels::LevelSave;
-use dsf_core::utility::files::get_levels_dir;
+use dsf_core::levels::{validate_level, LevelFormat, LevelFormatError, LevelIssue, LevelSave};
+use dsf_core::utility::files::{get_levels_dir, get_stamps_dir};
+use std::fmt;
+use std::fs;
 use std::path::PathBuf;
 
-/// Returns a `PathBuf` to the file that is used to store auto saves.
+/// The name of the level file that is used to store auto saves. Regular levels cannot use it.
+pub const AUTO_SAVE_NAME: &str = "auto_save";
+
+/// The reasons a name cannot be used to save a level under.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum NameError {
+    Empty,
+    /// The name is reserved for the auto save.
+    Reserved,
+    /// Level names may only contain letters, digits, underscores and dashes, so they are valid file
+    /// names on every platform.
+    InvalidCharacter(char),
+}
+
+impl fmt::Display for NameError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            NameError::Empty => write!(f, "the name cannot be empty"),
+            NameError::Reserved => write!(f, "the name {:?} is reserved", AUTO_SAVE_NAME),
+            NameError::InvalidCharacter(character) => write!(
+                f,
+                "the name cannot contain {:?}, only letters, digits, '_' and '-' are allowed",
+                character
+            ),
+        }
+    }
+}
+
+/// The reasons a level could not be saved.
+#[derive(Debug)]
+pub enum SaveError {
+    /// The level cannot be saved under the given name.
+    InvalidName(NameError),
+    /// The level breaks the rules of the tile definitions. Contains every issue that was found.
+    Invalid(Vec<LevelIssue>),
+    /// The level could not be written to file.
+    Write(LevelFormatError),
+}
+
+impl fmt::Display for SaveError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SaveError::InvalidName(error) => write!(f, "{}", error),
+            SaveError::Invalid(issues) => {
+                write!(f, "the level has {} issue(s):", issues.len())?;
+                for issue in issues {
+                    write!(f, "\n- {}", issue)?;
+                }
+                Ok(())
+            }
+            SaveError::Write(error) => write!(f, "failed to write the level: {}", error),
+        }
+    }
+}
+
+impl From<LevelFormatError> for SaveError {
+    fn from(error: LevelFormatError) -> Self {
+        SaveError::Write(error)
+    }
+}
+
+/// Returns a `PathBuf` to the file that is used to store auto saves. It is always stored in RON.
 pub fn auto_save_file() -> PathBuf {
-    get_levels_dir().join("auto_save.ron")
+    level_file_in(AUTO_SAVE_NAME, LevelFormat::Ron)
+}
+
+/// Returns a `PathBuf` to the file of the level with the given name, in whichever `LevelFormat`
+/// it is stored. If there is no level with that name yet, it is a new RON file.
+fn level_file(name: &str) -> PathBuf {
+    LevelFormat::ALL
+        .iter()
+        .map(|format| level_file_in(name, *format))
+        .find(|file| file.exists())
+        .unwrap_or_else(|| level_file_in(name, LevelFormat::Ron))
+}
+
+fn level_file_in(name: &str, format: LevelFormat) -> PathBuf {
+    get_levels_dir().join(format!("{}.{}", name, format.extension()))
 }
 
 /// Load and return the auto save level.
@@ -28,31 +105,186 @@ pub fn load_auto_save() -> LevelSave {
     }
 }
 
-/// Load and return the level with the given name.
-#[allow(dead_code)] //Not used yet, but will be used in the future.
-pub fn load(name: String) -> Result<LevelSave, ConfigError> {
-    let level_file = get_levels_dir().join(name + ".ron");
-    LevelSave::load(level_file)
+/// Checks whether the level can be saved under the given name.
+pub fn check_level_name(name: &str) -> Result<(), NameError> {
+    if name.is_empty() {
+        Err(NameError::Empty)
+    } else if name == AUTO_SAVE_NAME {
+        Err(NameError::Reserved)
+    } else if let Some(character) = name.chars().find(|character| {
+        !character.is_ascii_alphanumeric() && *character != '_' && *character != '-'
+    }) {
+        Err(NameError::InvalidCharacter(character))
+    } else {
+        Ok(())
+    }
+}
+
+/// Returns true if there already is a level with the given name.
+pub fn level_exists(name: &str) -> bool {
+    level_file(name).exists()
+}
+
+/// Returns the names of all levels in the levels directory, in any `LevelFormat`, sorted
+/// alphabetically. The auto save is not included.
+pub fn list_levels() -> Vec<String> {
+    let entries = match fs::read_dir(get_levels_dir()) {
+        Ok(entries) => entries,
+        Err(error) => {
+            error!("Failed to read the levels directory: {}", error);
+            return vec![];
+        }
+    };
+    let mut names = entries
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| LevelFormat::is_level_file(path))
+        .filter_map(|path| Some(path.file_stem()?.to_string_lossy().to_string()))
+        .filter(|name| name != AUTO_SAVE_NAME)
+        .collect::<Vec<_>>();
+    names.sort();
+    // A level that is stored in more than one format is listed once, see `level_file`.
+    names.dedup();
+    names
+}
+
+/// Opens the level with the given name in the editor.
+///
+/// The level is copied to the auto save file, which is where the editor loads its level from when
+/// it resumes. The undo history is cleared, because it belongs to the previous level.
+pub fn open(name: &str, world: &mut World) -> Result<(), LevelFormatError> {
+    let level = LevelSave::load(level_file(name))?;
+    level.write(auto_save_file())?;
+    world.write_resource::<EditorFile>().name = Some(name.to_string());
+    world.insert(EditHistory::default());
+    Ok(())
 }
 
 /// Write the current state of the `LevelEdit` to the auto save file, overwriting what is already
 /// there.
+///
+/// The auto save is a work in progress, so it is always written, even if the level is invalid.
+/// Any issues with the level are logged.
 pub fn auto_save(world: &mut World) -> Result<(), ConfigError> {
-    write_level_file(auto_save_file(), world)
+    let level = current_level(world);
+    for issue in validate(world, &level) {
+        warn!("Auto-saved level is not valid yet: {}", issue);
+    }
+    level.write(auto_save_file())
 }
 
 /// Store the current state of the `LevelEdit` to file. The given name will be used as a filename.
-/// TODO: check if name is reserved (ie: `auto_save`)
-/// TODO: check if level already exists, if so maybe ask to overwrite?
-///     (or keep track of which one we loaded, so we know whether it's safe to overwrite)
-#[allow(dead_code)] //Not used yet, but will be used in the future.
-pub fn save(name: String, world: &mut World) -> Result<(), ConfigError> {
-    let level_file = get_levels_dir().join(name + ".ron");
-    write_level_file(level_file, world)
-}
-
-fn write_level_file(level_file: PathBuf, world: &mut World) -> Result<(), ConfigError> {
-    let level_edit = world.write_resource::<LevelEdit>();
-    let level: LevelSave = (*level_edit).clone().into();
-    level.write(level_file)
+/// Any existing level with that name is overwritten in the format it is stored in, so check with
+/// `level_exists` first.
+///
+/// Refuses to save the level if it is invalid. After saving, the level counts as the currently
+/// opened file.
+pub fn save(name: &str, world: &mut World) -> Result<(), SaveError> {
+    check_level_name(name).map_err(SaveError::InvalidName)?;
+    let level = current_level(world);
+    let issues = validate(world, &level);
+    if !issues.is_empty() {
+        return Err(SaveError::Invalid(issues));
+    }
+    level.save(level_file(name))?;
+    world.write_resource::<EditorFile>().name = Some(name.to_string());
+    world.write_resource::<EditHistory>().mark_saved();
+    info!("Saved level {:?}.", name);
+    Ok(())
+}
+
+/// Returns the file of the level that is open in the editor.
+/// None if the level was never saved under a name.
+pub fn opened_level_file(world: &World) -> Option<PathBuf> {
+    world
+        .read_resource::<EditorFile>()
+        .name
+        .as_deref()
+        .map(level_file)
+}
+
+/// Reloads the level that is open in the editor from its file, for when the file was changed
+/// outside of the editor. Like `open`, this clears the undo history.
+///
+/// Returns the reloaded level, or None if it was not reloaded: because it could not be loaded,
+/// because the file contains the level exactly as it is in the editor (for example right after
+/// saving it), or because reloading it would discard unsaved changes.
+pub fn reload_opened_level(world: &mut World) -> Option<LevelSave> {
+    let name = world.read_resource::<EditorFile>().name.clone()?;
+    let level = match LevelSave::load(level_file(&name)) {
+        Ok(level) => level,
+        Err(error) => {
+            error!("Failed to reload level {:?}: {}", name, error);
+            return None;
+        }
+    };
+    if level == current_level(world) {
+        return None;
+    }
+    if world.read_resource::<EditHistory>().has_unsaved_changes() {
+        warn!(
+            "Level {:?} was changed on disk, but it was not reloaded to keep the unsaved changes.",
+            name
+        );
+        return None;
+    }
+    if let Err(error) = open(&name, world) {
+        error!("Failed to reload level {:?}: {}", name, error);
+        return None;
+    }
+    Some(level)
+}
+
+/// Returns the level as it currently is in the editor.
+pub fn current_level(world: &mut World) -> LevelSave {
+    let level_edit = world.read_resource::<LevelEdit>();
+    (*level_edit).clone().into()
+}
+
+fn validate(world: &mut World, level: &LevelSave) -> Vec<LevelIssue> {
+    let level_edit = world.read_resource::<LevelEdit>();
+    validate_level(level, &level_edit.tile_map.tile_defs)
+}
+
+/// Load all stamps from the stamps directory, along with their names.
+/// Stamps that fail to load are skipped.
+pub fn load_stamps() -> Vec<(String, Blueprint)> {
+    let entries = match fs::read_dir(get_stamps_dir()) {
+        Ok(entries) => entries,
+        Err(error) => {
+            error!("Failed to read the stamps directory: {}", error);
+            return vec![];
+        }
+    };
+    entries
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| {
+            path.extension()
+                .map_or(false, |extension| extension == "ron")
+        })
+        .filter_map(|path| {
+            let name = path.file_stem()?.to_string_lossy().to_string();
+            match Blueprint::load(&path) {
+                Ok(blueprint) => Some((name, blueprint)),
+                Err(error) => {
+                    error!("Failed to load stamp {:?}: {}", path, error);
+                    None
+                }
+            }
+        })
+        .collect()
+}
+
+/// Store the blueprint in the stamps directory, under the given name.
+/// Overwrites any existing stamp with the same name.
+pub fn save_stamp(name: &str, blueprint: &Blueprint) -> Result<(), ConfigError> {
+    blueprint.write(get_stamps_dir().join(name.to_string() + ".ron"))
+}
+
+/// Returns a name that is not yet used by any of the stamps in the palette. It is suggested when
+/// the user saves a new stamp.
+pub fn new_stamp_name(stamps: &StampPalette) -> String {
+    (1..)
+        .map(|number| format!("stamp_{}", number))
+        .find(|name| !stamps.contains(name))
+        .expect("There is always a free name.")
 }
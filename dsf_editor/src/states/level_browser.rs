@@ -0,0 +1,160 @@
+use amethyst::{
+    core::ecs::Entity,
+    input::{is_close_requested, is_key_down, VirtualKeyCode},
+    prelude::WorldExt,
+    GameData, SimpleState, SimpleTrans, StateData, StateEvent, Trans,
+};
+
+use dsf_core::resources::{UiHandles, UiType};
+use dsf_core::states::window_event_handler;
+
+use crate::resources::{EditHistory, EditorFile};
+use crate::states::dialog::{
+    find_ui, is_confirm_released, set_text, MESSAGE_LABEL_ID, TITLE_LABEL_ID,
+};
+use crate::states::file_actions::{list_levels, open};
+
+/// How many level names fit in the dialog at once.
+const VISIBLE_LEVELS: usize = 15;
+
+/// Dialog that lists all levels, so the user can pick one to open in the editor.
+///
+/// Use the Up and Down keys to choose a level, Enter to open it and Escape to go back to the
+/// editor. If the current level has unsaved changes, the user must press Enter a second time to
+/// confirm that they want to discard them.
+#[derive(Debug, Default)]
+pub struct LevelBrowserState {
+    levels: Vec<String>,
+    /// Index into `levels`.
+    selected: usize,
+    /// Set when the user was warned that opening a level discards their unsaved changes.
+    discard_warning: bool,
+    /// Set when the last attempt to open a level failed.
+    error: Option<String>,
+    title: Option<Entity>,
+    message: Option<Entity>,
+}
+
+impl LevelBrowserState {
+    fn init_ui(&mut self, data: &mut StateData<'_, GameData<'_, '_>>) {
+        UiHandles::add_ui(UiType::EditorDialog, data.world);
+        // invoke a world update to finish creating our ui entities
+        data.data.update(data.world);
+        self.title = find_ui(data.world, TITLE_LABEL_ID);
+        self.message = find_ui(data.world, MESSAGE_LABEL_ID);
+        set_text(data.world, self.title, "Open level");
+        self.levels = list_levels();
+        let current_name = data.world.read_resource::<EditorFile>().name.clone();
+        self.selected = current_name
+            .and_then(|name| self.levels.iter().position(|level| *level == name))
+            .unwrap_or(0);
+        self.refresh(data);
+    }
+
+    fn refresh(&self, data: &mut StateData<'_, GameData<'_, '_>>) {
+        let mut text = String::from("Up/Down: choose, Enter: open, Escape: cancel.\n\n");
+        if self.levels.is_empty() {
+            text.push_str("There are no levels yet.");
+        } else {
+            // Scroll the list so the selected level is always visible.
+            let first = (self.selected + 1).saturating_sub(VISIBLE_LEVELS);
+            for (index, level) in self
+                .levels
+                .iter()
+                .enumerate()
+                .skip(first)
+                .take(VISIBLE_LEVELS)
+            {
+                let marker = if index == self.selected { ">" } else { " " };
+                text.push_str(&format!("{} {}\n", marker, level));
+            }
+        }
+        if let Some(error) = &self.error {
+            text.push_str(&format!("\n{}", error));
+        } else if self.discard_warning {
+            text.push_str(
+                "\nThe current level has unsaved changes. \
+                Press Enter again to discard them and open the level.",
+            );
+        }
+        set_text(data.world, self.message, &text);
+    }
+
+    fn select(&mut self, data: &mut StateData<'_, GameData<'_, '_>>, down: bool) {
+        if self.levels.is_empty() {
+            return;
+        }
+        self.selected = if down {
+            (self.selected + 1) % self.levels.len()
+        } else {
+            (self.selected + self.levels.len() - 1) % self.levels.len()
+        };
+        self.discard_warning = false;
+        self.error = None;
+        self.refresh(data);
+    }
+
+    fn open_selected(&mut self, data: &mut StateData<'_, GameData<'_, '_>>) -> SimpleTrans {
+        let name = match self.levels.get(self.selected) {
+            Some(name) => name.clone(),
+            None => return Trans::None,
+        };
+        let unsaved_changes = data
+            .world
+            .read_resource::<EditHistory>()
+            .has_unsaved_changes();
+        if unsaved_changes && !self.discard_warning {
+            self.discard_warning = true;
+            self.refresh(data);
+            return Trans::None;
+        }
+        match open(&name, data.world) {
+            Ok(()) => {
+                info!("Opened level {:?}.", name);
+                Trans::Pop
+            }
+            Err(error) => {
+                error!("Failed to open level {:?}: {}", name, error);
+                self.error = Some(format!("Cannot open {:?}: {}", name, error));
+                self.refresh(data);
+                Trans::None
+            }
+        }
+    }
+}
+
+impl SimpleState for LevelBrowserState {
+    fn on_start(&mut self, mut data: StateData<'_, GameData<'_, '_>>) {
+        info!("LevelBrowserState on_start");
+        self.init_ui(&mut data);
+    }
+
+    fn on_stop(&mut self, data: StateData<'_, GameData<'_, '_>>) {
+        info!("LevelBrowserState on_stop");
+        data.world.delete_all();
+    }
+
+    fn handle_event(
+        &mut self,
+        mut data: StateData<'_, GameData<'_, '_>>,
+        event: StateEvent,
+    ) -> SimpleTrans {
+        window_event_handler::handle(&event, data.world);
+        match event {
+            StateEvent::Window(event) => {
+                if is_close_requested(&event) || is_key_down(&event, VirtualKeyCode::Escape) {
+                    Trans::Pop
+                } else {
+                    if is_key_down(&event, VirtualKeyCode::Up) {
+                        self.select(&mut data, false);
+                    } else if is_key_down(&event, VirtualKeyCode::Down) {
+                        self.select(&mut data, true);
+                    }
+                    Trans::None
+                }
+            }
+            event if is_confirm_released(&event) => self.open_selected(&mut data),
+            _ => Trans::None,
+        }
+    }
+}
@@ -0,0 +1,176 @@
+use amethyst::{
+    core::ecs::{Entity, WriteStorage},
+    input::{is_close_requested, is_key_down, VirtualKeyCode},
+    prelude::WorldExt,
+    ui::Selected,
+    GameData, SimpleState, SimpleTrans, StateData, StateEvent, Trans,
+};
+
+use dsf_core::levels::{Difficulty, LevelMetadata};
+use dsf_core::resources::{UiHandles, UiType};
+use dsf_core::states::window_event_handler;
+
+use crate::resources::{EditHistory, LevelEdit};
+use crate::states::dialog::{find_ui, get_text, is_confirm_released, set_text, MESSAGE_LABEL_ID};
+use crate::states::file_actions::auto_save;
+
+const TITLE_INPUT_ID: &str = "title_input";
+const AUTHOR_INPUT_ID: &str = "author_input";
+const DESCRIPTION_INPUT_ID: &str = "description_input";
+const DIFFICULTY_INPUT_ID: &str = "difficulty_input";
+const PAR_MOVES_INPUT_ID: &str = "par_moves_input";
+const PAR_SECONDS_INPUT_ID: &str = "par_seconds_input";
+
+/// Dialog in which the user edits the metadata of the level: its title, author, hint, difficulty
+/// and par. These are shown on the level select screen.
+///
+/// Tab moves to the next field, Enter applies the changes and Escape discards them.
+/// The changes are recorded in the `EditHistory`, so they can be undone like any other edit.
+#[derive(Copy, Clone, Debug, Default)]
+pub struct LevelInfoState {
+    title: Option<Entity>,
+    author: Option<Entity>,
+    description: Option<Entity>,
+    difficulty: Option<Entity>,
+    par_moves: Option<Entity>,
+    par_seconds: Option<Entity>,
+    message: Option<Entity>,
+}
+
+impl LevelInfoState {
+    fn init_ui(&mut self, data: &mut StateData<'_, GameData<'_, '_>>) {
+        UiHandles::add_ui(UiType::LevelInfo, data.world);
+        // invoke a world update to finish creating our ui entities
+        data.data.update(data.world);
+        let world = &mut *data.world;
+        self.title = find_ui(world, TITLE_INPUT_ID);
+        self.author = find_ui(world, AUTHOR_INPUT_ID);
+        self.description = find_ui(world, DESCRIPTION_INPUT_ID);
+        self.difficulty = find_ui(world, DIFFICULTY_INPUT_ID);
+        self.par_moves = find_ui(world, PAR_MOVES_INPUT_ID);
+        self.par_seconds = find_ui(world, PAR_SECONDS_INPUT_ID);
+        self.message = find_ui(world, MESSAGE_LABEL_ID);
+
+        let metadata = world.read_resource::<LevelEdit>().metadata.clone();
+        let to_text = |value: Option<u32>| value.map(|value| value.to_string()).unwrap_or_default();
+        set_text(world, self.title, &metadata.title);
+        set_text(world, self.author, &metadata.author);
+        set_text(world, self.description, &metadata.description);
+        set_text(
+            world,
+            self.difficulty,
+            &metadata
+                .difficulty
+                .map(|difficulty| difficulty.to_string())
+                .unwrap_or_default(),
+        );
+        set_text(world, self.par_moves, &to_text(metadata.par_moves));
+        set_text(world, self.par_seconds, &to_text(metadata.par_seconds));
+        set_text(
+            world,
+            self.message,
+            "Tab: next field, Enter: apply, Escape: cancel.\n\
+            Difficulty is one of Easy, Medium, Hard or Expert. Leave a field empty to hide it.",
+        );
+        if let Some(title) = self.title {
+            world.exec(|mut selected: WriteStorage<'_, Selected>| {
+                selected
+                    .insert(title, Selected)
+                    .expect("Failed to select the title input.");
+            });
+        }
+    }
+
+    /// Reads the metadata from the input fields.
+    /// Returns a message for the user if any of the fields is invalid.
+    fn read_metadata(
+        &self,
+        data: &mut StateData<'_, GameData<'_, '_>>,
+    ) -> Result<LevelMetadata, String> {
+        let world = &mut *data.world;
+        let difficulty_text = get_text(world, self.difficulty);
+        let difficulty = if difficulty_text.trim().is_empty() {
+            None
+        } else {
+            Some(Difficulty::parse(&difficulty_text).ok_or_else(|| {
+                format!(
+                    "Unknown difficulty {:?}. Choose Easy, Medium, Hard or Expert.",
+                    difficulty_text
+                )
+            })?)
+        };
+        Ok(LevelMetadata {
+            title: get_text(world, self.title).trim().to_string(),
+            author: get_text(world, self.author).trim().to_string(),
+            description: get_text(world, self.description).trim().to_string(),
+            difficulty,
+            par_moves: parse_par(&get_text(world, self.par_moves), "Par moves")?,
+            par_seconds: parse_par(&get_text(world, self.par_seconds), "Par seconds")?,
+        })
+    }
+
+    fn apply(&mut self, data: &mut StateData<'_, GameData<'_, '_>>) -> SimpleTrans {
+        match self.read_metadata(data) {
+            Ok(metadata) => {
+                let edit = data
+                    .world
+                    .write_resource::<LevelEdit>()
+                    .set_metadata(metadata);
+                data.world.write_resource::<EditHistory>().record(edit);
+                // The editor reloads the level from the auto save when it resumes.
+                auto_save(data.world).expect("Failed to auto-save level!");
+                Trans::Pop
+            }
+            Err(message) => {
+                set_text(data.world, self.message, &message);
+                Trans::None
+            }
+        }
+    }
+}
+
+/// Parses the contents of one of the par fields. An empty field means there is no par.
+fn parse_par(text: &str, field_name: &str) -> Result<Option<u32>, String> {
+    let text = text.trim();
+    if text.is_empty() {
+        Ok(None)
+    } else {
+        text.parse().map(Some).map_err(|_| {
+            format!(
+                "{} must be a whole number, but it is {:?}.",
+                field_name, text
+            )
+        })
+    }
+}
+
+impl SimpleState for LevelInfoState {
+    fn on_start(&mut self, mut data: StateData<'_, GameData<'_, '_>>) {
+        info!("LevelInfoState on_start");
+        self.init_ui(&mut data);
+    }
+
+    fn on_stop(&mut self, data: StateData<'_, GameData<'_, '_>>) {
+        info!("LevelInfoState on_stop");
+        data.world.delete_all();
+    }
+
+    fn handle_event(
+        &mut self,
+        mut data: StateData<'_, GameData<'_, '_>>,
+        event: StateEvent,
+    ) -> SimpleTrans {
+        window_event_handler::handle(&event, data.world);
+        match event {
+            StateEvent::Window(event) => {
+                if is_close_requested(&event) || is_key_down(&event, VirtualKeyCode::Escape) {
+                    Trans::Pop
+                } else {
+                    Trans::None
+                }
+            }
+            event if is_confirm_released(&event) => self.apply(&mut data),
+            _ => Trans::None,
+        }
+    }
+}
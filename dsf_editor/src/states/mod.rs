@@ -1,4 +1,15 @@
+mod dialog;
 mod editor_state;
 mod file_actions;
+mod level_browser;
+mod level_info;
+mod save_as;
+mod tile_properties;
+mod unsaved_changes;
 
 pub use self::editor_state::EditorState;
+pub use self::level_browser::LevelBrowserState;
+pub use self::level_info::LevelInfoState;
+pub use self::save_as::SaveAsState;
+pub use self::tile_properties::TilePropertiesState;
+pub use self::unsaved_changes::UnsavedChangesState;
@@ -0,0 +1,224 @@
+use amethyst::{
+    core::ecs::{Entity, WriteStorage},
+    input::{is_close_requested, is_key_down, VirtualKeyCode},
+    prelude::WorldExt,
+    ui::Selected,
+    GameData, SimpleState, SimpleTrans, StateData, StateEvent, Trans,
+};
+
+use dsf_core::resources::{UiHandles, UiType};
+use dsf_core::states::window_event_handler;
+
+use crate::resources::{Blueprint, EditorFile, EditorStatus};
+use crate::states::dialog::{
+    find_ui, get_text, is_confirm_released, set_text, MESSAGE_LABEL_ID, TITLE_LABEL_ID,
+};
+use crate::states::file_actions::{
+    check_level_name, level_exists, new_stamp_name, save, save_stamp,
+};
+
+const LEVEL_NAME_ID: &str = "level_name";
+
+/// What the `SaveAsState` saves.
+#[derive(Debug)]
+enum SaveTarget {
+    /// The level that is being edited.
+    Level {
+        /// If true, the editor closes after the level was saved.
+        exit_afterwards: bool,
+    },
+    /// A blueprint, which is added to the stamp library.
+    Stamp(Blueprint),
+}
+
+impl SaveTarget {
+    fn noun(&self) -> &'static str {
+        match self {
+            SaveTarget::Level { .. } => "level",
+            SaveTarget::Stamp(_) => "stamp",
+        }
+    }
+}
+
+impl Default for SaveTarget {
+    fn default() -> Self {
+        SaveTarget::Level {
+            exit_afterwards: false,
+        }
+    }
+}
+
+/// Dialog in which the user types the name to save the level under, or the name of a new stamp.
+///
+/// Press Enter to save, or Escape to go back to the editor without saving.
+/// If a different level or stamp with that name already exists, the user must press Enter a second
+/// time to overwrite it.
+#[derive(Debug, Default)]
+pub struct SaveAsState {
+    target: SaveTarget,
+    /// The name of the existing level or stamp that the user was warned about. If they press Enter
+    /// again without changing the name, it is overwritten.
+    overwrite_warning: Option<String>,
+    level_name: Option<Entity>,
+    message: Option<Entity>,
+}
+
+impl SaveAsState {
+    #[must_use]
+    pub fn new(exit_afterwards: bool) -> Self {
+        SaveAsState {
+            target: SaveTarget::Level { exit_afterwards },
+            ..SaveAsState::default()
+        }
+    }
+
+    /// Creates the dialog to save the given blueprint as a stamp. The next free `stamp_N` name is
+    /// suggested.
+    #[must_use]
+    pub fn for_stamp(blueprint: Blueprint) -> Self {
+        SaveAsState {
+            target: SaveTarget::Stamp(blueprint),
+            ..SaveAsState::default()
+        }
+    }
+
+    fn init_ui(&mut self, data: &mut StateData<'_, GameData<'_, '_>>) {
+        UiHandles::add_ui(UiType::Save, data.world);
+        // invoke a world update to finish creating our ui entities
+        data.data.update(data.world);
+        self.level_name = find_ui(data.world, LEVEL_NAME_ID);
+        self.message = find_ui(data.world, MESSAGE_LABEL_ID);
+        let current_name = match self.target {
+            SaveTarget::Level { .. } => data
+                .world
+                .read_resource::<EditorFile>()
+                .name
+                .clone()
+                .unwrap_or_default(),
+            SaveTarget::Stamp(_) => {
+                new_stamp_name(&data.world.read_resource::<EditorStatus>().stamps)
+            }
+        };
+        if let SaveTarget::Stamp(_) = self.target {
+            let title = find_ui(data.world, TITLE_LABEL_ID);
+            set_text(data.world, title, "Save clipboard as stamp");
+        }
+        set_text(data.world, self.level_name, &current_name);
+        set_text(
+            data.world,
+            self.message,
+            "Press Enter to save, or Escape to cancel.",
+        );
+        if let Some(level_name) = self.level_name {
+            data.world.exec(|mut selected: WriteStorage<'_, Selected>| {
+                selected
+                    .insert(level_name, Selected)
+                    .expect("Failed to select the level name input.");
+            });
+        }
+    }
+
+    fn try_save(&mut self, data: &mut StateData<'_, GameData<'_, '_>>) -> SimpleTrans {
+        let name = get_text(data.world, self.level_name).trim().to_string();
+        let noun = self.target.noun();
+        if let Err(error) = check_level_name(&name) {
+            set_text(
+                data.world,
+                self.message,
+                &format!("Cannot save the {}: {}.", noun, error),
+            );
+            return Trans::None;
+        }
+        let exists = match self.target {
+            SaveTarget::Level { .. } => {
+                let is_open_file =
+                    data.world.read_resource::<EditorFile>().name.as_deref() == Some(name.as_str());
+                level_exists(&name) && !is_open_file
+            }
+            SaveTarget::Stamp(_) => data
+                .world
+                .read_resource::<EditorStatus>()
+                .stamps
+                .contains(&name),
+        };
+        let warned = self.overwrite_warning.as_deref() == Some(name.as_str());
+        if exists && !warned {
+            set_text(
+                data.world,
+                self.message,
+                &format!(
+                    "A {} named {:?} already exists. \
+                    Press Enter again to overwrite it, or choose a different name.",
+                    noun, name
+                ),
+            );
+            self.overwrite_warning = Some(name);
+            return Trans::None;
+        }
+        let saved = match &self.target {
+            SaveTarget::Level { .. } => save(&name, data.world).map_err(|error| error.to_string()),
+            SaveTarget::Stamp(blueprint) => {
+                save_stamp(&name, blueprint).map_err(|error| error.to_string())
+            }
+        };
+        match saved {
+            Ok(()) => {
+                match &self.target {
+                    SaveTarget::Level { exit_afterwards } => {
+                        if *exit_afterwards {
+                            data.world.write_resource::<EditorFile>().exit_requested = true;
+                        }
+                    }
+                    SaveTarget::Stamp(blueprint) => {
+                        info!("Saved the clipboard as stamp {:?}.", name);
+                        data.world
+                            .write_resource::<EditorStatus>()
+                            .stamps
+                            .add_stamp(name, blueprint.clone());
+                    }
+                }
+                Trans::Pop
+            }
+            Err(error) => {
+                error!("Failed to save {} {:?}: {}", noun, name, error);
+                set_text(
+                    data.world,
+                    self.message,
+                    &format!("Cannot save the {}: {}", noun, error),
+                );
+                Trans::None
+            }
+        }
+    }
+}
+
+impl SimpleState for SaveAsState {
+    fn on_start(&mut self, mut data: StateData<'_, GameData<'_, '_>>) {
+        info!("SaveAsState on_start");
+        self.init_ui(&mut data);
+    }
+
+    fn on_stop(&mut self, data: StateData<'_, GameData<'_, '_>>) {
+        info!("SaveAsState on_stop");
+        data.world.delete_all();
+    }
+
+    fn handle_event(
+        &mut self,
+        mut data: StateData<'_, GameData<'_, '_>>,
+        event: StateEvent,
+    ) -> SimpleTrans {
+        window_event_handler::handle(&event, data.world);
+        match event {
+            StateEvent::Window(event) => {
+                if is_close_requested(&event) || is_key_down(&event, VirtualKeyCode::Escape) {
+                    Trans::Pop
+                } else {
+                    Trans::None
+                }
+            }
+            event if is_confirm_released(&event) => self.try_save(&mut data),
+            _ => Trans::None,
+        }
+    }
+}
@@ -0,0 +1,154 @@
+use amethyst::{
+    core::ecs::{Entity, WriteStorage},
+    input::{is_close_requested, is_key_down, VirtualKeyCode},
+    prelude::WorldExt,
+    ui::Selected,
+    GameData, SimpleState, SimpleTrans, StateData, StateEvent, Trans,
+};
+
+use dsf_core::components::Pos;
+use dsf_core::resources::{Tile, TileProperties, UiHandles, UiType};
+use dsf_core::states::window_event_handler;
+
+use crate::resources::{EditHistory, LevelEdit};
+use crate::states::dialog::{
+    find_ui, get_text, is_confirm_released, set_text, MESSAGE_LABEL_ID, TITLE_LABEL_ID,
+};
+use crate::states::file_actions::auto_save;
+
+const PROPERTIES_INPUT_ID: &str = "properties";
+
+/// Dialog in which the user edits the instance properties of a single tile, written in RON.
+/// For example: `(depth: Player, archetype: Tool(BreakBlocksBelow(3)))`.
+///
+/// Enter applies the properties and Escape discards them. Clearing the text removes all
+/// properties. The changes are recorded in the `EditHistory`, so they can be undone.
+#[derive(Copy, Clone, Debug)]
+pub struct TilePropertiesState {
+    /// The anchor position of the tile whose properties are edited.
+    anchor: Pos,
+    input: Option<Entity>,
+    message: Option<Entity>,
+}
+
+impl TilePropertiesState {
+    #[must_use]
+    pub fn new(anchor: Pos) -> Self {
+        TilePropertiesState {
+            anchor,
+            input: None,
+            message: None,
+        }
+    }
+
+    fn init_ui(&mut self, data: &mut StateData<'_, GameData<'_, '_>>) {
+        UiHandles::add_ui(UiType::TileProperties, data.world);
+        // invoke a world update to finish creating our ui entities
+        data.data.update(data.world);
+        let world = &mut *data.world;
+        let title = find_ui(world, TITLE_LABEL_ID);
+        self.input = find_ui(world, PROPERTIES_INPUT_ID);
+        self.message = find_ui(world, MESSAGE_LABEL_ID);
+        let (key, properties) = {
+            let level_edit = world.read_resource::<LevelEdit>();
+            let key = match level_edit.tile_map.tiles.get(&self.anchor) {
+                Some(Tile::TileDefKey(key)) => key.clone(),
+                _ => String::new(),
+            };
+            let properties = level_edit
+                .tile_map
+                .properties()
+                .get(&self.anchor)
+                .cloned()
+                .unwrap_or_default();
+            (key, properties)
+        };
+        set_text(
+            world,
+            title,
+            &format!(
+                "Properties of {} at ({}, {})",
+                key, self.anchor.x, self.anchor.y
+            ),
+        );
+        let text = if properties.is_empty() {
+            String::new()
+        } else {
+            properties.to_ron()
+        };
+        set_text(world, self.input, &text);
+        set_text(
+            world,
+            self.message,
+            "Override the tile definition, for example: (depth: Player, climbable: true)\n\
+            Enter: apply, Escape: cancel. Leave empty to remove all properties.",
+        );
+        if let Some(input) = self.input {
+            world.exec(|mut selected: WriteStorage<'_, Selected>| {
+                selected
+                    .insert(input, Selected)
+                    .expect("Failed to select the properties input.");
+            });
+        }
+    }
+
+    fn apply(&mut self, data: &mut StateData<'_, GameData<'_, '_>>) -> SimpleTrans {
+        let text = get_text(data.world, self.input);
+        let properties = if text.trim().is_empty() {
+            Ok(TileProperties::default())
+        } else {
+            TileProperties::parse(&text)
+        };
+        match properties {
+            Ok(properties) => {
+                let edit = data
+                    .world
+                    .write_resource::<LevelEdit>()
+                    .set_properties(self.anchor, properties);
+                data.world.write_resource::<EditHistory>().record(edit);
+                // The editor reloads the level from the auto save when it resumes.
+                auto_save(data.world).expect("Failed to auto-save level!");
+                Trans::Pop
+            }
+            Err(error) => {
+                set_text(
+                    data.world,
+                    self.message,
+                    &format!("These are not valid tile properties: {}", error),
+                );
+                Trans::None
+            }
+        }
+    }
+}
+
+impl SimpleState for TilePropertiesState {
+    fn on_start(&mut self, mut data: StateData<'_, GameData<'_, '_>>) {
+        info!("TilePropertiesState on_start");
+        self.init_ui(&mut data);
+    }
+
+    fn on_stop(&mut self, data: StateData<'_, GameData<'_, '_>>) {
+        info!("TilePropertiesState on_stop");
+        data.world.delete_all();
+    }
+
+    fn handle_event(
+        &mut self,
+        mut data: StateData<'_, GameData<'_, '_>>,
+        event: StateEvent,
+    ) -> SimpleTrans {
+        window_event_handler::handle(&event, data.world);
+        match event {
+            StateEvent::Window(event) => {
+                if is_close_requested(&event) || is_key_down(&event, VirtualKeyCode::Escape) {
+                    Trans::Pop
+                } else {
+                    Trans::None
+                }
+            }
+            event if is_confirm_released(&event) => self.apply(&mut data),
+            _ => Trans::None,
+        }
+    }
+}
@@ -0,0 +1,117 @@
+use amethyst::{
+    core::ecs::Entity,
+    input::{is_close_requested, is_key_down, InputEvent, VirtualKeyCode},
+    prelude::WorldExt,
+    GameData, SimpleState, SimpleTrans, StateData, StateEvent, Trans,
+};
+
+use dsf_core::resources::{UiHandles, UiType};
+use dsf_core::states::window_event_handler;
+
+use crate::resources::EditorFile;
+use crate::states::dialog::{
+    find_ui, is_confirm_released, set_text, MESSAGE_LABEL_ID, TITLE_LABEL_ID,
+};
+use crate::states::file_actions::save;
+use crate::states::SaveAsState;
+
+/// Dialog that pops up when the user exits the editor while the level has unsaved changes.
+///
+/// Enter saves the level and exits, Backspace exits without saving and Escape goes back to the
+/// editor. If the level was never saved under a name, saving opens the `SaveAsState`.
+#[derive(Copy, Clone, Debug, Default)]
+pub struct UnsavedChangesState {
+    message: Option<Entity>,
+}
+
+impl UnsavedChangesState {
+    fn init_ui(&mut self, data: &mut StateData<'_, GameData<'_, '_>>) {
+        UiHandles::add_ui(UiType::EditorDialog, data.world);
+        // invoke a world update to finish creating our ui entities
+        data.data.update(data.world);
+        let title = find_ui(data.world, TITLE_LABEL_ID);
+        self.message = find_ui(data.world, MESSAGE_LABEL_ID);
+        set_text(data.world, title, "Unsaved changes");
+        let level = match &data.world.read_resource::<EditorFile>().name {
+            Some(name) => format!("level {:?}", name),
+            None => "new level".to_string(),
+        };
+        set_text(
+            data.world,
+            self.message,
+            &format!(
+                "The {} has unsaved changes.\n\n\
+                Enter: save and exit.\n\
+                Backspace: exit without saving. Your changes are kept in the auto save.\n\
+                Escape: keep editing.",
+                level
+            ),
+        );
+    }
+
+    fn save_and_exit(&mut self, data: &mut StateData<'_, GameData<'_, '_>>) -> SimpleTrans {
+        let name = data.world.read_resource::<EditorFile>().name.clone();
+        if let Some(name) = name {
+            match save(&name, data.world) {
+                Ok(()) => {
+                    data.world.write_resource::<EditorFile>().exit_requested = true;
+                    Trans::Pop
+                }
+                Err(error) => {
+                    error!("Failed to save level {:?}: {}", name, error);
+                    set_text(
+                        data.world,
+                        self.message,
+                        &format!(
+                            "Cannot save the level: {}\n\n\
+                            Backspace: exit without saving.\n\
+                            Escape: keep editing.",
+                            error
+                        ),
+                    );
+                    Trans::None
+                }
+            }
+        } else {
+            Trans::Switch(Box::new(SaveAsState::new(true)))
+        }
+    }
+}
+
+impl SimpleState for UnsavedChangesState {
+    fn on_start(&mut self, mut data: StateData<'_, GameData<'_, '_>>) {
+        info!("UnsavedChangesState on_start");
+        self.init_ui(&mut data);
+    }
+
+    fn on_stop(&mut self, data: StateData<'_, GameData<'_, '_>>) {
+        info!("UnsavedChangesState on_stop");
+        data.world.delete_all();
+    }
+
+    fn handle_event(
+        &mut self,
+        mut data: StateData<'_, GameData<'_, '_>>,
+        event: StateEvent,
+    ) -> SimpleTrans {
+        window_event_handler::handle(&event, data.world);
+        match event {
+            StateEvent::Window(event) => {
+                if is_close_requested(&event) || is_key_down(&event, VirtualKeyCode::Escape) {
+                    Trans::Pop
+                } else {
+                    Trans::None
+                }
+            }
+            event if is_confirm_released(&event) => self.save_and_exit(&mut data),
+            StateEvent::Input(InputEvent::KeyReleased {
+                key_code: VirtualKeyCode::Back,
+                scancode: _,
+            }) => {
+                data.world.write_resource::<EditorFile>().exit_requested = true;
+                Trans::Pop
+            }
+            _ => Trans::None,
+        }
+    }
+}
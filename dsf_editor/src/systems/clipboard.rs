@@ -0,0 +1,109 @@
+use amethyst::core::ecs::{Read, System, Write};
+use amethyst::input::{InputHandler, StringBindings};
+use amethyst::shrev::EventChannel;
+
+use dsf_core::resources::{SignalEdge, SignalEdgeDetector, Tile};
+
+use crate::resources::{Blueprint, Clipboard, Edit, EditHistory, EditorStatus, LevelEdit};
+use crate::systems::RefreshPreviewsEvent;
+
+/// Responsible for copying, cutting and pasting selections.
+///
+/// Copying and cutting store the selection on the `Clipboard` as a `Blueprint`.
+/// Pasting does not place anything by itself: it switches the editor to pasting mode, in which the
+/// clipboard is previewed at the cursor and `place_blocks` pastes it. Selecting a different brush
+/// switches back to placing tiles from the brush.
+///
+/// The blueprint on the clipboard can also be mirrored and rotated. Selecting a stamp from the stamp
+/// library puts that stamp on the clipboard and switches to pasting mode.
+#[derive(Copy, Clone, Debug)]
+pub struct ClipboardSystem;
+
+impl<'s> System<'s> for ClipboardSystem {
+    type SystemData = (
+        Write<'s, EventChannel<RefreshPreviewsEvent>>,
+        Write<'s, SignalEdgeDetector>,
+        Read<'s, InputHandler<StringBindings>>,
+        Write<'s, EditorStatus>,
+        Write<'s, Clipboard>,
+        Write<'s, LevelEdit>,
+        Write<'s, EditHistory>,
+    );
+
+    fn run(
+        &mut self,
+        (mut channel, mut sed, input, mut status, mut clipboard, mut level_edit, mut history): Self::SystemData,
+    ) {
+        if let SignalEdge::Rising = sed.edge("copy", &input) {
+            clipboard.blueprint = Some(Blueprint::from_selection(
+                &status.selection,
+                &level_edit,
+                status.copy_air,
+            ));
+        }
+        if let SignalEdge::Rising = sed.edge("cut", &input) {
+            let blueprint =
+                Blueprint::from_selection(&status.selection, &level_edit, status.copy_air);
+            // Only remove the tiles that were copied. Tiles that stick out of the selection stay.
+            let lower_bounds = status.selection.lower_bounds();
+            let mut edit = Edit::default();
+            blueprint
+                .tiles
+                .iter()
+                .filter(|(_, tile)| matches!(tile, Tile::TileDefKey(_)))
+                .for_each(|(relative_pos, _)| {
+                    edit.extend(level_edit.place_tile(true, lower_bounds + *relative_pos, None));
+                });
+            history.record(edit);
+            clipboard.blueprint = Some(blueprint);
+            channel.single_write(RefreshPreviewsEvent);
+        }
+        let transformations: [(&str, fn(&Blueprint) -> Option<Blueprint>); 4] = [
+            ("mirror_horizontally", Blueprint::mirror_horizontally),
+            ("mirror_vertically", Blueprint::mirror_vertically),
+            ("rotate_clockwise", Blueprint::rotate_clockwise),
+            (
+                "rotate_counterclockwise",
+                Blueprint::rotate_counterclockwise,
+            ),
+        ];
+        for (action, transformation) in &transformations {
+            if let SignalEdge::Rising = sed.edge(action, &input) {
+                if let Some(blueprint) = &clipboard.blueprint {
+                    if let Some(transformed) = transformation(blueprint) {
+                        clipboard.blueprint = Some(transformed);
+                        channel.single_write(RefreshPreviewsEvent);
+                    } else {
+                        info!(
+                            "Cannot {}, not all tiles would fit.",
+                            action.replace('_', " ")
+                        );
+                    }
+                }
+            }
+        }
+        let previous_stamp = if let SignalEdge::Rising = sed.edge("select_previous_stamp", &input) {
+            status.stamps.select_previous().cloned()
+        } else {
+            None
+        };
+        let next_stamp = if let SignalEdge::Rising = sed.edge("select_next_stamp", &input) {
+            status.stamps.select_next().cloned()
+        } else {
+            None
+        };
+        if let Some((_, blueprint)) = next_stamp.or(previous_stamp) {
+            clipboard.blueprint = Some(blueprint);
+            status.pasting = true;
+            channel.single_write(RefreshPreviewsEvent);
+        }
+        if let SignalEdge::Rising = sed.edge("paste", &input) {
+            if clipboard.blueprint.is_some() {
+                status.pasting = true;
+                channel.single_write(RefreshPreviewsEvent);
+            } else {
+                info!("Nothing to paste, the clipboard is empty.");
+            }
+        }
+    }
+}
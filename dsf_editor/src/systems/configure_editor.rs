@@ -30,10 +30,12 @@ impl<'s> System<'s> for ConfigureEditorSystem {
     fn run(&mut self, (mut channel, input, mut sed, mut status): Self::SystemData) {
         if let SignalEdge::Rising = sed.edge("select_previous_brush", &input) {
             let _new_key = status.brush.select_previous();
+            status.pasting = false;
             channel.single_write(RefreshPreviewsEvent);
         }
         if let SignalEdge::Rising = sed.edge("select_next_brush", &input) {
             let _new_key = status.brush.select_next();
+            status.pasting = false;
             channel.single_write(RefreshPreviewsEvent);
         }
         if let SignalEdge::Rising = sed.edge("toggle_copy_air", &input) {
@@ -65,7 +67,7 @@ impl<'s> System<'s> for EditorUiUpdateSystem {
         if let Some(toggle_copy_air) = toggle_copy_air {
             let sprite_nr = if status.copy_air { 0 } else { 1 };
             *toggle_copy_air = UiImage::Sprite(load_sprite_render(
-                SpriteType::EditorUiIcons,
+                SpriteType::EDITOR_UI_ICONS,
                 sprite_nr,
                 &assets,
             ));
@@ -74,7 +76,7 @@ impl<'s> System<'s> for EditorUiUpdateSystem {
         if let Some(toggle_force_place) = toggle_force_place {
             let sprite_nr = 2 + if status.force_place { 0 } else { 1 };
             *toggle_force_place = UiImage::Sprite(load_sprite_render(
-                SpriteType::EditorUiIcons,
+                SpriteType::EDITOR_UI_ICONS,
                 sprite_nr,
                 &assets,
             ));
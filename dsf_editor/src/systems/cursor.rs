@@ -11,7 +11,7 @@ use dsf_core::components::Direction2D;
 use dsf_core::resources::{SignalEdge, SignalEdgeDetector};
 
 use crate::components::Cursor;
-use crate::resources::{EditorConfig, EditorStatus, LevelEdit};
+use crate::resources::{EditHistory, EditorConfig, EditorStatus, LevelEdit};
 use crate::systems::RefreshPreviewsEvent;
 
 /// Responsible for moving the cursor across the screen and managing its blinking animation.
@@ -27,6 +27,7 @@ impl<'s> System<'s> for CursorSystem {
         Read<'s, Time>,
         Read<'s, EditorConfig>,
         Write<'s, LevelEdit>,
+        Write<'s, EditHistory>,
         Write<'s, EditorStatus>,
         Write<'s, SignalEdgeDetector>,
     );
@@ -41,6 +42,7 @@ impl<'s> System<'s> for CursorSystem {
             time,
             config,
             mut level_edit,
+            mut history,
             mut status,
             mut sed,
         ): Self::SystemData,
@@ -48,8 +50,15 @@ impl<'s> System<'s> for CursorSystem {
         for (cursor, transform) in (&mut cursors, &mut transforms).join() {
             let adjust_bounds = input.action_is_down("adjust_bounds").unwrap_or(false);
             let shift = input.action_is_down("shift").unwrap_or(false);
-            let input_x = input.axis_value("move_x").unwrap_or(0.0);
-            let input_y = input.axis_value("move_y").unwrap_or(0.0);
+            // While control is held, the user is typing a shortcut such as Ctrl+S to save.
+            let (input_x, input_y) = if input.action_is_down("control").unwrap_or(false) {
+                (0.0, 0.0)
+            } else {
+                (
+                    input.axis_value("move_x").unwrap_or(0.0),
+                    input.axis_value("move_y").unwrap_or(0.0),
+                )
+            };
             let new_direction = Direction2D::new(input_x, input_y);
             let should_move = if cursor.last_direction.is_neutral() && !new_direction.is_neutral() {
                 // Start movement now. Move once and set cooldown to High.
@@ -93,12 +102,12 @@ impl<'s> System<'s> for CursorSystem {
             }
             if should_move {
                 if adjust_bounds {
-                    level_edit
-                        .bounds_mut()
-                        .adjust_x(status.selection.end.x, input_x as i32);
-                    level_edit
-                        .bounds_mut()
-                        .adjust_y(status.selection.end.y, input_y as i32);
+                    let edit = level_edit.adjust_bounds(
+                        status.selection.end,
+                        input_x as i32,
+                        input_y as i32,
+                    );
+                    history.record(edit);
                 }
                 status.selection.end.x += input_x as i32;
                 status.selection.end.y += input_y as i32;
@@ -1,3 +1,4 @@
+mod clipboard;
 mod configure_editor;
 mod cursor;
 mod place_tiles;
@@ -5,8 +6,10 @@ mod preview_animation;
 mod refresh_previews;
 mod selection;
 mod tile_paint;
+mod undo_redo;
 mod update_background;
 
+pub use self::clipboard::*;
 pub use self::configure_editor::*;
 pub use self::cursor::*;
 pub use self::place_tiles::*;
@@ -14,4 +17,5 @@ pub use self::preview_animation::*;
 pub use self::refresh_previews::*;
 pub use self::selection::*;
 pub use self::tile_paint::*;
+pub use self::undo_redo::*;
 pub use self::update_background::*;
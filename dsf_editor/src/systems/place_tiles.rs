@@ -4,7 +4,7 @@ use amethyst::shrev::EventChannel;
 
 use dsf_core::resources::{SignalEdge, SignalEdgeDetector};
 
-use crate::resources::{Blueprint, EditorStatus, LevelEdit};
+use crate::resources::{Blueprint, Clipboard, Edit, EditHistory, EditorStatus, LevelEdit};
 use crate::systems::RefreshPreviewsEvent;
 
 /// Responsible for placing and removing tiles based on player input.
@@ -17,30 +17,39 @@ impl<'s> System<'s> for PlaceTilesSystem {
         Write<'s, SignalEdgeDetector>,
         Read<'s, InputHandler<StringBindings>>,
         Read<'s, EditorStatus>,
+        Read<'s, Clipboard>,
         Write<'s, LevelEdit>,
+        Write<'s, EditHistory>,
     );
 
-    fn run(&mut self, (mut channel, mut sed, input, status, mut level_edit): Self::SystemData) {
+    fn run(
+        &mut self,
+        (mut channel, mut sed, input, status, clipboard, mut level_edit, mut history): Self::SystemData,
+    ) {
         if let SignalEdge::Rising = sed.edge("place_blocks", &input) {
-            let blueprint = Blueprint::from_placing_tiles(&status, &level_edit);
-            let lower_bounds = status.selection.lower_bounds();
+            let (blueprint, lower_bounds) =
+                Blueprint::for_placement(&status, &level_edit, &clipboard);
+            let mut edit = Edit::default();
             blueprint.tiles.iter().for_each(|(relative_pos, tile)| {
-                level_edit.place_tile(
+                edit.extend(level_edit.place_tile(
                     status.force_place,
                     lower_bounds + *relative_pos,
                     Some(tile.clone()),
-                );
+                ));
             });
+            history.record(edit);
             channel.single_write(RefreshPreviewsEvent);
         }
         if let SignalEdge::Rising = sed.edge("delete_blocks", &input) {
             let lower_bounds = status.selection.lower_bounds();
             let selection_dimens = status.selection.dimens();
+            let mut edit = Edit::default();
             (0..selection_dimens.x).for_each(|x| {
                 (0..selection_dimens.y).for_each(|y| {
-                    level_edit.place_tile(true, lower_bounds.append_xy(x, y), None);
+                    edit.extend(level_edit.place_tile(true, lower_bounds.append_xy(x, y), None));
                 });
             });
+            history.record(edit);
             channel.single_write(RefreshPreviewsEvent);
         }
     }
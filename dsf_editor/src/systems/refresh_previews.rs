@@ -3,7 +3,7 @@ use amethyst::core::ecs::{
 };
 
 use crate::components::{PaintedTile, PreviewGhostTag};
-use crate::resources::{Blueprint, EditorStatus, LevelEdit, PlaceTileDryRun};
+use crate::resources::{Blueprint, Clipboard, EditorStatus, LevelEdit, PlaceTileDryRun};
 use amethyst::core::ecs::shrev::EventChannel;
 use amethyst::core::shred::SystemData;
 use amethyst::core::Transform;
@@ -29,6 +29,7 @@ impl<'s> System<'s> for RefreshPreviewsSystem {
     type SystemData = (
         Read<'s, EventChannel<RefreshPreviewsEvent>>,
         Read<'s, EditorStatus>,
+        Read<'s, Clipboard>,
         Read<'s, LevelEdit>,
         WriteStorage<'s, Tint>,
         ReadStorage<'s, PaintedTile>,
@@ -39,7 +40,17 @@ impl<'s> System<'s> for RefreshPreviewsSystem {
 
     fn run(
         &mut self,
-        (channel, status, level_edit, mut tints, painted_tiles, previews, lazy, entities): Self::SystemData,
+        (
+            channel,
+            status,
+            clipboard,
+            level_edit,
+            mut tints,
+            painted_tiles,
+            previews,
+            lazy,
+            entities,
+        ): Self::SystemData,
     ) {
         let reader_id = self.reader_id.as_mut().expect(
             "`RefreshPreviewsSystem::setup` was not called before `RefreshPreviewsSystem::run`",
@@ -51,8 +62,7 @@ impl<'s> System<'s> for RefreshPreviewsSystem {
         if !at_least_one_event {
             return;
         }
-        let blueprint = Blueprint::from_placing_tiles(&status, &level_edit);
-        let lower_bounds = status.selection.lower_bounds();
+        let (blueprint, lower_bounds) = Blueprint::for_placement(&status, &level_edit, &clipboard);
         let blueprint_dry_run =
             blueprint
                 .tiles
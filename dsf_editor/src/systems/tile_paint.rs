@@ -78,6 +78,7 @@ impl<'s> System<'s> for TilePaintSystem {
                         tile_def.depth,
                         tile_def.dimens,
                         asset,
+                        &assets,
                     ));
                 let mut builder = entities.build_entity();
                 if let Some(still_asset) = still_asset {
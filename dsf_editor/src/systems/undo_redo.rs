@@ -0,0 +1,42 @@
+use amethyst::core::ecs::{Read, System, Write};
+use amethyst::input::{InputHandler, StringBindings};
+use amethyst::shrev::EventChannel;
+
+use dsf_core::resources::{SignalEdge, SignalEdgeDetector};
+
+use crate::resources::{EditHistory, LevelEdit};
+use crate::systems::RefreshPreviewsEvent;
+
+/// Responsible for undoing and redoing edits to the level based on player input.
+#[derive(Copy, Clone, Debug)]
+pub struct UndoRedoSystem;
+
+impl<'s> System<'s> for UndoRedoSystem {
+    type SystemData = (
+        Write<'s, EventChannel<RefreshPreviewsEvent>>,
+        Write<'s, SignalEdgeDetector>,
+        Read<'s, InputHandler<StringBindings>>,
+        Write<'s, EditHistory>,
+        Write<'s, LevelEdit>,
+    );
+
+    fn run(
+        &mut self,
+        (mut channel, mut sed, input, mut history, mut level_edit): Self::SystemData,
+    ) {
+        if let SignalEdge::Rising = sed.edge("undo", &input) {
+            if history.undo(&mut level_edit) {
+                channel.single_write(RefreshPreviewsEvent);
+            } else {
+                info!("Nothing to undo.");
+            }
+        }
+        if let SignalEdge::Rising = sed.edge("redo", &input) {
+            if history.redo(&mut level_edit) {
+                channel.single_write(RefreshPreviewsEvent);
+            } else {
+                info!("Nothing to redo.");
+            }
+        }
+    }
+}
@@ -2,8 +2,8 @@ use amethyst::prelude::WorldExt;
 use amethyst::ui::UiCreator;
 use amethyst::ui::UiLoader;
 use dsf_core::resources::{
-    load_audio_settings, load_debug_settings, Assets, AudioSettings, MovementConfig, Music,
-    UiHandles, UserCache,
+    load_audio_settings, load_debug_settings, Assets, AudioSettings, FileWatcher, MovementConfig,
+    Music, UiHandles, UserCache,
 };
 
 use amethyst::{
@@ -17,8 +17,8 @@ use dsf_precompile::MyPrefabData;
 
 use crate::state_main_menu::MainMenuState;
 use amethyst::audio::{AudioSink, Mp3Format, WavFormat};
-use dsf_core::utility::files::{get_config_dir, get_user_cache_file};
-use dsf_core::utility::loading_config::LoadingConfig;
+use dsf_core::utility::files::{get_config_dir, get_movement_config_file, get_user_cache_file};
+use dsf_core::utility::loading_config::{AnimationConfig, LoadingConfig};
 use dsf_editor::resources::EditorConfig;
 
 /// This state is briefly active when the game is first started up. It loads all assets used in the
@@ -35,6 +35,7 @@ pub struct LoadingState {
 impl SimpleState for LoadingState {
     fn on_start(&mut self, data: StateData<'_, GameData<'_, '_>>) {
         load_configs(data.world);
+        data.world.insert(FileWatcher::default());
         self.load_ui = Some(data.world.exec(|mut creator: UiCreator<'_>| {
             creator.create("ui/loading.ron", &mut self.progress)
         }));
@@ -57,40 +58,41 @@ impl SimpleState for LoadingState {
         data.world.insert(ui_handles);
 
         // Load all sprite sheets for still images and add them to an Assets instance.
-        let assets = loading_config.stills.drain(..).fold(
-            Assets::default(),
-            |assets, (sprite_type, texture_path, spritesheet_path)| {
+        let assets = loading_config
+            .stills
+            .drain(..)
+            .fold(Assets::default(), |assets, still| {
                 let loader = data.world.read_resource::<Loader>();
                 let texture_handle = loader.load(
-                    texture_path,
+                    still.texture,
                     ImageFormat::default(),
                     &mut self.progress,
                     &data.world.read_resource::<AssetStorage<Texture>>(),
                 );
                 assets.put_still(
-                    sprite_type,
+                    still.id,
                     loader.load(
-                        spritesheet_path,
+                        still.sprite_sheet,
                         SpriteSheetFormat(texture_handle),
                         &mut self.progress,
                         &data.world.read_resource::<AssetStorage<SpriteSheet>>(),
                     ),
+                    still.dimens,
+                )
+            });
+        // Take the Assets instance we previously filled with still images and add animations.
+        let assets = loading_config.animations.drain(..).fold(
+            assets,
+            |assets, AnimationConfig { id, prefab, dimens }| {
+                assets.put_animated(
+                    id,
+                    data.world.exec(|loader: PrefabLoader<'_, MyPrefabData>| {
+                        loader.load(prefab, RonFormat, &mut self.progress)
+                    }),
+                    dimens,
                 )
             },
         );
-        // Take the Assets instance we previously filled with still images and add animations.
-        let assets =
-            loading_config
-                .animations
-                .drain(..)
-                .fold(assets, |assets, (anim_type, prefab_path)| {
-                    assets.put_animated(
-                        anim_type,
-                        data.world.exec(|loader: PrefabLoader<'_, MyPrefabData>| {
-                            loader.load(prefab_path, RonFormat, &mut self.progress)
-                        }),
-                    )
-                });
         // Take the Assets instance we previously filled with stills and animations and
         // add sound effects.
         let assets = loading_config.sound_effects.drain(..).fold(
@@ -173,7 +175,7 @@ fn load_configs(world: &mut World) {
     world.insert(load_audio_settings());
     let config_dir = get_config_dir();
     world.insert(
-        MovementConfig::load(&config_dir.join("movement.ron")).unwrap_or_else(|error| {
+        MovementConfig::load(&get_movement_config_file()).unwrap_or_else(|error| {
             error!(
                 "Failed to load movement config! Falling back to default. Error: {:?}",
                 error